@@ -2,8 +2,9 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use sp1_cli::{
     commands::{
-        build::BuildCmd, build_toolchain::BuildToolchainCmd,
-        install_toolchain::InstallToolchainCmd, new::NewCmd, prove::ProveCmd,
+        build::BuildCmd, build_toolchain::BuildToolchainCmd, execute::ExecuteCmd,
+        install_toolchain::InstallToolchainCmd, new::NewCmd, prove::ProveCmd, trace::TraceCmd,
+        verify::VerifyCmd, vkey::VkeyCmd, worker::WorkerCmd,
     },
     SP1_VERSION_MESSAGE,
 };
@@ -29,8 +30,13 @@ pub enum ProveCliCommands {
     New(NewCmd),
     Build(BuildCmd),
     Prove(ProveCmd),
+    Execute(ExecuteCmd),
+    Trace(TraceCmd),
+    Verify(VerifyCmd),
+    Vkey(VkeyCmd),
     BuildToolchain(BuildToolchainCmd),
     InstallToolchain(InstallToolchainCmd),
+    Worker(WorkerCmd),
 }
 
 fn main() -> Result<()> {
@@ -40,7 +46,12 @@ fn main() -> Result<()> {
         ProveCliCommands::New(cmd) => cmd.run(),
         ProveCliCommands::Build(cmd) => cmd.run(),
         ProveCliCommands::Prove(cmd) => cmd.run(),
+        ProveCliCommands::Execute(cmd) => cmd.run(),
+        ProveCliCommands::Trace(cmd) => cmd.run(),
+        ProveCliCommands::Verify(cmd) => cmd.run(),
+        ProveCliCommands::Vkey(cmd) => cmd.run(),
         ProveCliCommands::BuildToolchain(cmd) => cmd.run(),
         ProveCliCommands::InstallToolchain(cmd) => cmd.run(),
+        ProveCliCommands::Worker(cmd) => cmd.run(),
     }
 }
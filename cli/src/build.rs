@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
-use cargo_metadata::camino::Utf8PathBuf;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
 use clap::Parser;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::{
     fs,
     io::{BufRead, BufReader},
@@ -8,29 +10,261 @@ use std::{
     thread,
 };
 
+/// A record of how an ELF was built, written next to it by [build_programs].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildManifest {
+    /// The version of the succinct toolchain, as printed by `rustc --version`.
+    pub toolchain: String,
+    /// The docker image the program was built in, if it was built with `--docker`.
+    pub docker_image: Option<String>,
+    /// The digest of the docker image, which can be used in place of the image to rebuild it.
+    pub docker_image_digest: Option<String>,
+    /// The SHA-256 of the Cargo.lock of the program's workspace, if there is one.
+    pub cargo_lock_sha256: Option<String>,
+    pub target: String,
+    /// The package and binary the ELF was built from.
+    pub package: String,
+    pub binary: String,
+    /// The features and the cargo profile the ELF was built with.
+    pub features: Vec<String>,
+    pub no_default_features: bool,
+    pub profile: String,
+    /// The SHA-256 of the ELF.
+    pub elf_sha256: String,
+}
+
+impl BuildManifest {
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("failed to read build manifest {}", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("failed to parse build manifest {}", path.display()))
+    }
+
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+}
+
+/// The path of the manifest of an ELF.
+pub fn manifest_path(elf_path: &Utf8Path) -> Utf8PathBuf {
+    let mut path = elf_path.to_owned().into_string();
+    path.push_str(".manifest.json");
+    Utf8PathBuf::from(path)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Returns the version of the succinct toolchain.
+fn toolchain_version() -> Result<String> {
+    let output = Command::new("rustc")
+        .env("RUSTUP_TOOLCHAIN", "succinct")
+        .arg("--version")
+        .output()
+        .context("failed to run rustc")?;
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Returns the digest of a docker image, or its id if it has no digest because it was built
+/// locally.
+fn docker_image_digest(image: &str) -> Result<String> {
+    for format in ["{{index .RepoDigests 0}}", "{{.Id}}"] {
+        let output = Command::new("docker")
+            .args(["image", "inspect", "--format", format, image])
+            .stderr(Stdio::null())
+            .output()
+            .context("failed to run docker command")?;
+        if output.status.success() {
+            return Ok(String::from_utf8(output.stdout)?.trim().to_string());
+        }
+    }
+    anyhow::bail!("failed to inspect docker image {}", image)
+}
+
 fn get_docker_image() -> String {
     // Get the docker image name from the environment variable
     std::env::var("SP1_DOCKER_IMAGE")
         .unwrap_or_else(|_| "ghcr.io/succinctlabs/sp1:latest".to_string())
 }
 
-#[derive(Parser)]
+/// The name of the ELF written when the root package is built without selecting programs.
+const DEFAULT_ELF_NAME: &str = "riscv32im-succinct-zkvm-elf";
+
+#[derive(Clone, Parser)]
 pub(crate) struct BuildArgs {
     #[clap(long, action, help = "Ignore Rust version check.")]
     pub(crate) docker: bool,
     #[clap(long, action, help = "Ignore Rust version check.")]
     pub(crate) ignore_rust_version: bool,
+    #[clap(
+        long = "bin",
+        help = "Build only the given binaries, writing each ELF to `elf/<name>`."
+    )]
+    pub(crate) binaries: Vec<String>,
+    #[clap(
+        long = "package",
+        help = "Build the binaries of the given packages instead of the root package."
+    )]
+    pub(crate) packages: Vec<String>,
+    #[clap(
+        long,
+        value_delimiter = ',',
+        help = "Features to enable, as `feature` or `package/feature`."
+    )]
+    pub(crate) features: Vec<String>,
+    #[clap(long, action, help = "Do not enable the default features.")]
+    pub(crate) no_default_features: bool,
+    #[clap(
+        long,
+        default_value = "release",
+        help = "The cargo profile to build with."
+    )]
+    pub(crate) build_profile: String,
+    #[clap(
+        long,
+        help = "The name of the ELF to write, when a single program is built."
+    )]
+    pub(crate) elf_name: Option<String>,
+}
+
+impl BuildArgs {
+    /// The arguments to pass to `cargo prove build` to build the same programs, except `--docker`.
+    fn prove_build_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.ignore_rust_version {
+            args.push("--ignore-rust-version".to_string());
+        }
+        for binary in self.binaries.iter() {
+            args.extend(["--bin".to_string(), binary.clone()]);
+        }
+        for package in self.packages.iter() {
+            args.extend(["--package".to_string(), package.clone()]);
+        }
+        if !self.features.is_empty() {
+            args.extend(["--features".to_string(), self.features.join(",")]);
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".to_string());
+        }
+        args.extend(["--build-profile".to_string(), self.build_profile.clone()]);
+        if let Some(ref elf_name) = self.elf_name {
+            args.extend(["--elf-name".to_string(), elf_name.clone()]);
+        }
+        args
+    }
+
+    /// The name of the directory cargo writes the binaries of the build profile to.
+    fn profile_dir(&self) -> &str {
+        match self.build_profile.as_str() {
+            "dev" | "test" => "debug",
+            "bench" => "release",
+            profile => profile,
+        }
+    }
+}
+
+/// A binary built by [build_programs], and the name of the ELF it is written to.
+struct Program {
+    package: String,
+    binary: String,
+    elf_name: String,
 }
 
+/// Returns the binaries selected by `args` in the workspace.
+fn select_programs(metadata: &cargo_metadata::Metadata, args: &BuildArgs) -> Result<Vec<Program>> {
+    let members = metadata.workspace_packages();
+    let selected = if !args.packages.is_empty() {
+        args.packages
+            .iter()
+            .map(|name| {
+                members
+                    .iter()
+                    .find(|package| &package.name == name)
+                    .copied()
+                    .with_context(|| format!("package `{}` not found in the workspace", name))
+            })
+            .collect::<Result<Vec<_>>>()?
+    } else if !args.binaries.is_empty() {
+        members
+    } else {
+        vec![metadata.root_package().context(
+            "the workspace has no root package, select the programs to build with --package or --bin",
+        )?]
+    };
+
+    let mut programs = Vec::new();
+    for package in selected {
+        for target in package.targets.iter() {
+            if target.kind.iter().any(|kind| kind == "bin")
+                && (args.binaries.is_empty() || args.binaries.contains(&target.name))
+            {
+                programs.push(Program {
+                    package: package.name.clone(),
+                    binary: target.name.clone(),
+                    elf_name: target.name.clone(),
+                });
+            }
+        }
+    }
+    for binary in args.binaries.iter() {
+        if !programs.iter().any(|program| &program.binary == binary) {
+            anyhow::bail!("binary `{}` not found in the selected packages", binary);
+        }
+    }
+    if programs.is_empty() {
+        anyhow::bail!("no binaries to build");
+    }
+
+    if let Some(ref elf_name) = args.elf_name {
+        if programs.len() > 1 {
+            anyhow::bail!("--elf-name requires a single program to be built");
+        }
+        programs[0].elf_name = elf_name.clone();
+    } else if args.binaries.is_empty() && args.packages.is_empty() && programs.len() == 1 {
+        programs[0].elf_name = DEFAULT_ELF_NAME.to_string();
+    }
+    Ok(programs)
+}
+
+/// Builds the single program selected by `args`, returning the path of its ELF.
 pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
+    let mut elf_paths = build_programs(args)?;
+    if elf_paths.len() > 1 {
+        anyhow::bail!(
+            "built {} programs, select the one to use with --bin",
+            elf_paths.len()
+        );
+    }
+    Ok(elf_paths.remove(0))
+}
+
+/// Builds the programs selected by `args`, returning the paths of their ELFs.
+pub fn build_programs(args: &BuildArgs) -> Result<Vec<Utf8PathBuf>> {
+    build_programs_with_image(args, &get_docker_image())
+}
+
+/// Builds the programs, using the given docker image if `args.docker` is set, and writes the
+/// [BuildManifest] of each ELF next to it.
+pub fn build_programs_with_image(args: &BuildArgs, image: &str) -> Result<Vec<Utf8PathBuf>> {
     let metadata_cmd = cargo_metadata::MetadataCommand::new();
     let metadata = metadata_cmd.exec().unwrap();
-    let root_package = metadata.root_package();
-    let root_package_name = root_package.as_ref().map(|p| &p.name);
+    let programs = select_programs(&metadata, args)?;
 
     let build_target = "riscv32im-succinct-zkvm-elf";
+    let elf_dir = metadata.target_directory.parent().unwrap().join("elf");
     if args.docker {
-        let image = get_docker_image();
+        // The build inside the container writes the manifests read below, so stale ones must not
+        // be mistaken for them.
+        for program in programs.iter() {
+            let manifest_path = manifest_path(&elf_dir.join(&program.elf_name));
+            if manifest_path.exists() {
+                fs::remove_file(&manifest_path)?;
+            }
+        }
 
         let docker_check = Command::new("docker")
             .args(["info"])
@@ -45,18 +279,17 @@ pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
         }
 
         let workspace_root_path = format!("{}:/root/program", metadata.workspace_root);
+        let prove_build_args = args.prove_build_args();
         let mut child_args = vec![
             "run",
             "--rm",
             "-v",
             workspace_root_path.as_str(),
-            image.as_str(),
+            image,
             "prove",
             "build",
         ];
-        if args.ignore_rust_version {
-            child_args.push("--ignore-rust-version");
-        }
+        child_args.extend(prove_build_args.iter().map(String::as_str));
 
         let mut child = Command::new("docker")
             .args(&child_args)
@@ -95,10 +328,28 @@ pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
             "panic=abort",
         ];
 
-        let mut cargo_args = vec!["build", "--release", "--target", build_target, "--locked"];
+        let mut cargo_args = vec![
+            "build",
+            "--profile",
+            args.build_profile.as_str(),
+            "--target",
+            build_target,
+            "--locked",
+        ];
         if args.ignore_rust_version {
             cargo_args.push("--ignore-rust-version");
         }
+        for program in programs.iter() {
+            cargo_args.extend(["--package", program.package.as_str()]);
+            cargo_args.extend(["--bin", program.binary.as_str()]);
+        }
+        let features = args.features.join(",");
+        if !features.is_empty() {
+            cargo_args.extend(["--features", features.as_str()]);
+        }
+        if args.no_default_features {
+            cargo_args.push("--no-default-features");
+        }
 
         let result = Command::new("cargo")
             .env("RUSTUP_TOOLCHAIN", "succinct")
@@ -113,15 +364,61 @@ pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
         }
     }
 
-    let elf_path = metadata
-        .target_directory
-        .join(build_target)
-        .join("release")
-        .join(root_package_name.unwrap());
-    let elf_dir = metadata.target_directory.parent().unwrap().join("elf");
     fs::create_dir_all(&elf_dir)?;
-    let result_elf_path = elf_dir.join("riscv32im-succinct-zkvm-elf");
-    fs::copy(elf_path, &result_elf_path)?;
+    let toolchain = if args.docker {
+        None
+    } else {
+        Some(toolchain_version()?)
+    };
+    let docker_image_digest = if args.docker {
+        Some(docker_image_digest(image)?)
+    } else {
+        None
+    };
+    let cargo_lock_path = metadata.workspace_root.join("Cargo.lock");
+    let cargo_lock_sha256 = if cargo_lock_path.exists() {
+        Some(sha256_hex(&fs::read(&cargo_lock_path)?))
+    } else {
+        None
+    };
+
+    let mut elf_paths = Vec::with_capacity(programs.len());
+    for program in programs.iter() {
+        let elf_path = metadata
+            .target_directory
+            .join(build_target)
+            .join(args.profile_dir())
+            .join(&program.binary);
+        let result_elf_path = elf_dir.join(&program.elf_name);
+        fs::copy(elf_path, &result_elf_path)?;
+
+        // With docker, the toolchain is the one of the image, recorded by the build inside it in
+        // the manifest of the mounted workspace.
+        let manifest_path = manifest_path(&result_elf_path);
+        let toolchain = match toolchain {
+            Some(ref toolchain) => toolchain.clone(),
+            None => {
+                BuildManifest::load(&manifest_path)
+                    .context("the docker image did not write a build manifest, it may be outdated")?
+                    .toolchain
+            }
+        };
+        let manifest = BuildManifest {
+            toolchain,
+            docker_image: args.docker.then(|| image.to_string()),
+            docker_image_digest: docker_image_digest.clone(),
+            cargo_lock_sha256: cargo_lock_sha256.clone(),
+            target: build_target.to_string(),
+            package: program.package.clone(),
+            binary: program.binary.clone(),
+            features: args.features.clone(),
+            no_default_features: args.no_default_features,
+            profile: args.build_profile.clone(),
+            elf_sha256: sha256_hex(&fs::read(&result_elf_path)?),
+        };
+        manifest.save(&manifest_path)?;
+        elf_paths.push(result_elf_path);
+    }
 
-    Ok(result_elf_path)
+    Ok(elf_paths)
 }
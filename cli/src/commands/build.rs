@@ -1,6 +1,14 @@
-use crate::build::{build_program, BuildArgs};
-use anyhow::Result;
+use crate::{
+    build::{build_programs, build_programs_with_image, manifest_path, BuildArgs, BuildManifest},
+    util::write_status,
+};
+use anstyle::*;
+use anyhow::{bail, Context, Result};
 use clap::Parser;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 #[derive(Parser)]
 #[command(name = "build", about = "Build a program")]
@@ -8,14 +16,94 @@ pub struct BuildCmd {
     #[clap(long, action)]
     verbose: bool,
 
+    /// Rebuilds the program in the environment recorded in a build manifest and checks that it
+    /// produces the same ELF.
+    #[clap(long)]
+    verify: Option<PathBuf>,
+
     #[clap(flatten)]
     build_args: BuildArgs,
 }
 
 impl BuildCmd {
     pub fn run(&self) -> Result<()> {
-        build_program(&self.build_args)?;
+        match self.verify {
+            Some(ref path) => self.verify(path),
+            None => {
+                build_programs(&self.build_args)?;
+                Ok(())
+            }
+        }
+    }
+
+    fn verify(&self, path: &Path) -> Result<()> {
+        let expected = BuildManifest::load(path)?;
+
+        // Rebuild the same program with the same options, and with the exact image it was built
+        // with if it was built with docker. The program is rebuilt into a temporary ELF, so that
+        // the ELF being verified and its manifest are left untouched.
+        let mut args = self.build_args.clone();
+        args.binaries = vec![expected.binary.clone()];
+        args.packages = vec![expected.package.clone()];
+        args.features = expected.features.clone();
+        args.no_default_features = expected.no_default_features;
+        args.build_profile = expected.profile.clone();
+        args.elf_name = Some(format!("{}.verify-{}", expected.binary, std::process::id()));
+        let elf_paths = match expected
+            .docker_image_digest
+            .as_ref()
+            .or(expected.docker_image.as_ref())
+        {
+            Some(image) => {
+                args.docker = true;
+                build_programs_with_image(&args, image)?
+            }
+            None => build_programs(&args)?,
+        };
+        let elf_path = &elf_paths[0];
+        let found = BuildManifest::load(manifest_path(elf_path));
+        for path in [elf_path.clone(), manifest_path(elf_path)] {
+            if path.exists() {
+                fs::remove_file(&path).with_context(|| format!("failed to remove {}", path))?;
+            }
+        }
+        let found = found?;
+
+        let fields = [
+            (
+                "toolchain",
+                format!("{:?}", expected.toolchain),
+                format!("{:?}", found.toolchain),
+            ),
+            (
+                "Cargo.lock",
+                format!("{:?}", expected.cargo_lock_sha256),
+                format!("{:?}", found.cargo_lock_sha256),
+            ),
+            ("target", expected.target.clone(), found.target.clone()),
+            ("ELF", expected.elf_sha256.clone(), found.elf_sha256.clone()),
+        ];
+        for (name, expected, found) in fields.iter().filter(|(_, e, f)| e != f) {
+            eprintln!(
+                "{} differs:\n  expected: {}\n  found:    {}",
+                name, expected, found
+            );
+        }
+
+        if expected.elf_sha256 != found.elf_sha256 {
+            bail!(
+                "the rebuilt ELF {} does not match {}",
+                found.elf_sha256,
+                path.display()
+            );
+        }
 
+        let green = AnsiColor::Green.on_default().effects(Effects::BOLD);
+        write_status(
+            &green,
+            "Verified",
+            format!("ELF {} matches {}", found.elf_sha256, path.display()).as_str(),
+        );
         Ok(())
     }
 }
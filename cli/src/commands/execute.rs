@@ -0,0 +1,81 @@
+use anstyle::*;
+use anyhow::{Context, Result};
+use clap::Parser;
+use sp1_core::{
+    runtime::{Program, Runtime},
+    utils::{setup_logger, SP1CoreOpts},
+};
+use sp1_prover::SP1PublicValues;
+use std::time::Instant;
+use std::{env, fs, path::PathBuf};
+
+use crate::{
+    build::{build_program, BuildArgs},
+    commands::prove::{read_stdin, Input},
+    util::{elapsed, write_status},
+};
+
+#[derive(Parser)]
+#[command(
+    name = "execute",
+    about = "Execute a program without proving it and print its public values"
+)]
+pub struct ExecuteCmd {
+    /// The ELF to execute. Defaults to building the program in the current directory.
+    #[clap(long)]
+    elf: Option<PathBuf>,
+
+    #[clap(long, value_parser)]
+    input: Option<Input>,
+
+    #[clap(flatten)]
+    build_args: BuildArgs,
+}
+
+impl ExecuteCmd {
+    pub fn run(&self) -> Result<()> {
+        let elf = read_elf(self.elf.as_ref(), &self.build_args)?;
+
+        match env::var("RUST_LOG") {
+            Ok(_) => {}
+            Err(_) => env::set_var("RUST_LOG", "info"),
+        }
+        setup_logger();
+
+        let stdin = read_stdin(self.input.as_ref())?;
+        let start_time = Instant::now();
+        let mut runtime = Runtime::new(Program::from(&elf), SP1CoreOpts::default());
+        runtime.write_vecs(&stdin.buffer);
+        for (proof, vkey) in stdin.proofs.iter() {
+            runtime.write_proof(proof.clone(), vkey.clone());
+        }
+        runtime
+            .run_untraced()
+            .context("failed to execute program")?;
+        let public_values = SP1PublicValues::from(&runtime.state.public_values_stream);
+
+        let green = AnsiColor::Green.on_default().effects(Effects::BOLD);
+        write_status(
+            &green,
+            "Finished",
+            format!(
+                "executing {} cycles in {}",
+                runtime.state.global_clk,
+                elapsed(start_time.elapsed())
+            )
+            .as_str(),
+        );
+        println!("public values: {}", public_values.bytes());
+
+        Ok(())
+    }
+}
+
+/// Reads the ELF at the given path, or builds the program in the current directory.
+pub(crate) fn read_elf(path: Option<&PathBuf>, build_args: &BuildArgs) -> Result<Vec<u8>> {
+    let path = match path {
+        Some(path) => path.clone(),
+        None => build_program(build_args)?.into_std_path_buf(),
+    };
+    fs::read(&path).with_context(|| format!("failed to read ELF {}", path.display()))
+}
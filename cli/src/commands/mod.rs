@@ -1,5 +1,10 @@
 pub mod build;
 pub mod build_toolchain;
+pub mod execute;
 pub mod install_toolchain;
 pub mod new;
 pub mod prove;
+pub mod trace;
+pub mod verify;
+pub mod vkey;
+pub mod worker;
@@ -13,7 +13,7 @@ use crate::{
 };
 
 #[derive(Debug, Clone)]
-enum Input {
+pub(crate) enum Input {
     FilePath(PathBuf),
     HexBytes(Vec<u8>),
 }
@@ -97,29 +97,16 @@ impl ProveCmd {
             .read_to_end(&mut elf)
             .expect("failed to read from input file");
 
-        let mut stdin = SP1Stdin::new();
-        if let Some(ref input) = self.input {
-            match input {
-                Input::FilePath(ref path) => {
-                    let mut file = File::open(path).expect("failed to open input file");
-                    let mut bytes = Vec::new();
-                    file.read_to_end(&mut bytes)?;
-                    stdin.write_slice(&bytes);
-                }
-                Input::HexBytes(ref bytes) => {
-                    stdin.write_slice(bytes);
-                }
-            }
-        }
+        let stdin = read_stdin(self.input.as_ref())?;
 
         let start_time = Instant::now();
         let client = ProverClient::new();
-        let (pk, _) = client.setup(&elf);
+        let (pk, vk) = client.setup(&elf);
         let proof = client.prove(&pk, stdin).unwrap();
 
         if let Some(ref path) = self.output {
             proof
-                .save(path.to_str().unwrap())
+                .save(path.to_str().unwrap(), &vk)
                 .expect("failed to save proof");
         }
 
@@ -134,3 +121,22 @@ impl ProveCmd {
         Ok(())
     }
 }
+
+/// Writes the given input, if any, to a new [SP1Stdin].
+pub(crate) fn read_stdin(input: Option<&Input>) -> Result<SP1Stdin> {
+    let mut stdin = SP1Stdin::new();
+    if let Some(input) = input {
+        match input {
+            Input::FilePath(ref path) => {
+                let mut file = File::open(path).expect("failed to open input file");
+                let mut bytes = Vec::new();
+                file.read_to_end(&mut bytes)?;
+                stdin.write_slice(&bytes);
+            }
+            Input::HexBytes(ref bytes) => {
+                stdin.write_slice(bytes);
+            }
+        }
+    }
+    Ok(stdin)
+}
@@ -0,0 +1,82 @@
+use anstyle::*;
+use anyhow::{Context, Result};
+use clap::Parser;
+use sp1_core::{
+    runtime::Program,
+    utils::{dump_traces, setup_logger, BabyBearPoseidon2, SP1CoreOpts},
+};
+use std::{env, path::PathBuf};
+
+use crate::{
+    build::BuildArgs,
+    commands::{
+        execute::read_elf,
+        prove::{read_stdin, Input},
+    },
+    util::write_status,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "trace",
+    about = "Execute a program and save the traces of its chips as CSV files",
+    long_about = "Execute a program and save the traces of its chips as CSV files. CSV is the only \
+                  supported output format."
+)]
+pub struct TraceCmd {
+    /// The ELF to execute. Defaults to building the program in the current directory.
+    #[clap(long)]
+    elf: Option<PathBuf>,
+
+    #[clap(long, value_parser)]
+    input: Option<Input>,
+
+    /// The chips to save, for example `CPU,MemoryInit`. Defaults to every chip.
+    #[clap(long, value_delimiter = ',')]
+    chips: Vec<String>,
+
+    /// The directory to save the traces to, as `<output-dir>/shard_<i>/<chip>.csv`. Traces are
+    /// only saved as CSV.
+    #[clap(long, default_value = "traces")]
+    output_dir: PathBuf,
+
+    #[clap(flatten)]
+    build_args: BuildArgs,
+}
+
+impl TraceCmd {
+    pub fn run(&self) -> Result<()> {
+        let elf = read_elf(self.elf.as_ref(), &self.build_args)?;
+
+        match env::var("RUST_LOG") {
+            Ok(_) => {}
+            Err(_) => env::set_var("RUST_LOG", "info"),
+        }
+        setup_logger();
+
+        let stdin = read_stdin(self.input.as_ref())?;
+        let paths = dump_traces(
+            Program::from(&elf),
+            &stdin,
+            BabyBearPoseidon2::new(),
+            SP1CoreOpts::default(),
+            &self.chips,
+            &self.output_dir,
+        )
+        .context("failed to generate traces")?;
+
+        let green = AnsiColor::Green.on_default().effects(Effects::BOLD);
+        write_status(
+            &green,
+            "Finished",
+            format!(
+                "saving {} traces to {}",
+                paths.len(),
+                self.output_dir.display()
+            )
+            .as_str(),
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use anstyle::*;
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use sp1_core::utils::setup_logger;
+use sp1_sdk::{
+    envelope::read_header, HashableKey, ProofKind, ProverClient, SP1CompressedProof,
+    SP1Groth16Proof, SP1PlonkProof, SP1Proof, SP1VerifyingKey,
+};
+use std::{env, fs::File, io::BufReader, path::PathBuf};
+
+use crate::{build::BuildArgs, commands::execute::read_elf, util::write_status};
+
+#[derive(Parser)]
+#[command(name = "verify", about = "Verify a proof saved with `save`")]
+pub struct VerifyCmd {
+    /// The proof to verify, of any kind.
+    #[clap(long)]
+    proof: PathBuf,
+
+    /// The ELF of the program. Defaults to building the program in the current directory.
+    #[clap(long, conflicts_with = "vkey")]
+    elf: Option<PathBuf>,
+
+    /// The verifying key of the program, encoded with bincode.
+    #[clap(long)]
+    vkey: Option<PathBuf>,
+
+    #[clap(flatten)]
+    build_args: BuildArgs,
+}
+
+impl VerifyCmd {
+    pub fn run(&self) -> Result<()> {
+        match env::var("RUST_LOG") {
+            Ok(_) => {}
+            Err(_) => env::set_var("RUST_LOG", "info"),
+        }
+        setup_logger();
+
+        let file = File::open(&self.proof)
+            .with_context(|| format!("failed to open proof {}", self.proof.display()))?;
+        let header = read_header(&mut BufReader::new(file))?;
+
+        // The proof is always verified with the local prover, even if `SP1_PROVER` selects the
+        // mock prover, which would accept any proof.
+        let client = ProverClient::local();
+        let vk = match self.vkey {
+            Some(ref path) => {
+                let file = File::open(path)
+                    .with_context(|| format!("failed to open vkey {}", path.display()))?;
+                bincode::deserialize_from::<_, SP1VerifyingKey>(BufReader::new(file))
+                    .context("failed to deserialize vkey")?
+            }
+            None => {
+                let elf = read_elf(self.elf.as_ref(), &self.build_args)?;
+                client.setup(&elf).1
+            }
+        };
+
+        if header.vkey_hash != vk.bytes32() {
+            bail!(
+                "the proof is for the program with vkey hash {}, but the vkey hash is {}",
+                header.vkey_hash,
+                vk.bytes32()
+            );
+        }
+
+        match header.kind {
+            ProofKind::Core => client.verify(&SP1Proof::load(&self.proof)?, &vk)?,
+            ProofKind::Compressed => {
+                client.verify_compressed(&SP1CompressedProof::load(&self.proof)?, &vk)?
+            }
+            ProofKind::Groth16 => {
+                client.verify_groth16(&SP1Groth16Proof::load(&self.proof)?, &vk)?
+            }
+            ProofKind::Plonk => client.verify_plonk(&SP1PlonkProof::load(&self.proof)?, &vk)?,
+        }
+
+        let green = AnsiColor::Green.on_default().effects(Effects::BOLD);
+        write_status(
+            &green,
+            "Verified",
+            format!(
+                "{:?} proof {} with {} security",
+                header.kind,
+                self.proof.display(),
+                vk.vk.security
+            )
+            .as_str(),
+        );
+
+        Ok(())
+    }
+}
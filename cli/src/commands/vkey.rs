@@ -0,0 +1,80 @@
+use anstyle::*;
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use p3_field::PrimeField;
+use serde::{Deserialize, Serialize};
+use sp1_prover::{HashableKey, SP1Prover};
+use std::{fs, path::PathBuf};
+
+use crate::{build::BuildArgs, commands::execute::read_elf, util::write_status};
+
+#[derive(Parser)]
+#[command(name = "vkey", about = "Print the verifying key hash of a program")]
+pub struct VkeyCmd {
+    /// The ELF of the program. Defaults to building the program in the current directory.
+    #[clap(long)]
+    elf: Option<PathBuf>,
+
+    /// Writes the hashes to a JSON file instead of printing them.
+    #[clap(long)]
+    output: Option<PathBuf>,
+
+    /// Compares the hashes against a JSON file written with `--output`, failing if they differ.
+    #[clap(long, conflicts_with = "output")]
+    check: Option<PathBuf>,
+
+    #[clap(flatten)]
+    build_args: BuildArgs,
+}
+
+/// The hashes of a verifying key, in the forms used by the verifiers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct VkeyHashes {
+    /// The digest of BabyBear elements, as verified by `verify_sp1_proof` in programs.
+    babybear: [u32; 8],
+    /// The digest hashed into a BN254 element, in decimal.
+    bn254: String,
+    /// The BN254 element as 32 bytes, as verified by the onchain verifiers.
+    bytes32: String,
+}
+
+impl VkeyCmd {
+    pub fn run(&self) -> Result<()> {
+        let elf = read_elf(self.elf.as_ref(), &self.build_args)?;
+        let (_, vk) = SP1Prover::new().setup(&elf);
+        let hashes = VkeyHashes {
+            babybear: vk.hash_u32(),
+            bn254: vk.hash_bn254().as_canonical_biguint().to_string(),
+            bytes32: vk.bytes32(),
+        };
+        let json = serde_json::to_string_pretty(&hashes)?;
+
+        if let Some(ref path) = self.check {
+            let expected = fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let expected: VkeyHashes = serde_json::from_str(&expected)
+                .with_context(|| format!("failed to parse {}", path.display()))?;
+            if expected != hashes {
+                bail!(
+                    "the vkey of the program does not match {}:\nexpected: {}\nfound: {}",
+                    path.display(),
+                    expected.bytes32,
+                    hashes.bytes32
+                );
+            }
+            let green = AnsiColor::Green.on_default().effects(Effects::BOLD);
+            write_status(
+                &green,
+                "Matched",
+                format!("vkey {} in {}", hashes.bytes32, path.display()).as_str(),
+            );
+        } else if let Some(ref path) = self.output {
+            fs::write(path, json + "\n")
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        } else {
+            println!("{}", json);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::Result;
+use clap::Parser;
+use sp1_core::utils::setup_logger;
+use sp1_sdk::{
+    client::NetworkClient,
+    worker::{ProverWorker, WorkerOpts},
+    LocalProver,
+};
+use std::{env, sync::Arc, time::Duration};
+
+#[derive(Parser)]
+#[command(
+    name = "worker",
+    about = "Claim and prove the proof requests of a prover network"
+)]
+pub struct WorkerCmd {
+    /// The private key of the prover account, used to sign the claims and fulfillments.
+    #[clap(long, env = "SP1_PRIVATE_KEY", hide_env_values = true)]
+    private_key: String,
+
+    /// The RPC endpoint of the prover network. Defaults to `PROVER_NETWORK_RPC` or the Succinct
+    /// prover network.
+    #[clap(long)]
+    rpc_url: Option<String>,
+
+    /// The number of requests proven concurrently.
+    #[clap(long, default_value_t = 1)]
+    concurrency: usize,
+
+    /// The number of times a failed step is retried before the request is unclaimed.
+    #[clap(long, default_value_t = 2)]
+    max_retries: usize,
+
+    /// The number of seconds between two polls for requests.
+    #[clap(long, default_value_t = 5)]
+    poll_interval: u64,
+}
+
+impl WorkerCmd {
+    pub fn run(&self) -> Result<()> {
+        match env::var("RUST_LOG") {
+            Ok(_) => {}
+            Err(_) => env::set_var("RUST_LOG", "info"),
+        }
+        setup_logger();
+
+        let client = match self.rpc_url {
+            Some(ref rpc_url) => NetworkClient::with_rpc_url(&self.private_key, rpc_url),
+            None => NetworkClient::new(&self.private_key),
+        };
+        let opts = WorkerOpts {
+            concurrency: self.concurrency,
+            max_retries: self.max_retries,
+            poll_interval: Duration::from_secs(self.poll_interval),
+            ..Default::default()
+        };
+        let worker = Arc::new(ProverWorker::new(client, LocalProver::new(), opts));
+
+        tokio::runtime::Runtime::new()?.block_on(worker.run())
+    }
+}
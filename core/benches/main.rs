@@ -1,7 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use sp1_core::io::SP1Stdin;
 use sp1_core::runtime::{Program, Runtime};
-use sp1_core::utils::{prove, BabyBearPoseidon2};
+use sp1_core::utils::{prove, BabyBearPoseidon2, ProofProgress, SP1CoreOpts};
 
 #[allow(unreachable_code)]
 pub fn criterion_benchmark(c: &mut Criterion) {
@@ -12,7 +12,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         let elf_path = format!("../programs/demo/{}/elf/riscv32im-succinct-zkvm-elf", p);
         let program = Program::from_elf(&elf_path);
         let cycles = {
-            let mut runtime = Runtime::new(program.clone());
+            let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
             runtime.run().unwrap();
             runtime.state.global_clk
         };
@@ -24,6 +24,8 @@ pub fn criterion_benchmark(c: &mut Criterion) {
                         black_box(program.clone()),
                         &SP1Stdin::new(),
                         BabyBearPoseidon2::new(),
+                        SP1CoreOpts::default(),
+                        &ProofProgress::default(),
                     )
                 })
             },
@@ -0,0 +1,54 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use sp1_core::io::SP1Stdin;
+use sp1_core::runtime::{Program, Runtime};
+use sp1_core::utils::{prove, BabyBearPoseidon2, ProofProgress, SP1CoreOpts};
+
+/// Compares the throughput of proving in batches with and without overlapping the trace
+/// generation of the next shards with the proof of the current one.
+pub fn pipeline_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pipeline");
+    group.sample_size(10);
+
+    let program = Program::from_elf("../tests/fibonacci/elf/riscv32im-succinct-zkvm-elf");
+    // Use the smallest shards proven in small batches, so that the program spans many shards.
+    let base_opts = SP1CoreOpts {
+        shard_size: 1 << 10,
+        shard_batch_size: 4,
+        ..SP1CoreOpts::default()
+    };
+    let (cycles, num_shards) = {
+        let mut runtime = Runtime::new(program.clone(), base_opts);
+        runtime.run().unwrap();
+        (runtime.state.global_clk, runtime.shard() as usize)
+    };
+    // The pipeline only overlaps work across shards, so it needs more than one batch of shards.
+    assert!(
+        num_shards > base_opts.shard_batch_size,
+        "the program spans only {} shards",
+        num_shards
+    );
+    group.throughput(Throughput::Elements(cycles));
+
+    for depth in [0, 1, 2] {
+        let opts = SP1CoreOpts {
+            pipeline_depth: depth,
+            ..base_opts
+        };
+        group.bench_function(format!("depth:{}:{}:{}", depth, num_shards, cycles), |b| {
+            b.iter(|| {
+                prove(
+                    black_box(program.clone()),
+                    &SP1Stdin::new(),
+                    BabyBearPoseidon2::new(),
+                    opts,
+                    &ProofProgress::default(),
+                )
+                .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, pipeline_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+use std::marker::PhantomData;
+use std::mem::size_of;
+
+use generic_array::{ArrayLength, GenericArray};
+use p3_keccak_air::KeccakCols;
+use typenum::Unsigned;
+
+use crate::operations::field::params::Limbs;
+
+/// The names of the columns of a column struct, in the order they appear in the trace.
+///
+/// This is implemented by `#[derive(AlignedBorrow)]`: named fields are joined with `.` and array
+/// elements are indexed with `[i]`, so the `unsigned_mem_val` word of `CpuCols` has the names
+/// `unsigned_mem_val[0]` through `unsigned_mem_val[3]`. Types whose layout the derive cannot see,
+/// such as unions and column structs from other crates, are named by index.
+pub trait ColumnNames {
+    /// Appends the names of the columns to `names`, each starting with `prefix`.
+    fn column_names_with_prefix(prefix: &str, names: &mut Vec<String>);
+
+    /// The names of the columns.
+    fn column_names() -> Vec<String> {
+        let mut names = Vec::new();
+        Self::column_names_with_prefix("", &mut names);
+        names
+    }
+}
+
+/// Joins the name of a field to the prefix of the struct that contains it.
+pub fn column_name(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", prefix, field)
+    }
+}
+
+/// Appends `width` columns named `prefix[0]` through `prefix[width - 1]` to `names`.
+pub fn indexed_column_names(prefix: &str, width: usize, names: &mut Vec<String>) {
+    names.extend((0..width).map(|i| format!("{}[{}]", prefix, i)));
+}
+
+impl<T> ColumnNames for PhantomData<T> {
+    fn column_names_with_prefix(_prefix: &str, _names: &mut Vec<String>) {}
+}
+
+impl<T, N: ArrayLength> ColumnNames for Limbs<T, N> {
+    fn column_names_with_prefix(prefix: &str, names: &mut Vec<String>) {
+        indexed_column_names(prefix, N::USIZE, names);
+    }
+}
+
+impl<T: ColumnNames, N: ArrayLength> ColumnNames for GenericArray<T, N> {
+    fn column_names_with_prefix(prefix: &str, names: &mut Vec<String>) {
+        for i in 0..N::USIZE {
+            T::column_names_with_prefix(&format!("{}[{}]", prefix, i), names);
+        }
+    }
+}
+
+impl<T> ColumnNames for KeccakCols<T> {
+    fn column_names_with_prefix(prefix: &str, names: &mut Vec<String>) {
+        indexed_column_names(prefix, size_of::<Self>() / size_of::<T>(), names);
+    }
+}
@@ -16,6 +16,14 @@ pub trait MachineAir<F: Field>: BaseAir<F> {
     /// A unique identifier for this AIR as part of a machine.
     fn name(&self) -> String;
 
+    /// The names of the columns of the main trace, in order.
+    ///
+    /// Chips whose columns are laid out by a `#[derive(AlignedBorrow)]` struct return the names
+    /// from [`ColumnNames`](super::ColumnNames). The default names the columns by index.
+    fn column_names(&self) -> Vec<String> {
+        (0..self.width()).map(|i| format!("column_{}", i)).collect()
+    }
+
     /// Generate the trace for a given execution record.
     ///
     /// - `input` is the execution record containing the events to be written to the trace.
@@ -23,6 +31,14 @@ pub trait MachineAir<F: Field>: BaseAir<F> {
     ///    the record such as byte lookup requests.
     fn generate_trace(&self, input: &Self::Record, output: &mut Self::Record) -> RowMajorMatrix<F>;
 
+    /// The number of rows of the trace of `input` before padding to a power of two.
+    ///
+    /// Chips count these from the events of the record without generating the trace. The default
+    /// returns `None` for chips that do not report it.
+    fn num_rows(&self, _input: &Self::Record) -> Option<usize> {
+        None
+    }
+
     /// Generate the dependencies for a given execution record.
     fn generate_dependencies(&self, input: &Self::Record, output: &mut Self::Record) {
         self.generate_trace(input, output);
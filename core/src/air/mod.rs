@@ -1,4 +1,5 @@
 mod builder;
+mod column_names;
 mod extension;
 mod interaction;
 mod machine;
@@ -8,6 +9,7 @@ mod sub_builder;
 mod word;
 
 pub use builder::*;
+pub use column_names::*;
 pub use extension::*;
 pub use interaction::*;
 pub use machine::*;
@@ -9,6 +9,7 @@ use p3_maybe_rayon::prelude::ParallelIterator;
 use p3_maybe_rayon::prelude::ParallelSlice;
 use sp1_derive::AlignedBorrow;
 
+use crate::air::ColumnNames;
 use crate::air::MachineAir;
 use crate::air::{SP1AirBuilder, Word};
 use crate::operations::AddOperation;
@@ -61,6 +62,10 @@ impl<F: PrimeField> MachineAir<F> for AddSubChip {
         "AddSub".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        AddSubCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -123,6 +128,10 @@ impl<F: PrimeField> MachineAir<F> for AddSubChip {
         trace
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.add_events.len() + input.sub_events.len())
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.add_events.is_empty() || !shard.sub_events.is_empty()
     }
@@ -7,6 +7,7 @@ use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use sp1_derive::AlignedBorrow;
 
+use crate::air::ColumnNames;
 use crate::air::MachineAir;
 use crate::air::{SP1AirBuilder, Word};
 use crate::bytes::event::ByteRecord;
@@ -56,6 +57,10 @@ impl<F: PrimeField> MachineAir<F> for BitwiseChip {
         "Bitwise".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        BitwiseCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -109,6 +114,10 @@ impl<F: PrimeField> MachineAir<F> for BitwiseChip {
         trace
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.bitwise_events.len())
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.bitwise_events.is_empty()
     }
@@ -73,6 +73,7 @@ use p3_matrix::Matrix;
 use sp1_derive::AlignedBorrow;
 
 use self::utils::eval_abs_value;
+use crate::air::ColumnNames;
 use crate::air::MachineAir;
 use crate::air::{SP1AirBuilder, Word};
 use crate::alu::divrem::utils::{get_msb, get_quotient_and_remainder, is_signed_operation};
@@ -194,6 +195,10 @@ impl<F: PrimeField> MachineAir<F> for DivRemChip {
         "DivRem".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        DivRemCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -410,6 +415,10 @@ impl<F: PrimeField> MachineAir<F> for DivRemChip {
         trace
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.divrem_events.len())
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.divrem_events.is_empty()
     }
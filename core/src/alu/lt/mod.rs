@@ -10,6 +10,7 @@ use p3_matrix::Matrix;
 use p3_maybe_rayon::prelude::*;
 use sp1_derive::AlignedBorrow;
 
+use crate::air::ColumnNames;
 use crate::air::{BaseAirBuilder, MachineAir};
 use crate::air::{SP1AirBuilder, Word};
 use crate::bytes::event::ByteRecord;
@@ -98,6 +99,10 @@ impl<F: PrimeField32> MachineAir<F> for LtChip {
         "Lt".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        LtCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -216,6 +221,10 @@ impl<F: PrimeField32> MachineAir<F> for LtChip {
         trace
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.lt_events.len())
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.lt_events.is_empty()
     }
@@ -42,6 +42,7 @@ use p3_maybe_rayon::prelude::ParallelIterator;
 use p3_maybe_rayon::prelude::ParallelSlice;
 use sp1_derive::AlignedBorrow;
 
+use crate::air::ColumnNames;
 use crate::air::MachineAir;
 use crate::air::{SP1AirBuilder, Word};
 use crate::alu::mul::utils::get_msb;
@@ -128,6 +129,10 @@ impl<F: PrimeField> MachineAir<F> for MulChip {
         "Mul".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        MulCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -264,6 +269,10 @@ impl<F: PrimeField> MachineAir<F> for MulChip {
         trace
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.mul_events.len())
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.mul_events.is_empty()
     }
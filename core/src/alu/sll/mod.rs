@@ -40,6 +40,7 @@ use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use sp1_derive::AlignedBorrow;
 
+use crate::air::ColumnNames;
 use crate::air::MachineAir;
 use crate::air::{SP1AirBuilder, Word};
 use crate::bytes::event::ByteRecord;
@@ -103,6 +104,10 @@ impl<F: PrimeField> MachineAir<F> for ShiftLeft {
         "ShiftLeft".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        ShiftLeftCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -198,6 +203,10 @@ impl<F: PrimeField> MachineAir<F> for ShiftLeft {
         trace
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.shift_left_events.len())
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.shift_left_events.is_empty()
     }
@@ -52,6 +52,7 @@ use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use sp1_derive::AlignedBorrow;
 
+use crate::air::ColumnNames;
 use crate::air::MachineAir;
 use crate::air::{SP1AirBuilder, Word};
 use crate::alu::sr::utils::{nb_bits_to_shift, nb_bytes_to_shift};
@@ -134,6 +135,10 @@ impl<F: PrimeField> MachineAir<F> for ShiftRightChip {
         "ShiftRight".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        ShiftRightCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -276,6 +281,10 @@ impl<F: PrimeField> MachineAir<F> for ShiftRightChip {
         trace
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.shift_right_events.len())
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.shift_right_events.is_empty()
     }
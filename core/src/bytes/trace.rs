@@ -8,7 +8,7 @@ use super::{
     ByteChip,
 };
 use crate::{
-    air::MachineAir,
+    air::{ColumnNames, MachineAir},
     runtime::{ExecutionRecord, Program},
 };
 
@@ -23,6 +23,10 @@ impl<F: Field> MachineAir<F> for ByteChip<F> {
         "Byte".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        ByteMultCols::<u8>::column_names()
+    }
+
     fn preprocessed_width(&self) -> usize {
         NUM_BYTE_PREPROCESSED_COLS
     }
@@ -66,6 +70,10 @@ impl<F: Field> MachineAir<F> for ByteChip<F> {
         trace
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(NUM_ROWS)
+    }
+
     fn included(&self, _shard: &Self::Record) -> bool {
         true
     }
@@ -1,3 +1,4 @@
+use crate::air::{indexed_column_names, ColumnNames};
 use crate::cpu::columns::{AuipcCols, BranchCols, JumpCols, MemoryColumns};
 use std::fmt::{Debug, Formatter};
 use std::mem::{size_of, transmute};
@@ -33,6 +34,13 @@ impl<T: Copy + Debug> Debug for OpcodeSpecificCols<T> {
     }
 }
 
+// The views overlap, so the columns are named by index.
+impl<T: Copy> ColumnNames for OpcodeSpecificCols<T> {
+    fn column_names_with_prefix(prefix: &str, names: &mut Vec<String>) {
+        indexed_column_names(prefix, size_of::<Self>() / size_of::<T>(), names);
+    }
+}
+
 // SAFETY: Each view is a valid interpretation of the underlying array.
 impl<T: Copy> OpcodeSpecificCols<T> {
     pub fn memory(&self) -> &MemoryColumns<T> {
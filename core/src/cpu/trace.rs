@@ -10,6 +10,7 @@ use tracing::instrument;
 
 use super::columns::{CPU_COL_MAP, NUM_CPU_COLS};
 use super::{CpuChip, CpuEvent};
+use crate::air::ColumnNames;
 use crate::air::MachineAir;
 use crate::alu::{self, AluEvent};
 use crate::bytes::event::ByteRecord;
@@ -30,6 +31,10 @@ impl<F: PrimeField32> MachineAir<F> for CpuChip {
         "CPU".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        CpuCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -114,6 +119,10 @@ impl<F: PrimeField32> MachineAir<F> for CpuChip {
             });
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.cpu_events.len())
+    }
+
     fn included(&self, _: &Self::Record) -> bool {
         true
     }
@@ -624,7 +633,7 @@ mod tests {
     use super::*;
 
     use crate::runtime::{tests::simple_program, Instruction, Runtime};
-    use crate::utils::{run_test, setup_logger};
+    use crate::utils::{run_test, setup_logger, SP1CoreOpts};
 
     #[test]
     fn generate_trace() {
@@ -661,7 +670,7 @@ mod tests {
     #[test]
     fn generate_trace_simple_program() {
         let program = simple_program();
-        let mut runtime = Runtime::new(program);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
         runtime.run().unwrap();
         let chip = CpuChip::default();
         let trace: RowMajorMatrix<BabyBear> =
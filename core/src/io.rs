@@ -2,9 +2,177 @@ use crate::{
     stark::{ShardProof, StarkVerifyingKey},
     utils::{BabyBearPoseidon2, Buffer},
 };
+use alloy_sol_types::SolValue;
+use borsh::{BorshDeserialize, BorshSerialize};
 use k256::sha2::{Digest, Sha256};
 use num_bigint::BigUint;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+/// The encoding of a value written to [SP1Stdin] or committed to [SP1PublicValues].
+///
+/// The guest must read a value with the same format it was written with, see `read_with` and
+/// `commit_with` in `sp1_zkvm::io`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CodecFormat {
+    Bincode,
+    Borsh,
+    Json,
+    /// The Solidity ABI encoding, as decoded by `abi.decode` in contracts.
+    SolidityAbi,
+}
+
+impl CodecFormat {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            CodecFormat::Bincode => "bincode",
+            CodecFormat::Borsh => "borsh",
+            CodecFormat::Json => "json",
+            CodecFormat::SolidityAbi => "abi",
+        }
+    }
+}
+
+impl fmt::Display for CodecFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// An error returned when a value cannot be encoded or decoded with a [Codec].
+#[derive(Error, Debug)]
+pub enum CodecError {
+    #[error("failed to encode {format} value: {message}")]
+    Encode {
+        format: CodecFormat,
+        message: String,
+    },
+    #[error("failed to decode {format} value: {message}")]
+    Decode {
+        format: CodecFormat,
+        message: String,
+    },
+}
+
+impl CodecError {
+    fn encode(format: CodecFormat, err: impl fmt::Display) -> Self {
+        Self::Encode {
+            format,
+            message: err.to_string(),
+        }
+    }
+
+    fn decode(format: CodecFormat, err: impl fmt::Display) -> Self {
+        Self::Decode {
+            format,
+            message: err.to_string(),
+        }
+    }
+}
+
+/// An encoding of values of type `T`, tagged with its [CodecFormat].
+///
+/// The codecs are the unit structs [Bincode], [Borsh], [Json] and [SolidityAbi], used as
+/// `stdin.write_with::<Json, _>(&value)`.
+pub trait Codec<T> {
+    const FORMAT: CodecFormat;
+
+    fn encode(value: &T) -> Result<Vec<u8>, CodecError>;
+
+    /// Decodes a value from the start of `bytes`, returning it with the number of bytes read.
+    fn decode(bytes: &[u8]) -> Result<(T, usize), CodecError>;
+}
+
+/// The bincode encoding, used by [SP1Stdin::write] and [SP1PublicValues::read].
+pub struct Bincode;
+
+/// The borsh encoding.
+pub struct Borsh;
+
+/// The JSON encoding, with each value followed by a newline so that consecutive values, such as
+/// the numbers `1` and `2`, are not read as a single one.
+pub struct Json;
+
+/// The Solidity ABI encoding of [SolValue]s.
+pub struct SolidityAbi;
+
+impl<T: Serialize + DeserializeOwned> Codec<T> for Bincode {
+    const FORMAT: CodecFormat = CodecFormat::Bincode;
+
+    fn encode(value: &T) -> Result<Vec<u8>, CodecError> {
+        bincode::serialize(value).map_err(|err| CodecError::encode(Self::FORMAT, err))
+    }
+
+    fn decode(bytes: &[u8]) -> Result<(T, usize), CodecError> {
+        let value: T =
+            bincode::deserialize(bytes).map_err(|err| CodecError::decode(Self::FORMAT, err))?;
+        let nb_bytes = bincode::serialized_size(&value)
+            .map_err(|err| CodecError::decode(Self::FORMAT, err))?;
+        Ok((value, nb_bytes as usize))
+    }
+}
+
+impl<T: BorshSerialize + BorshDeserialize> Codec<T> for Borsh {
+    const FORMAT: CodecFormat = CodecFormat::Borsh;
+
+    fn encode(value: &T) -> Result<Vec<u8>, CodecError> {
+        borsh::to_vec(value).map_err(|err| CodecError::encode(Self::FORMAT, err))
+    }
+
+    fn decode(bytes: &[u8]) -> Result<(T, usize), CodecError> {
+        let mut remaining = bytes;
+        let value =
+            T::deserialize(&mut remaining).map_err(|err| CodecError::decode(Self::FORMAT, err))?;
+        Ok((value, bytes.len() - remaining.len()))
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> Codec<T> for Json {
+    const FORMAT: CodecFormat = CodecFormat::Json;
+
+    fn encode(value: &T) -> Result<Vec<u8>, CodecError> {
+        let mut bytes =
+            serde_json::to_vec(value).map_err(|err| CodecError::encode(Self::FORMAT, err))?;
+        bytes.push(b'\n');
+        Ok(bytes)
+    }
+
+    fn decode(bytes: &[u8]) -> Result<(T, usize), CodecError> {
+        let mut values = serde_json::Deserializer::from_slice(bytes).into_iter::<T>();
+        let value = values
+            .next()
+            .ok_or_else(|| CodecError::decode(Self::FORMAT, "unexpected end of input"))?
+            .map_err(|err| CodecError::decode(Self::FORMAT, err))?;
+        let nb_bytes = values.byte_offset();
+        if bytes.get(nb_bytes) != Some(&b'\n') {
+            return Err(CodecError::decode(
+                Self::FORMAT,
+                "expected a newline after the value",
+            ));
+        }
+        Ok((value, nb_bytes + 1))
+    }
+}
+
+impl<T: SolValue> Codec<T> for SolidityAbi {
+    const FORMAT: CodecFormat = CodecFormat::SolidityAbi;
+
+    fn encode(value: &T) -> Result<Vec<u8>, CodecError> {
+        Ok(value.abi_encode())
+    }
+
+    fn decode(bytes: &[u8]) -> Result<(T, usize), CodecError> {
+        // The value may be followed by other values, which a validating decode would reject, so its
+        // length is found first and its bytes are then validated as the guest does.
+        let value =
+            T::abi_decode(bytes, false).map_err(|err| CodecError::decode(Self::FORMAT, err))?;
+        let nb_bytes = value.abi_encode().len();
+        let value = T::abi_decode(&bytes[..nb_bytes], true)
+            .map_err(|err| CodecError::decode(Self::FORMAT, err))?;
+        Ok((value, nb_bytes))
+    }
+}
 
 /// Standard input for the prover.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,6 +220,13 @@ impl SP1Stdin {
         result
     }
 
+    /// Read a value written with [Self::write_with] from the buffer.
+    pub fn read_with<C: Codec<T>, T>(&mut self) -> Result<T, CodecError> {
+        let (result, _) = C::decode(&self.buffer[self.ptr])?;
+        self.ptr += 1;
+        Ok(result)
+    }
+
     /// Read a slice of bytes from the buffer.
     pub fn read_slice(&mut self, slice: &mut [u8]) {
         slice.copy_from_slice(&self.buffer[self.ptr]);
@@ -65,6 +240,13 @@ impl SP1Stdin {
         self.buffer.push(tmp);
     }
 
+    /// Write a value encoded with the codec `C` to the buffer, to be read in the program with
+    /// `sp1_zkvm::io::read_with` and the same codec.
+    pub fn write_with<C: Codec<T>, T>(&mut self, data: &T) -> Result<(), CodecError> {
+        self.buffer.push(C::encode(data)?);
+        Ok(())
+    }
+
     /// Write a slice of bytes to the buffer.
     pub fn write_slice(&mut self, slice: &[u8]) {
         self.buffer.push(slice.to_vec());
@@ -115,6 +297,13 @@ impl SP1PublicValues {
         self.buffer.read()
     }
 
+    /// Read a value committed with `sp1_zkvm::io::commit_with` and the codec `C` from the buffer.
+    pub fn read_with<C: Codec<T>, T>(&mut self) -> Result<T, CodecError> {
+        let (result, nb_bytes) = C::decode(&self.buffer.data[self.buffer.ptr..])?;
+        self.buffer.ptr += nb_bytes;
+        Ok(result)
+    }
+
     /// Read a slice of bytes from the buffer.
     pub fn read_slice(&mut self, slice: &mut [u8]) {
         self.buffer.read_slice(slice);
@@ -125,6 +314,12 @@ impl SP1PublicValues {
         self.buffer.write(data);
     }
 
+    /// Write a value encoded with the codec `C` to the buffer.
+    pub fn write_with<C: Codec<T>, T>(&mut self, data: &T) -> Result<(), CodecError> {
+        self.buffer.write_slice(&C::encode(data)?);
+        Ok(())
+    }
+
     /// Write a slice of bytes to the buffer.
     pub fn write_slice(&mut self, slice: &[u8]) {
         self.buffer.write_slice(slice);
@@ -213,4 +408,57 @@ mod tests {
 
         assert_eq!(hash, expected_hash_biguint);
     }
+
+    #[test]
+    fn test_codecs() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+        struct Value {
+            n: u32,
+            name: String,
+        }
+        let value = Value {
+            n: 42,
+            name: "sp1".to_string(),
+        };
+
+        let mut stdin = SP1Stdin::new();
+        stdin.write_with::<Borsh, _>(&value).unwrap();
+        stdin.write_with::<Json, _>(&value).unwrap();
+        assert_eq!(stdin.read_with::<Borsh, Value>().unwrap(), value);
+        assert_eq!(stdin.read_with::<Json, Value>().unwrap(), value);
+
+        // Values of different formats can follow each other in the public values.
+        let mut public_values = SP1PublicValues::new();
+        public_values.write_with::<Json, _>(&value).unwrap();
+        public_values
+            .write_with::<SolidityAbi, _>(&(7u64, "sp1".to_string()))
+            .unwrap();
+        public_values.write_with::<Bincode, _>(&value.n).unwrap();
+        assert_eq!(public_values.read_with::<Json, Value>().unwrap(), value);
+        assert_eq!(
+            public_values
+                .read_with::<SolidityAbi, (u64, String)>()
+                .unwrap(),
+            (7, "sp1".to_string())
+        );
+        assert_eq!(public_values.read_with::<Bincode, u32>().unwrap(), 42);
+
+        // Consecutive JSON values are delimited.
+        let mut public_values = SP1PublicValues::new();
+        public_values.write_with::<Json, _>(&1u32).unwrap();
+        public_values.write_with::<Json, _>(&2u32).unwrap();
+        assert_eq!(public_values.read_with::<Json, u32>().unwrap(), 1);
+        assert_eq!(public_values.read_with::<Json, u32>().unwrap(), 2);
+
+        let err = SP1Stdin::from(b"not json")
+            .read_with::<Json, Value>()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CodecError::Decode {
+                format: CodecFormat::Json,
+                ..
+            }
+        ));
+    }
 }
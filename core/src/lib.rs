@@ -17,6 +17,9 @@
 
 extern crate alloc;
 
+// Lets `#[derive(AlignedBorrow)]` refer to `sp1_core` from inside this crate.
+extern crate self as sp1_core;
+
 pub mod air;
 pub mod alu;
 pub mod bytes;
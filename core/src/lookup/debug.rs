@@ -4,6 +4,7 @@ use p3_baby_bear::BabyBear;
 use p3_field::{AbstractField, PrimeField32};
 use p3_field::{Field, PrimeField64};
 use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
 
 use super::InteractionKind;
 use crate::air::MachineAir;
@@ -17,6 +18,18 @@ pub struct InteractionData<F: Field> {
     pub interaction_number: usize,
     pub is_send: bool,
     pub multiplicity: F,
+    pub values: Vec<F>,
+}
+
+/// An interaction whose sends and receives do not balance across the shards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InteractionImbalance {
+    /// The values of the interaction, as canonical integers.
+    pub values: Vec<u32>,
+    /// The number of sends minus the number of receives.
+    pub discrepancy: i32,
+    /// The discrepancy of each chip sending or receiving the interaction.
+    pub chips: BTreeMap<String, i32>,
 }
 
 pub fn vec_to_string<F: Field>(vec: Vec<F>) -> String {
@@ -97,7 +110,7 @@ pub fn debug_interactions<SC: StarkGenericConfig, A: MachineAir<Val<SC>>>(
                 let key = format!(
                     "{} {}",
                     &interaction.kind.to_string(),
-                    vec_to_string(values)
+                    vec_to_string(values.clone())
                 );
                 key_to_vec_data
                     .entry(key.clone())
@@ -109,6 +122,7 @@ pub fn debug_interactions<SC: StarkGenericConfig, A: MachineAir<Val<SC>>>(
                         interaction_number: m,
                         is_send,
                         multiplicity: multiplicity_eval,
+                        values,
                     });
                 let current = key_to_count.entry(key.clone()).or_insert(Val::<SC>::zero());
                 if is_send {
@@ -204,16 +218,72 @@ where
     !any_nonzero
 }
 
+/// Returns the interactions of the given kinds whose sends and receives do not balance across the
+/// shards, grouped by kind.
+pub fn interaction_imbalances<SC, A>(
+    machine: &StarkMachine<SC, A>,
+    pkey: &StarkProvingKey<SC>,
+    shards: &[A::Record],
+    interaction_kinds: Vec<InteractionKind>,
+) -> BTreeMap<InteractionKind, Vec<InteractionImbalance>>
+where
+    SC: StarkGenericConfig,
+    SC::Val: PrimeField32,
+    A: MachineAir<SC::Val>,
+{
+    // The kind, values, total count and count per chip of each interaction.
+    let mut interactions = BTreeMap::new();
+    for chip in machine.chips().iter() {
+        for shard in shards {
+            let (data, count) =
+                debug_interactions::<SC, A>(chip, pkey, shard, interaction_kinds.clone());
+            for (key, value) in count.iter() {
+                let entry = interactions.entry(key.clone()).or_insert_with(|| {
+                    let first = &data[key][0];
+                    (
+                        first.kind,
+                        first.values.clone(),
+                        SC::Val::zero(),
+                        BTreeMap::new(),
+                    )
+                });
+                entry.2 += *value;
+                *entry.3.entry(chip.name()).or_insert(SC::Val::zero()) += *value;
+            }
+        }
+    }
+
+    let mut imbalances = BTreeMap::<_, Vec<_>>::new();
+    for (kind, values, total, chip_values) in interactions.into_values() {
+        if total.is_zero() {
+            continue;
+        }
+        imbalances
+            .entry(kind)
+            .or_default()
+            .push(InteractionImbalance {
+                values: values.iter().map(|v| v.as_canonical_u32()).collect(),
+                discrepancy: field_to_int(total),
+                chips: chip_values
+                    .into_iter()
+                    .filter(|(_, value)| !value.is_zero())
+                    .map(|(chip, value)| (chip, field_to_int(value)))
+                    .collect(),
+            });
+    }
+    imbalances
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
         lookup::InteractionKind,
         runtime::{Program, Runtime, ShardingConfig},
         stark::RiscvAir,
-        utils::{setup_logger, tests::UINT256_MUL_ELF, BabyBearPoseidon2},
+        utils::{setup_logger, tests::UINT256_MUL_ELF, BabyBearPoseidon2, SP1CoreOpts},
     };
 
-    use super::debug_interactions_with_all_chips;
+    use super::{debug_interactions_with_all_chips, interaction_imbalances};
 
     #[test]
     fn test_debug_interactions() {
@@ -222,11 +292,14 @@ mod test {
         let config = BabyBearPoseidon2::new();
         let machine = RiscvAir::machine(config);
         let (pk, _) = machine.setup(&program);
-        let mut runtime = Runtime::new(program);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
         runtime.run().unwrap();
         let shards = machine.shard(runtime.record, &ShardingConfig::default());
         let ok =
             debug_interactions_with_all_chips(&machine, &pk, &shards, InteractionKind::all_kinds());
         assert!(ok);
+        assert!(
+            interaction_imbalances(&machine, &pk, &shards, InteractionKind::all_kinds()).is_empty()
+        );
     }
 }
@@ -3,6 +3,7 @@ use core::fmt::Display;
 
 use p3_air::VirtualPairCol;
 use p3_field::Field;
+use serde::{Deserialize, Serialize};
 
 /// An interaction for a lookup or a permutation argument.
 pub struct Interaction<F: Field> {
@@ -12,7 +13,7 @@ pub struct Interaction<F: Field> {
 }
 
 /// The type of interaction for a lookup argument.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum InteractionKind {
     /// Interaction with the memory table, such as read and write.
     Memory = 1,
@@ -10,6 +10,7 @@ use p3_matrix::Matrix;
 use sp1_derive::AlignedBorrow;
 
 use super::MemoryInitializeFinalizeEvent;
+use crate::air::ColumnNames;
 use crate::air::{AirInteraction, SP1AirBuilder, Word};
 use crate::air::{MachineAir, WordAirBuilder};
 use crate::runtime::{ExecutionRecord, Program};
@@ -52,6 +53,10 @@ impl<F: PrimeField> MachineAir<F> for MemoryChip {
         }
     }
 
+    fn column_names(&self) -> Vec<String> {
+        MemoryInitCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -93,6 +98,13 @@ impl<F: PrimeField> MachineAir<F> for MemoryChip {
         trace
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        match self.kind {
+            MemoryChipType::Initialize => Some(input.memory_initialize_events.len()),
+            MemoryChipType::Finalize => Some(input.memory_finalize_events.len()),
+        }
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         match self.kind {
             MemoryChipType::Initialize => !shard.memory_initialize_events.is_empty(),
@@ -177,14 +189,14 @@ mod tests {
     use crate::stark::MachineRecord;
     use crate::stark::{RiscvAir, StarkGenericConfig};
     use crate::syscall::precompiles::sha256::extend_tests::sha_extend_program;
-    use crate::utils::{setup_logger, BabyBearPoseidon2};
+    use crate::utils::{setup_logger, BabyBearPoseidon2, SP1CoreOpts};
     use crate::utils::{uni_stark_prove as prove, uni_stark_verify as verify};
     use p3_baby_bear::BabyBear;
 
     #[test]
     fn test_memory_generate_trace() {
         let program = simple_program();
-        let mut runtime = Runtime::new(program);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
         runtime.run().unwrap();
         let shard = runtime.record.clone();
 
@@ -210,7 +222,7 @@ mod tests {
         let mut challenger = config.challenger();
 
         let program = simple_program();
-        let mut runtime = Runtime::new(program);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
         runtime.run().unwrap();
 
         let chip = MemoryChip::new(MemoryChipType::Initialize);
@@ -228,7 +240,7 @@ mod tests {
         setup_logger();
         let program = sha_extend_program();
         let program_clone = program.clone();
-        let mut runtime = Runtime::new(program);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
         runtime.run().unwrap();
         let machine: crate::stark::StarkMachine<BabyBearPoseidon2, RiscvAir<BabyBear>> =
             RiscvAir::machine(BabyBearPoseidon2::new());
@@ -251,7 +263,7 @@ mod tests {
         setup_logger();
         let program = sha_extend_program();
         let program_clone = program.clone();
-        let mut runtime = Runtime::new(program);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
         runtime.run().unwrap();
         let machine = RiscvAir::machine(BabyBearPoseidon2::new());
         let (pkey, _) = machine.setup(&program_clone);
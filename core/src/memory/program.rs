@@ -8,6 +8,7 @@ use p3_matrix::Matrix;
 
 use sp1_derive::AlignedBorrow;
 
+use crate::air::ColumnNames;
 use crate::air::{AirInteraction, PublicValues, SP1AirBuilder};
 use crate::air::{MachineAir, Word};
 use crate::operations::IsZeroOperation;
@@ -58,6 +59,10 @@ impl<F: PrimeField> MachineAir<F> for MemoryProgramChip {
         "MemoryProgram".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        MemoryProgramMultCols::<u8>::column_names()
+    }
+
     fn preprocessed_width(&self) -> usize {
         NUM_MEMORY_PROGRAM_PREPROCESSED_COLS
     }
@@ -138,6 +143,10 @@ impl<F: PrimeField> MachineAir<F> for MemoryProgramChip {
         trace
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.program.memory_image.len())
+    }
+
     fn included(&self, _: &Self::Record) -> bool {
         true
     }
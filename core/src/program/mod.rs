@@ -8,6 +8,7 @@ use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use sp1_derive::AlignedBorrow;
 
+use crate::air::ColumnNames;
 use crate::air::MachineAir;
 use crate::air::SP1AirBuilder;
 use crate::cpu::columns::InstructionCols;
@@ -57,6 +58,10 @@ impl<F: PrimeField> MachineAir<F> for ProgramChip {
         "Program".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        ProgramMultiplicityCols::<u8>::column_names()
+    }
+
     fn preprocessed_width(&self) -> usize {
         NUM_PROGRAM_PREPROCESSED_COLS
     }
@@ -142,6 +147,10 @@ impl<F: PrimeField> MachineAir<F> for ProgramChip {
         trace
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.program.instructions.len())
+    }
+
     fn included(&self, _: &Self::Record) -> bool {
         true
     }
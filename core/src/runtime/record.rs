@@ -24,7 +24,7 @@ use crate::syscall::precompiles::sha256::{ShaCompressEvent, ShaExtendEvent};
 use crate::syscall::precompiles::uint256::Uint256MulEvent;
 use crate::syscall::precompiles::ECDecompressEvent;
 use crate::syscall::precompiles::{ECAddEvent, ECDoubleEvent};
-use crate::utils::env;
+use crate::utils::SP1CoreOpts;
 
 /// A record of the execution of a program. Contains event data for everything that happened during
 /// the execution of the shard.
@@ -105,6 +105,7 @@ pub struct ExecutionRecord {
     pub public_values: PublicValues<u32, u32>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ShardingConfig {
     pub shard_size: usize,
     pub add_len: usize,
@@ -127,14 +128,8 @@ pub struct ShardingConfig {
 }
 
 impl ShardingConfig {
-    pub const fn shard_size(&self) -> usize {
-        self.shard_size
-    }
-}
-
-impl Default for ShardingConfig {
-    fn default() -> Self {
-        let shard_size = env::shard_size();
+    /// Creates a sharding configuration where every chip uses the given number of rows.
+    pub const fn new(shard_size: usize) -> Self {
         Self {
             shard_size,
             add_len: shard_size,
@@ -156,6 +151,23 @@ impl Default for ShardingConfig {
             uint256_mul_len: shard_size,
         }
     }
+
+    pub const fn shard_size(&self) -> usize {
+        self.shard_size
+    }
+}
+
+impl Default for ShardingConfig {
+    fn default() -> Self {
+        Self::new(SP1CoreOpts::default().shard_size)
+    }
+}
+
+impl From<SP1CoreOpts> for ShardingConfig {
+    fn from(opts: SP1CoreOpts) -> Self {
+        opts.sharding_config
+            .unwrap_or_else(|| Self::new(opts.shard_size))
+    }
 }
 
 impl MachineRecord for ExecutionRecord {
@@ -0,0 +1,70 @@
+use std::ops::AddAssign;
+
+use super::{Opcode, SyscallCode};
+
+/// The number of opcodes, which index [ExecutionReport::opcode_counts].
+pub const NUM_OPCODES: usize = Opcode::UNIMP as usize + 1;
+
+/// The number of syscall ids, which index [ExecutionReport::syscall_counts].
+pub const NUM_SYSCALL_IDS: usize = 1 << 8;
+
+/// Counts of the instructions and syscalls executed by the runtime.
+///
+/// The counts are collected whether or not the runtime emits events, so they can be used to
+/// estimate the size of the traces from an untraced execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionReport {
+    /// The number of times each opcode was executed, indexed by the opcode.
+    pub opcode_counts: [u64; NUM_OPCODES],
+    /// The number of times each syscall was executed, indexed by [SyscallCode::syscall_id].
+    pub syscall_counts: [u64; NUM_SYSCALL_IDS],
+}
+
+impl ExecutionReport {
+    /// Counts an executed instruction, and the syscall it made if any.
+    #[inline]
+    pub fn count(&mut self, opcode: Opcode, syscall: Option<SyscallCode>) {
+        self.opcode_counts[opcode as usize] += 1;
+        if let Some(syscall) = syscall {
+            self.syscall_counts[syscall.syscall_id() as usize] += 1;
+        }
+    }
+
+    /// The total number of instructions executed, which is the number of rows of the CPU table.
+    pub fn total_instruction_count(&self) -> u64 {
+        self.opcode_counts.iter().sum()
+    }
+
+    /// The number of times any of the given opcodes was executed.
+    pub fn opcode_count(&self, opcodes: &[Opcode]) -> u64 {
+        opcodes
+            .iter()
+            .map(|opcode| self.opcode_counts[*opcode as usize])
+            .sum()
+    }
+
+    /// The number of times the given syscall was executed.
+    pub fn syscall_count(&self, syscall: SyscallCode) -> u64 {
+        self.syscall_counts[syscall.syscall_id() as usize]
+    }
+}
+
+impl Default for ExecutionReport {
+    fn default() -> Self {
+        Self {
+            opcode_counts: [0; NUM_OPCODES],
+            syscall_counts: [0; NUM_SYSCALL_IDS],
+        }
+    }
+}
+
+impl AddAssign<&ExecutionReport> for ExecutionReport {
+    fn add_assign(&mut self, other: &ExecutionReport) {
+        for (count, other) in self.opcode_counts.iter_mut().zip(other.opcode_counts) {
+            *count += other;
+        }
+        for (count, other) in self.syscall_counts.iter_mut().zip(other.syscall_counts) {
+            *count += other;
+        }
+    }
+}
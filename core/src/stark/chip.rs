@@ -29,6 +29,11 @@ pub struct Chip<F: Field, A> {
 }
 
 impl<F: Field, A> Chip<F, A> {
+    /// The underlying AIR of the chip.
+    pub fn air(&self) -> &A {
+        &self.air
+    }
+
     /// The send interactions of the chip.
     pub fn sends(&self) -> &[Interaction<F>] {
         &self.sends
@@ -164,6 +169,10 @@ where
         self.air.name()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        self.air.column_names()
+    }
+
     fn preprocessed_width(&self) -> usize {
         <A as MachineAir<F>>::preprocessed_width(&self.air)
     }
@@ -176,6 +185,10 @@ where
         self.air.generate_trace(input, output)
     }
 
+    fn num_rows(&self, input: &A::Record) -> Option<usize> {
+        self.air.num_rows(input)
+    }
+
     fn generate_dependencies(&self, input: &A::Record, output: &mut A::Record) {
         self.air.generate_dependencies(input, output)
     }
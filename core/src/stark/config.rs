@@ -3,6 +3,8 @@ use p3_commit::{Pcs, PolynomialSpace};
 use p3_field::{ExtensionField, Field, PrimeField};
 use serde::{de::DeserializeOwned, Serialize};
 
+use super::SecurityConfig;
+
 pub type Domain<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
     <SC as StarkGenericConfig>::Challenger,
@@ -67,6 +69,9 @@ pub trait StarkGenericConfig: Send + Sync + Serialize + DeserializeOwned + Clone
 
     /// Initialize a new challenger.
     fn challenger(&self) -> Self::Challenger;
+
+    /// The FRI parameters of the PCS, which determine the security of the proofs.
+    fn security(&self) -> SecurityConfig;
 }
 
 pub struct UniConfig<SC>(pub SC);
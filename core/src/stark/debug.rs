@@ -1,6 +1,8 @@
 use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
 use std::panic::{self, AssertUnwindSafe};
-use std::process::exit;
+use std::path::Path;
 
 use p3_air::{
     Air, AirBuilder, AirBuilderWithPublicValues, ExtensionBuilder, PairBuilder,
@@ -12,34 +14,116 @@ use p3_matrix::dense::RowMajorMatrixView;
 use p3_matrix::stack::VerticalPair;
 use p3_matrix::{dense::RowMajorMatrix, Matrix};
 
+use serde::{Deserialize, Serialize};
+
 use super::{MachineChip, StarkGenericConfig, Val};
 use crate::air::{EmptyMessageBuilder, MachineAir, MultiTableAirBuilder};
+use crate::lookup::{InteractionImbalance, InteractionKind};
+
+/// The constraints and interactions that do not hold in an execution, as found by
+/// [super::StarkMachine::debug_constraints].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConstraintDebugReport {
+    pub constraint_failures: Vec<ConstraintFailure>,
+    /// Whether the cumulative sum of the permutation traces of all shards is not zero.
+    pub nonzero_cumulative_sum: bool,
+    /// The interactions whose sends and receives do not balance, computed when the cumulative sum
+    /// is not zero.
+    pub interaction_imbalances: BTreeMap<InteractionKind, Vec<InteractionImbalance>>,
+}
+
+/// A constraint of a chip that does not hold on a row of its trace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstraintFailure {
+    pub shard: usize,
+    pub chip: String,
+    pub row: usize,
+    /// The index of the constraint in the order the chip asserts its constraints, or `None` if the
+    /// chip panicked while evaluating them.
+    pub constraint: Option<usize>,
+    pub message: String,
+    /// The main trace of the row and of the next row, as canonical integers.
+    pub local: Vec<u32>,
+    pub next: Vec<u32>,
+}
+
+impl ConstraintDebugReport {
+    /// Whether all the constraints and interactions hold.
+    pub fn is_ok(&self) -> bool {
+        self.constraint_failures.is_empty() && !self.nonzero_cumulative_sum
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Writes the report to a JSON file.
+    pub fn save_json(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json()?)
+    }
+}
 
-/// Checks that the constraints of the given AIR are satisfied, including the permutation trace.
+impl Display for ConstraintDebugReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            return write!(f, "all constraints and interactions hold");
+        }
+        for failure in self.constraint_failures.iter() {
+            let constraint = match failure.constraint {
+                Some(index) => format!("constraint {}", index),
+                None => "evaluation".to_string(),
+            };
+            writeln!(
+                f,
+                "shard {}, chip {}, row {}, {} failed: {}",
+                failure.shard, failure.chip, failure.row, constraint, failure.message
+            )?;
+        }
+        if self.nonzero_cumulative_sum {
+            writeln!(f, "the cumulative sum is not zero")?;
+        }
+        for (kind, imbalances) in self.interaction_imbalances.iter() {
+            for imbalance in imbalances.iter() {
+                writeln!(
+                    f,
+                    "{} interaction {:?} has a send-receive discrepancy of {} ({:?})",
+                    kind, imbalance.values, imbalance.discrepancy, imbalance.chips
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks that the constraints of the given AIR are satisfied, including the permutation trace,
+/// and returns the ones that are not.
 ///
 /// Note that this does not actually verify the proof.
 pub fn debug_constraints<SC, A>(
+    shard: usize,
     chip: &MachineChip<SC, A>,
     preprocessed: Option<&RowMajorMatrix<Val<SC>>>,
     main: &RowMajorMatrix<Val<SC>>,
     perm: &RowMajorMatrix<SC::Challenge>,
     perm_challenges: &[SC::Challenge],
     public_values: Vec<Val<SC>>,
-) where
+) -> Vec<ConstraintFailure>
+where
     SC: StarkGenericConfig,
     Val<SC>: PrimeField32,
     A: MachineAir<Val<SC>> + for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
 {
     assert_eq!(main.height(), perm.height());
     let height = main.height();
+    let mut failures = Vec::new();
     if height == 0 {
-        return;
+        return failures;
     }
 
     let cumulative_sum = perm.row_slice(perm.height() - 1).last().copied().unwrap();
 
     // Check that constraints are satisfied.
-    (0..height).for_each(|i| {
+    for i in 0..height {
         let i_next = (i + 1) % height;
 
         let main_local = main.row_slice(i);
@@ -85,6 +169,8 @@ pub fn debug_constraints<SC, A>(
             is_last_row: Val::<SC>::zero(),
             is_transition: Val::<SC>::one(),
             public_values: &public_values,
+            constraint_index: 0,
+            failures: Vec::new(),
         };
         if i == 0 {
             builder.is_first_row = Val::<SC>::one();
@@ -96,13 +182,30 @@ pub fn debug_constraints<SC, A>(
         let result = catch_unwind_silent(AssertUnwindSafe(|| {
             chip.eval(&mut builder);
         }));
+        let mut row_failures = std::mem::take(&mut builder.failures)
+            .into_iter()
+            .map(|(index, message)| (Some(index), message))
+            .collect::<Vec<_>>();
         if result.is_err() {
-            eprintln!("local: {:?}", main_local);
-            eprintln!("next:  {:?}", main_next);
-            eprintln!("failed at row {} of chip {}", i, chip.name());
-            exit(1);
+            row_failures.push((
+                None,
+                "the chip panicked while evaluating its constraints".to_string(),
+            ));
+        }
+        for (constraint, message) in row_failures {
+            failures.push(ConstraintFailure {
+                shard,
+                chip: chip.name(),
+                row: i,
+                constraint,
+                message,
+                local: main_local.iter().map(|v| v.as_canonical_u32()).collect(),
+                next: main_next.iter().map(|v| v.as_canonical_u32()).collect(),
+            });
         }
-    });
+    }
+
+    failures
 }
 
 fn catch_unwind_silent<F: FnOnce() -> R + panic::UnwindSafe, R>(f: F) -> std::thread::Result<R> {
@@ -135,6 +238,10 @@ pub struct DebugConstraintBuilder<'a, F: Field, EF: ExtensionField<F>> {
     pub(crate) is_last_row: F,
     pub(crate) is_transition: F,
     pub(crate) public_values: &'a [F],
+    /// The index of the next constraint to be asserted.
+    pub(crate) constraint_index: usize,
+    /// The index and a description of each constraint that failed.
+    pub(crate) failures: Vec<(usize, String)>,
 }
 
 impl<'a, F, EF> ExtensionBuilder for DebugConstraintBuilder<'a, F, EF>
@@ -150,7 +257,9 @@ where
     where
         I: Into<Self::ExprEF>,
     {
-        assert_eq!(x.into(), EF::zero(), "constraints must evaluate to zero");
+        let x = x.into();
+        let failed = x != EF::zero();
+        self.record_constraint(failed, || format!("{} != 0", x));
     }
 }
 
@@ -188,12 +297,17 @@ where
     EF: ExtensionField<F>,
 {
     #[inline]
-    fn debug_constraint(&self, x: F, y: F) {
-        if x != y {
-            let backtrace = std::backtrace::Backtrace::force_capture();
-            eprintln!("constraint failed: {:?} != {:?}\n{}", x, y, backtrace);
-            panic!();
+    fn debug_constraint(&mut self, x: F, y: F) {
+        self.record_constraint(x != y, || format!("{} != {}", x, y));
+    }
+
+    /// Records the result of the next constraint.
+    #[inline]
+    fn record_constraint(&mut self, failed: bool, message: impl FnOnce() -> String) {
+        if failed {
+            self.failures.push((self.constraint_index, message()));
         }
+        self.constraint_index += 1;
     }
 }
 
@@ -242,11 +356,8 @@ where
     /// Assert that `x` is a boolean, i.e. either 0 or 1.
     fn assert_bool<I: Into<Self::Expr>>(&mut self, x: I) {
         let x = x.into();
-        if x != F::zero() && x != F::one() {
-            let backtrace = std::backtrace::Backtrace::force_capture();
-            eprintln!("constraint failed: {:?} is not a bool\n{}", x, backtrace);
-            panic!();
-        }
+        let failed = x != F::zero() && x != F::one();
+        self.record_constraint(failed, || format!("{} is not a bool", x));
     }
 }
 
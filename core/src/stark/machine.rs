@@ -19,10 +19,11 @@ use std::fmt::Debug;
 use tracing::instrument;
 
 use super::debug_constraints;
+use super::ConstraintDebugReport;
 use super::Dom;
 use crate::air::MachineAir;
 use crate::air::MachineProgram;
-use crate::lookup::debug_interactions_with_all_chips;
+use crate::lookup::interaction_imbalances;
 use crate::lookup::InteractionBuilder;
 use crate::lookup::InteractionKind;
 use crate::stark::record::MachineRecord;
@@ -30,12 +31,14 @@ use crate::stark::DebugConstraintBuilder;
 use crate::stark::ProverConstraintFolder;
 use crate::stark::ShardProof;
 use crate::stark::VerifierConstraintFolder;
+use crate::utils::{ProofProgress, SP1CoreOpts};
 
 use super::Chip;
 use super::Com;
 use super::MachineProof;
 use super::PcsProverData;
 use super::Prover;
+use super::SecurityConfig;
 use super::StarkGenericConfig;
 use super::Val;
 use super::VerificationError;
@@ -90,6 +93,8 @@ pub struct StarkVerifyingKey<SC: StarkGenericConfig> {
     pub pc_start: Val<SC>,
     pub chip_information: Vec<(String, Dom<SC>, Dimensions)>,
     pub chip_ordering: HashMap<String, usize>,
+    /// The FRI parameters the key was generated for, checked against the verifier's.
+    pub security: SecurityConfig,
 }
 
 impl<SC: StarkGenericConfig> StarkVerifyingKey<SC> {
@@ -234,6 +239,7 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                 pc_start,
                 chip_information,
                 chip_ordering,
+                security: self.config.security(),
             },
         )
     }
@@ -273,6 +279,7 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
         pk: &StarkProvingKey<SC>,
         record: A::Record,
         challenger: &mut SC::Challenger,
+        opts: SP1CoreOpts,
     ) -> MachineProof<SC>
     where
         A: for<'a> Air<ProverConstraintFolder<'a, SC>>
@@ -281,10 +288,14 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
             + for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
     {
         let shards = tracing::info_span!("shard_record")
-            .in_scope(|| self.shard(record, &<A::Record as MachineRecord>::Config::default()));
+            .in_scope(|| self.shard(record, &<A::Record as MachineRecord>::Config::from(opts)));
 
+        // `prove_shards` only fails when the proof is cancelled, and the cancellation token of a
+        // default progress is not shared with anything that could cancel it.
+        let progress = ProofProgress::default();
         tracing::info_span!("prove_shards")
-            .in_scope(|| P::prove_shards(self, pk, shards, challenger))
+            .in_scope(|| P::prove_shards(self, pk, shards, challenger, opts, &progress))
+            .expect("a proof with an unshared cancellation token cannot be cancelled")
     }
 
     pub const fn config(&self) -> &SC {
@@ -303,6 +314,15 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
         SC::Challenger: Clone,
         A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
     {
+        // Check that the key was generated for the FRI parameters of this machine.
+        let security = self.config.security();
+        if vk.security != security {
+            return Err(MachineVerificationError::SecurityMismatch {
+                expected: security,
+                found: vk.security,
+            });
+        }
+
         // Observe the preprocessed commitment.
         vk.observe_into(challenger);
         tracing::debug_span!("observe challenges for all shards").in_scope(|| {
@@ -330,7 +350,7 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                         &mut challenger.clone(),
                         shard_proof,
                     )
-                    .map_err(MachineVerificationError::InvalidSegmentProof)
+                    .map_err(|e| MachineVerificationError::InvalidSegmentProof(i, e))
                 })?;
             }
 
@@ -350,13 +370,16 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
         })
     }
 
+    /// Checks the constraints and interactions of every chip of every shard of the record, and
+    /// returns the ones that do not hold.
     #[instrument("debug constraints", level = "debug", skip_all)]
     pub fn debug_constraints(
         &self,
         pk: &StarkProvingKey<SC>,
         record: A::Record,
         challenger: &mut SC::Challenger,
-    ) where
+    ) -> ConstraintDebugReport
+    where
         SC::Val: PrimeField32,
         A: for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
     {
@@ -365,8 +388,9 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
 
         tracing::debug!("checking constraints for each shard");
 
+        let mut report = ConstraintDebugReport::default();
         let mut cumulative_sum = SC::Challenge::zero();
-        for shard in shards.iter() {
+        for (shard_index, shard) in shards.iter().enumerate() {
             // Filter the chips based on what is used.
             let chips = self.shard_chips(shard).collect::<Vec<_>>();
 
@@ -438,46 +462,54 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                         .chip_ordering
                         .get(&chips[i].name())
                         .map(|index| &pk.traces[*index]);
-                    debug_constraints::<SC, A>(
-                        chips[i],
-                        permutation_trace,
-                        &traces[i].0,
-                        &permutation_traces[i],
-                        &permutation_challenges,
-                        shard.public_values(),
-                    );
+                    report
+                        .constraint_failures
+                        .extend(debug_constraints::<SC, A>(
+                            shard_index,
+                            chips[i],
+                            permutation_trace,
+                            &traces[i].0,
+                            &permutation_traces[i],
+                            &permutation_challenges,
+                            shard.public_values(),
+                        ));
                 }
             });
         }
 
-        // If the cumulative sum is not zero, debug the interactions.
+        // If the cumulative sum is not zero, find the unbalanced interactions.
         if !cumulative_sum.is_zero() {
-            debug_interactions_with_all_chips::<SC, A>(
-                self,
-                pk,
-                &shards,
-                InteractionKind::all_kinds(),
-            );
-            panic!("Cumulative sum is not zero");
+            report.nonzero_cumulative_sum = true;
+            report.interaction_imbalances =
+                interaction_imbalances::<SC, A>(self, pk, &shards, InteractionKind::all_kinds());
         }
+
+        report
     }
 }
 
 pub enum MachineVerificationError<SC: StarkGenericConfig> {
-    InvalidSegmentProof(VerificationError<SC>),
+    /// The proof of the shard at the given index in the proof is invalid.
+    InvalidSegmentProof(usize, VerificationError<SC>),
     InvalidGlobalProof(VerificationError<SC>),
     NonZeroCumulativeSum,
     InvalidPublicValuesDigest,
+    InvalidDeferredProofsDigest,
     DebugInteractionsFailed,
     EmptyProof,
     InvalidPublicValues(&'static str),
+    /// The verifying key was generated for different FRI parameters than the verifier's.
+    SecurityMismatch {
+        expected: SecurityConfig,
+        found: SecurityConfig,
+    },
 }
 
 impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            MachineVerificationError::InvalidSegmentProof(e) => {
-                write!(f, "Invalid segment proof: {:?}", e)
+            MachineVerificationError::InvalidSegmentProof(shard, e) => {
+                write!(f, "Invalid segment proof for shard {}: {:?}", shard, e)
             }
             MachineVerificationError::InvalidGlobalProof(e) => {
                 write!(f, "Invalid global proof: {:?}", e)
@@ -488,6 +520,9 @@ impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
             MachineVerificationError::InvalidPublicValuesDigest => {
                 write!(f, "Invalid public values digest")
             }
+            MachineVerificationError::InvalidDeferredProofsDigest => {
+                write!(f, "Invalid deferred proofs digest")
+            }
             MachineVerificationError::EmptyProof => {
                 write!(f, "Empty proof")
             }
@@ -497,13 +532,59 @@ impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
             MachineVerificationError::InvalidPublicValues(s) => {
                 write!(f, "Invalid public values: {}", s)
             }
+            MachineVerificationError::SecurityMismatch { expected, found } => {
+                write!(
+                    f,
+                    "Security mismatch: expected {:?}, found {:?}",
+                    expected, found
+                )
+            }
         }
     }
 }
 
 impl<SC: StarkGenericConfig> std::fmt::Display for MachineVerificationError<SC> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Debug::fmt(self, f)
+        match self {
+            MachineVerificationError::InvalidSegmentProof(shard, e) => {
+                write!(f, "invalid proof for shard {}: {}", shard, e)
+            }
+            MachineVerificationError::InvalidGlobalProof(e) => {
+                write!(f, "invalid global proof: {}", e)
+            }
+            MachineVerificationError::NonZeroCumulativeSum => {
+                write!(
+                    f,
+                    "the cumulative sum of the shards is not zero, their interactions do not balance"
+                )
+            }
+            MachineVerificationError::InvalidPublicValuesDigest => {
+                write!(
+                    f,
+                    "the public values do not match the public values digest committed in the proof"
+                )
+            }
+            MachineVerificationError::InvalidDeferredProofsDigest => {
+                write!(
+                    f,
+                    "the deferred proofs do not match the deferred proofs digest committed in the proof"
+                )
+            }
+            MachineVerificationError::EmptyProof => write!(f, "the proof has no shards"),
+            MachineVerificationError::DebugInteractionsFailed => {
+                write!(f, "debug interactions failed")
+            }
+            MachineVerificationError::InvalidPublicValues(s) => {
+                write!(f, "invalid public values: {}", s)
+            }
+            MachineVerificationError::SecurityMismatch { expected, found } => {
+                write!(
+                    f,
+                    "the verifying key was generated for {} but the verifier uses {}",
+                    found, expected
+                )
+            }
+        }
     }
 }
 
@@ -522,6 +603,7 @@ pub mod tests {
     use crate::runtime::Opcode;
     use crate::runtime::Program;
     use crate::stark::RiscvAir;
+    use crate::stark::StarkGenericConfig;
     use crate::stark::StarkProvingKey;
     use crate::stark::StarkVerifyingKey;
     use crate::utils;
@@ -529,6 +611,7 @@ pub mod tests {
     use crate::utils::run_test;
     use crate::utils::setup_logger;
     use crate::utils::BabyBearPoseidon2;
+    use crate::utils::{ProofProgress, SP1CoreOpts};
 
     #[test]
     fn test_simple_prove() {
@@ -676,7 +759,43 @@ pub mod tests {
         setup_logger();
         let program = fibonacci_program();
         let stdin = SP1Stdin::new();
-        prove(program, &stdin, BabyBearPoseidon2::new()).unwrap();
+        prove(
+            program,
+            &stdin,
+            BabyBearPoseidon2::new(),
+            SP1CoreOpts::default(),
+            &ProofProgress::default(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_fibonacci_prove_pipelined() {
+        setup_logger();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (_, vk) = machine.setup(&fibonacci_program());
+        let mut num_shards = Vec::new();
+        for pipeline_depth in [0, 2] {
+            let opts = SP1CoreOpts {
+                shard_size: 1 << 10,
+                shard_batch_size: 2,
+                pipeline_depth,
+                ..SP1CoreOpts::default()
+            };
+            let (proof, _) = prove(
+                fibonacci_program(),
+                &SP1Stdin::new(),
+                BabyBearPoseidon2::new(),
+                opts,
+                &ProofProgress::default(),
+            )
+            .unwrap();
+            let mut challenger = machine.config().challenger();
+            machine.verify(&vk, &proof, &mut challenger).unwrap();
+            num_shards.push(proof.shard_proofs.len());
+        }
+        assert!(num_shards[0] > 1);
+        assert_eq!(num_shards[0], num_shards[1]);
     }
 
     #[test]
@@ -8,6 +8,9 @@ mod permutation;
 mod prover;
 mod quotient;
 mod record;
+mod security;
+mod stats;
+mod trace_dump;
 mod types;
 mod util;
 mod verifier;
@@ -22,6 +25,9 @@ pub use permutation::*;
 pub use prover::*;
 pub use quotient::*;
 pub use record::*;
+pub use security::*;
+pub use stats::*;
+pub use trace_dump::*;
 pub use types::*;
 pub use verifier::*;
 
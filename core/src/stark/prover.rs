@@ -16,8 +16,9 @@ use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use p3_maybe_rayon::prelude::*;
 use p3_util::log2_strict_usize;
+use web_time::Instant;
 
-use super::{quotient_values, PcsProverData, StarkMachine, Val};
+use super::{quotient_values, ChipStats, PcsProverData, ShardStats, StarkMachine, Val};
 use super::{types::*, StarkGenericConfig};
 use super::{Com, OpeningProof};
 use super::{StarkProvingKey, VerifierConstraintFolder};
@@ -27,7 +28,7 @@ use crate::stark::record::MachineRecord;
 use crate::stark::MachineChip;
 use crate::stark::PackedChallenge;
 use crate::stark::ProverConstraintFolder;
-use crate::utils::env;
+use crate::utils::{ProofCancelled, ProofPhase, ProofProgress, SP1CoreOpts};
 
 fn chunk_vec<T>(mut vec: Vec<T>, chunk_size: usize) -> Vec<Vec<T>> {
     let mut result = Vec::new();
@@ -45,7 +46,9 @@ pub trait Prover<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> {
         pk: &StarkProvingKey<SC>,
         shards: Vec<A::Record>,
         challenger: &mut SC::Challenger,
-    ) -> MachineProof<SC>
+        opts: SP1CoreOpts,
+        progress: &ProofProgress,
+    ) -> Result<MachineProof<SC>, ProofCancelled>
     where
         A: for<'a> Air<ProverConstraintFolder<'a, SC>>
             + Air<InteractionBuilder<Val<SC>>>
@@ -68,7 +71,9 @@ where
         pk: &StarkProvingKey<SC>,
         shards: Vec<A::Record>,
         challenger: &mut SC::Challenger,
-    ) -> MachineProof<SC>
+        opts: SP1CoreOpts,
+        progress: &ProofProgress,
+    ) -> Result<MachineProof<SC>, ProofCancelled>
     where
         A: for<'a> Air<ProverConstraintFolder<'a, SC>>
             + Air<InteractionBuilder<Val<SC>>>
@@ -77,7 +82,7 @@ where
         // Observe the preprocessed commitment.
         pk.observe_into(challenger);
         // Generate and commit the traces for each segment.
-        let (shard_commits, shard_data) = Self::commit_shards(machine, &shards);
+        let (shard_commits, shard_data) = Self::commit_shards(machine, &shards, opts);
 
         // Observe the challenges for each segment.
         tracing::debug_span!("observing all challenges").in_scope(|| {
@@ -92,13 +97,14 @@ where
         });
 
         let finished = AtomicU32::new(0);
+        let num_shards = shards.len();
 
         // Generate a proof for each segment. Note that we clone the challenger so we can observe
         // identical global challenges across the segments.
-        let chunking_multiplier = env::shard_chunking_multiplier();
+        let chunking_multiplier = opts.shard_chunking_multiplier;
         let chunk_size = std::cmp::max(chunking_multiplier * shards.len() / num_cpus::get(), 1);
         let config = machine.config();
-        let reconstruct_commitments = env::reconstruct_commitments();
+        let reconstruct_commitments = opts.reconstruct_commitments;
         let shard_data_chunks = chunk_vec(shard_data, chunk_size);
         let shard_chunks = chunk_vec(shards, chunk_size);
         let parent_span = tracing::debug_span!("open_shards");
@@ -111,6 +117,7 @@ where
                         .into_iter()
                         .zip(shards)
                         .map(|(data, shard)| {
+                            progress.check()?;
                             tracing::debug_span!(parent: &parent_span, "prove shard opening")
                                 .in_scope(|| {
                                     let idx = shard.index() as usize;
@@ -123,24 +130,31 @@ where
                                     let ordering = data.chip_ordering.clone();
                                     let chips =
                                         machine.shard_chips_ordered(&ordering).collect::<Vec<_>>();
-                                    let proof = Self::prove_shard(
+                                    let (proof, stats) = Self::prove_shard(
                                         config,
                                         pk,
                                         &chips,
                                         data,
                                         &mut challenger.clone(),
                                     );
-                                    finished.fetch_add(1, Ordering::Relaxed);
-                                    proof
+                                    progress.report_shard_stats(&stats);
+                                    let done = finished.fetch_add(1, Ordering::Relaxed) + 1;
+                                    progress.report(
+                                        ProofPhase::Prove,
+                                        done as usize,
+                                        Some(num_shards),
+                                    );
+                                    Ok(proof)
                                 })
                         })
-                        .collect::<Vec<_>>()
+                        .collect::<Result<Vec<_>, ProofCancelled>>()
                 })
-                .flatten()
-                .collect::<Vec<_>>()
-        });
+                .collect::<Result<Vec<_>, ProofCancelled>>()
+        })?;
 
-        MachineProof { shard_proofs }
+        Ok(MachineProof {
+            shard_proofs: shard_proofs.into_iter().flatten().collect(),
+        })
     }
 }
 
@@ -175,41 +189,50 @@ where
                     // We need to create an outer span here because, for some reason,
                     // the #[instrument] macro on the chip impl isn't attaching its span to `parent_span`
                     // to avoid the unnecessary span, remove the #[instrument] macro.
+                    let start = Instant::now();
                     let trace =
                         tracing::debug_span!(parent: &parent_span, "generate trace for chip", %chip_name)
                             .in_scope(|| chip.generate_trace(shard, &mut A::Record::default()));
-                    (chip_name, trace)
+                    let stats = ChipStats::new(
+                        chip_name.clone(),
+                        chip.num_rows(shard),
+                        &trace,
+                        start.elapsed(),
+                    );
+                    (chip_name, trace, stats)
                 })
                 .collect::<Vec<_>>()
         });
 
         // Order the chips and traces by trace size (biggest first), and get the ordering map.
-        named_traces.sort_by_key(|(_, trace)| Reverse(trace.height()));
+        named_traces.sort_by_key(|(_, trace, _)| Reverse(trace.height()));
 
         let pcs = config.pcs();
 
         let domains_and_traces = named_traces
             .iter()
-            .map(|(_, trace)| {
+            .map(|(_, trace, _)| {
                 let domain = pcs.natural_domain_for_degree(trace.height());
                 (domain, trace.to_owned())
             })
             .collect::<Vec<_>>();
 
         // Commit to the batch of traces.
+        let start = Instant::now();
         let (main_commit, main_data) = pcs.commit(domains_and_traces);
+        let main_commit_time = start.elapsed();
 
         // Get the chip ordering.
         let chip_ordering = named_traces
             .iter()
             .enumerate()
-            .map(|(i, (name, _))| (name.to_owned(), i))
+            .map(|(i, (name, _, _))| (name.to_owned(), i))
             .collect();
 
-        let traces = named_traces
+        let (traces, chip_stats): (Vec<_>, Vec<_>) = named_traces
             .into_iter()
-            .map(|(_, trace)| trace)
-            .collect::<Vec<_>>();
+            .map(|(_, trace, stats)| (trace, stats))
+            .unzip();
 
         ShardMainData {
             traces,
@@ -218,17 +241,26 @@ where
             chip_ordering,
             index,
             public_values: shard.public_values(),
+            stats: ShardStats {
+                shard: index,
+                chips: chip_stats,
+                main_commit: main_commit_time,
+                ..Default::default()
+            },
         }
     }
 
     /// Prove the program for the given shard and given a commitment to the main data.
+    ///
+    /// Returns the proof along with the statistics of the shard, which extend the ones collected
+    /// while committing to the main data.
     pub fn prove_shard(
         config: &SC,
         pk: &StarkProvingKey<SC>,
         chips: &[&MachineChip<SC, A>],
         mut shard_data: ShardMainData<SC>,
         challenger: &mut SC::Challenger,
-    ) -> ShardProof<SC>
+    ) -> (ShardProof<SC>, ShardStats)
     where
         Val<SC>: PrimeField32,
         SC: Send + Sync,
@@ -239,6 +271,7 @@ where
     {
         // Get the traces.
         let traces = &mut shard_data.traces;
+        let mut stats = std::mem::take(&mut shard_data.stats);
 
         let degrees = traces
             .iter()
@@ -272,6 +305,7 @@ where
             .collect::<Vec<_>>();
 
         // Generate the permutation traces.
+        let start = Instant::now();
         let mut permutation_traces = Vec::with_capacity(chips.len());
         let mut cumulative_sums = Vec::with_capacity(chips.len());
         tracing::debug_span!("generate permutation traces").in_scope(|| {
@@ -312,6 +346,12 @@ where
                 traces[i].height(),
                 total_width * traces[i].height(),
             );
+            if let Some(chip_stats) = stats.chips.get_mut(i) {
+                chip_stats.permutation_width =
+                    permutation_width * <SC::Challenge as AbstractExtensionField<SC::Val>>::D;
+                chip_stats.quotient_width = chips[i].quotient_width()
+                    * <SC::Challenge as AbstractExtensionField<SC::Val>>::D;
+            }
         }
 
         let domains_and_perm_traces =
@@ -331,10 +371,11 @@ where
         let (permutation_commit, permutation_data) =
             tracing::debug_span!("commit to permutation traces")
                 .in_scope(|| pcs.commit(domains_and_perm_traces));
+        stats.permutation_commit = start.elapsed();
         challenger.observe(permutation_commit.clone());
 
         // Compute the quotient polynomial for all chips.
-
+        let start = Instant::now();
         let quotient_domains = trace_domains
             .iter()
             .zip_eq(log_degrees.iter())
@@ -422,6 +463,7 @@ where
 
         let (quotient_commit, quotient_data) = tracing::debug_span!("commit to quotient traces")
             .in_scope(|| pcs.commit(quotient_domains_and_chunks));
+        stats.quotient_commit = start.elapsed();
         challenger.observe(quotient_commit.clone());
 
         // Compute the quotient argument.
@@ -451,6 +493,7 @@ where
             .map(|_| vec![zeta])
             .collect::<Vec<_>>();
 
+        let start = Instant::now();
         let (openings, opening_proof) = tracing::debug_span!("open multi batches").in_scope(|| {
             pcs.open(
                 vec![
@@ -462,6 +505,7 @@ where
                 challenger,
             )
         });
+        stats.opening = start.elapsed();
 
         // Collect the opened values for each chip.
         let [preprocessed_values, main_values, permutation_values, mut quotient_values] =
@@ -525,7 +569,7 @@ where
             )
             .collect::<Vec<_>>();
 
-        ShardProof::<SC> {
+        let proof = ShardProof::<SC> {
             commitment: ShardCommitment {
                 main_commit: shard_data.main_commit.clone(),
                 permutation_commit,
@@ -537,12 +581,96 @@ where
             opening_proof,
             chip_ordering: shard_data.chip_ordering,
             public_values: shard_data.public_values,
+        };
+        (proof, stats)
+    }
+
+    /// Prove a sequence of shards in order, generating and committing to the main traces of the
+    /// next shards on another thread while the current shard is proven.
+    ///
+    /// At most `depth` committed shards wait in a bounded queue to be proven, which bounds the
+    /// memory held by the pipeline. With a depth of 0, each shard is committed and then proven on
+    /// the calling thread. The shards are pulled from `shards` on the committing thread, so any
+    /// work done by the iterator, such as re-executing a checkpoint, is pipelined as well.
+    pub fn prove_shards_pipelined<I>(
+        machine: &StarkMachine<SC, A>,
+        pk: &StarkProvingKey<SC>,
+        shards: I,
+        challenger: &SC::Challenger,
+        depth: usize,
+        num_shards: usize,
+        progress: &ProofProgress,
+    ) -> Result<Vec<ShardProof<SC>>, ProofCancelled>
+    where
+        I: Iterator<Item = A::Record> + Send,
+        Val<SC>: PrimeField32,
+        SC: Send + Sync,
+        ShardMainData<SC>: DeserializeOwned,
+        A: for<'a> Air<ProverConstraintFolder<'a, SC>>
+            + Air<InteractionBuilder<Val<SC>>>
+            + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+    {
+        let config = machine.config();
+        let mut shard_proofs = Vec::new();
+        let mut prove = |data: ShardMainData<SC>| {
+            let chips = machine
+                .shard_chips_ordered(&data.chip_ordering)
+                .collect::<Vec<_>>();
+            let (proof, stats) =
+                Self::prove_shard(config, pk, &chips, data, &mut challenger.clone());
+            progress.report_shard_stats(&stats);
+            shard_proofs.push(proof);
+            progress.report(ProofPhase::Prove, shard_proofs.len(), Some(num_shards));
+        };
+
+        if depth == 0 {
+            for shard in shards {
+                progress.check()?;
+                prove(Self::commit_main(
+                    config,
+                    machine,
+                    &shard,
+                    shard.index() as usize,
+                ));
+            }
+            return Ok(shard_proofs);
         }
+
+        // The committing thread does not inherit the current span, so it is passed explicitly.
+        let parent_span = tracing::Span::current();
+        std::thread::scope(|s| {
+            let (tx, rx) = std::sync::mpsc::sync_channel(depth);
+            s.spawn(move || {
+                for shard in shards {
+                    if progress.check().is_err() {
+                        break;
+                    }
+                    let data =
+                        tracing::debug_span!(parent: &parent_span, "commit main").in_scope(|| {
+                            Self::commit_main(config, machine, &shard, shard.index() as usize)
+                        });
+                    // The receiver is dropped if proving stops early.
+                    if tx.send(data).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            for data in rx {
+                progress.check()?;
+                tracing::debug_span!("prove shard").in_scope(|| prove(data));
+            }
+            // The committing thread stops without an error when the proof is cancelled.
+            progress.check()
+        })?;
+
+        Ok(shard_proofs)
     }
 
     pub fn commit_shards<F, EF>(
         machine: &StarkMachine<SC, A>,
         shards: &[A::Record],
+        opts: SP1CoreOpts,
     ) -> (Vec<Com<SC>>, Vec<ShardMainDataWrapper<SC>>)
     where
         F: PrimeField32,
@@ -556,8 +684,7 @@ where
 
         // Get the number of shards that is the threshold for saving shards to disk instead of
         // keeping all the shards in memory.
-        let reconstruct_commitments = env::reconstruct_commitments();
-        let finished = AtomicU32::new(0);
+        let reconstruct_commitments = opts.reconstruct_commitments;
         let chunk_size = std::cmp::max(shards.len() / num_cpus::get(), 1);
         let parent_span = tracing::debug_span!("commit to all shards");
         let (commitments, shard_main_data): (Vec<_>, Vec<_>) = parent_span.in_scope(|| {
@@ -572,7 +699,6 @@ where
                                     let index = shard.index();
                                     let data =
                                         Self::commit_main(config, machine, shard, index as usize);
-                                    finished.fetch_add(1, Ordering::Relaxed);
                                     let commitment = data.main_commit.clone();
                                     let data = if reconstruct_commitments {
                                         ShardMainDataWrapper::Empty()
@@ -2,8 +2,11 @@ use std::collections::HashMap;
 
 use p3_field::AbstractField;
 
+use crate::utils::SP1CoreOpts;
+
 pub trait MachineRecord: Default + Sized + Send + Sync + Clone {
-    type Config: Default;
+    /// The configuration used to split the record into shards, built from the prover options.
+    type Config: Default + From<SP1CoreOpts>;
 
     fn index(&self) -> u32;
 
@@ -0,0 +1,230 @@
+use std::fmt;
+use std::str::FromStr;
+
+use p3_fri::FriConfig;
+use serde::{Deserialize, Serialize};
+
+/// The default number of proof-of-work bits ground before sampling the FRI query indices.
+pub const DEFAULT_PROOF_OF_WORK_BITS: usize = 16;
+
+/// The assumption under which the soundness of FRI is estimated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SoundnessAssumption {
+    /// The ethSTARK conjecture, under which each query contributes `log_blowup` bits.
+    #[default]
+    Conjectured,
+    /// The list-decoding bound up to the Johnson radius, under which each query contributes
+    /// `log_blowup / 2` bits.
+    Proven,
+}
+
+impl SoundnessAssumption {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            SoundnessAssumption::Conjectured => "conjectured",
+            SoundnessAssumption::Proven => "proven",
+        }
+    }
+
+    /// The bits of security contributed by each query for the given blowup.
+    fn bits_per_query(&self, log_blowup: usize) -> f64 {
+        match self {
+            SoundnessAssumption::Conjectured => log_blowup as f64,
+            SoundnessAssumption::Proven => log_blowup as f64 / 2.0,
+        }
+    }
+}
+
+impl fmt::Display for SoundnessAssumption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SoundnessAssumption {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "conjectured" => Ok(SoundnessAssumption::Conjectured),
+            "proven" => Ok(SoundnessAssumption::Proven),
+            _ => Err(format!(
+                "unknown soundness assumption `{}`, expected one of conjectured or proven",
+                s
+            )),
+        }
+    }
+}
+
+/// The FRI parameters of a STARK configuration, from which its bits of security are estimated.
+///
+/// The estimate counts the query phase and the proof-of-work grinding only. It ignores the
+/// commit phase error and the size of the challenge field, which bound the security of the
+/// BabyBear configurations to roughly 124 bits regardless of the number of queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SecurityConfig {
+    /// The logarithm of the blowup factor of the Reed-Solomon code.
+    pub log_blowup: usize,
+    /// The number of FRI queries.
+    pub num_queries: usize,
+    /// The number of proof-of-work bits ground before sampling the query indices.
+    pub proof_of_work_bits: usize,
+}
+
+impl SecurityConfig {
+    pub const fn new(log_blowup: usize, num_queries: usize, proof_of_work_bits: usize) -> Self {
+        Self {
+            log_blowup,
+            num_queries,
+            proof_of_work_bits,
+        }
+    }
+
+    /// Derives the fewest queries reaching `bits` of security under `assumption` with the given
+    /// blowup and [DEFAULT_PROOF_OF_WORK_BITS] bits of proof-of-work.
+    pub fn for_level(bits: usize, assumption: SoundnessAssumption, log_blowup: usize) -> Self {
+        assert!(log_blowup > 0, "the blowup factor must be at least 2");
+        let query_bits = bits.saturating_sub(DEFAULT_PROOF_OF_WORK_BITS) as f64;
+        let num_queries = (query_bits / assumption.bits_per_query(log_blowup)).ceil() as usize;
+        Self::new(log_blowup, num_queries.max(1), DEFAULT_PROOF_OF_WORK_BITS)
+    }
+
+    /// The parameters of the core and recursion proofs: a blowup of 2 and 100 queries, or the
+    /// number of queries in the `FRI_QUERIES` environment variable.
+    pub fn core() -> Self {
+        Self::new(1, num_queries_from_env(100), DEFAULT_PROOF_OF_WORK_BITS)
+    }
+
+    /// The parameters of the compressed proofs: a blowup of 8 and 33 queries, or the number of
+    /// queries in the `FRI_QUERIES` environment variable.
+    pub fn compressed() -> Self {
+        Self::new(3, num_queries_from_env(33), DEFAULT_PROOF_OF_WORK_BITS)
+    }
+
+    /// Reads the parameters of a FRI configuration.
+    pub fn from_fri_config<M>(config: &FriConfig<M>) -> Self {
+        Self::new(
+            config.log_blowup,
+            config.num_queries,
+            config.proof_of_work_bits,
+        )
+    }
+
+    /// Creates a FRI configuration with these parameters.
+    pub fn fri_config<M>(&self, mmcs: M) -> FriConfig<M> {
+        FriConfig {
+            log_blowup: self.log_blowup,
+            num_queries: self.num_queries,
+            proof_of_work_bits: self.proof_of_work_bits,
+            mmcs,
+        }
+    }
+
+    /// The estimated bits of security under `assumption`.
+    pub fn security_bits(&self, assumption: SoundnessAssumption) -> usize {
+        let query_bits = self.num_queries as f64 * assumption.bits_per_query(self.log_blowup);
+        query_bits.floor() as usize + self.proof_of_work_bits
+    }
+
+    /// The estimated bits of security under the ethSTARK conjecture.
+    pub fn conjectured_bits(&self) -> usize {
+        self.security_bits(SoundnessAssumption::Conjectured)
+    }
+
+    /// The estimated bits of provable security.
+    pub fn proven_bits(&self) -> usize {
+        self.security_bits(SoundnessAssumption::Proven)
+    }
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self::core()
+    }
+}
+
+impl fmt::Display for SecurityConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "~{} bits conjectured, ~{} bits proven (log blowup {}, {} queries, {} proof-of-work bits)",
+            self.conjectured_bits(),
+            self.proven_bits(),
+            self.log_blowup,
+            self.num_queries,
+            self.proof_of_work_bits
+        )
+    }
+}
+
+fn num_queries_from_env(default: usize) -> usize {
+    match std::env::var("FRI_QUERIES") {
+        Ok(value) => value.parse().unwrap(),
+        Err(_) => default,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::Program;
+    use crate::stark::{MachineProof, MachineVerificationError, RiscvAir, StarkGenericConfig};
+    use crate::utils::{tests::FIBONACCI_ELF, BabyBearBlake3, BabyBearKeccak, BabyBearPoseidon2};
+
+    #[test]
+    fn test_security_bits() {
+        let config = SecurityConfig::new(1, 100, 16);
+        assert_eq!(config.conjectured_bits(), 116);
+        assert_eq!(config.proven_bits(), 66);
+
+        let config = SecurityConfig::for_level(100, SoundnessAssumption::Conjectured, 1);
+        assert_eq!(config.num_queries, 84);
+        assert_eq!(config.conjectured_bits(), 100);
+
+        let config = SecurityConfig::for_level(128, SoundnessAssumption::Proven, 3);
+        assert_eq!(config.num_queries, 75);
+        assert!(config.proven_bits() >= 128);
+        assert!(config.conjectured_bits() >= config.proven_bits());
+    }
+
+    #[test]
+    fn test_security_mismatch() {
+        let program = Program::from(FIBONACCI_ELF);
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (_, vk) = machine.setup(&program);
+        assert_eq!(vk.security, machine.config().security());
+
+        let security = SecurityConfig::for_level(128, SoundnessAssumption::Conjectured, 1);
+        let other = RiscvAir::machine(BabyBearPoseidon2::with_security(security));
+        let proof = MachineProof {
+            shard_proofs: vec![],
+        };
+        let result = other.verify(&vk, &proof, &mut other.config().challenger());
+        assert!(matches!(
+            result,
+            Err(MachineVerificationError::SecurityMismatch { expected, .. }) if expected == security
+        ));
+    }
+
+    #[test]
+    fn test_config_serde() {
+        let security = SecurityConfig::for_level(128, SoundnessAssumption::Proven, 2);
+
+        let config = BabyBearPoseidon2::with_security(security);
+        let bytes = bincode::serialize(&config).unwrap();
+        let config: BabyBearPoseidon2 = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(config.security(), security);
+
+        let config = BabyBearKeccak::with_security(security);
+        assert_eq!(config.clone().security(), security);
+        let bytes = bincode::serialize(&config).unwrap();
+        let config: BabyBearKeccak = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(config.security(), security);
+
+        let config = BabyBearBlake3::with_security(security);
+        assert_eq!(config.clone().security(), security);
+        let bytes = bincode::serialize(&config).unwrap();
+        let config: BabyBearBlake3 = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(config.security(), security);
+    }
+}
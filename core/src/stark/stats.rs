@@ -0,0 +1,97 @@
+use std::fmt;
+use std::time::Duration;
+
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+
+/// Statistics about the trace of a chip in a shard, collected while proving the shard.
+///
+/// The traces of a shard are committed together, so commit times are only reported per shard in
+/// [`ShardStats`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChipStats {
+    /// The name of the chip.
+    pub chip: String,
+    /// The number of rows of the trace before padding, if the chip reports it (see
+    /// [`MachineAir::num_rows`](crate::air::MachineAir::num_rows)).
+    pub rows: Option<usize>,
+    /// The height of the trace after padding to a power of two.
+    pub height: usize,
+    /// The number of columns of the main trace.
+    pub main_width: usize,
+    /// The number of columns of the permutation trace, counted in base field elements.
+    pub permutation_width: usize,
+    /// The number of columns of the quotient chunks, counted in base field elements.
+    pub quotient_width: usize,
+    /// The time taken to generate the main trace.
+    pub trace_generation: Duration,
+}
+
+impl ChipStats {
+    /// Creates the statistics of a main trace. The permutation and quotient widths are filled in
+    /// once those traces are computed.
+    pub fn new<F: Field>(
+        chip: String,
+        rows: Option<usize>,
+        trace: &RowMajorMatrix<F>,
+        trace_generation: Duration,
+    ) -> Self {
+        Self {
+            chip,
+            rows,
+            height: trace.height(),
+            main_width: trace.width(),
+            permutation_width: 0,
+            quotient_width: 0,
+            trace_generation,
+        }
+    }
+
+    /// The number of cells committed for the chip in the main and permutation traces.
+    pub fn cells(&self) -> usize {
+        (self.main_width + self.permutation_width) * self.height
+    }
+}
+
+/// Statistics about the proof of a shard.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardStats {
+    /// The index of the shard.
+    pub shard: usize,
+    /// The statistics of the chips included in the shard, ordered by decreasing height.
+    pub chips: Vec<ChipStats>,
+    /// The time taken to commit to the main traces.
+    pub main_commit: Duration,
+    /// The time taken to generate and commit to the permutation traces.
+    pub permutation_commit: Duration,
+    /// The time taken to compute and commit to the quotient chunks.
+    pub quotient_commit: Duration,
+    /// The time taken to open the commitments.
+    pub opening: Duration,
+}
+
+impl fmt::Display for ShardStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "shard {}: main commit {:?}, permutation commit {:?}, quotient commit {:?}, opening {:?}",
+            self.shard, self.main_commit, self.permutation_commit, self.quotient_commit, self.opening
+        )?;
+        for chip in self.chips.iter() {
+            writeln!(
+                f,
+                "{:<20} | Rows = {:<8} | Height = {:<8} | Main Cols = {:<5} | Perm Cols = {:<5} | Quotient Cols = {:<5} | Trace Gen = {:?}",
+                chip.chip,
+                chip.rows.map_or_else(|| "-".to_string(), |rows| rows.to_string()),
+                chip.height,
+                chip.main_width,
+                chip.permutation_width,
+                chip.quotient_width,
+                chip.trace_generation,
+            )?;
+        }
+        Ok(())
+    }
+}
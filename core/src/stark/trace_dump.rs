@@ -0,0 +1,137 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_maybe_rayon::prelude::*;
+
+use super::{StarkGenericConfig, StarkMachine, Val};
+use crate::air::MachineAir;
+
+/// The main trace of a chip in one shard, along with the names of its columns.
+#[derive(Debug, Clone)]
+pub struct ChipTrace<F> {
+    /// The index of the shard the trace belongs to.
+    pub shard: usize,
+    /// The name of the chip.
+    pub chip: String,
+    /// The names of the columns of the trace, one per column.
+    pub column_names: Vec<String>,
+    /// The trace, including its padding rows.
+    pub trace: RowMajorMatrix<F>,
+}
+
+impl<F: PrimeField32> ChipTrace<F> {
+    /// Writes the trace as CSV, with a header row of column names followed by one line per row.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{}", self.column_names.join(","))?;
+        for row in self.trace.rows() {
+            let row = row
+                .map(|value| value.as_canonical_u32().to_string())
+                .collect::<Vec<_>>();
+            writeln!(writer, "{}", row.join(","))?;
+        }
+        writer.flush()
+    }
+
+    /// Saves the trace as CSV to `dir/shard_<shard>/<chip>.csv` and returns the path.
+    pub fn save_csv(&self, dir: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let dir = dir.as_ref().join(format!("shard_{}", self.shard));
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.csv", self.chip));
+        self.write_csv(BufWriter::new(File::create(&path)?))?;
+        Ok(path)
+    }
+}
+
+/// Generates the main traces of the selected chips for every shard.
+///
+/// Chips are selected by name, ignoring case. An empty selection selects every chip. Chips that
+/// have no events in a shard are skipped for that shard.
+pub fn chip_traces<SC, A>(
+    machine: &StarkMachine<SC, A>,
+    shards: &[A::Record],
+    chips: &[String],
+) -> Vec<ChipTrace<Val<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: MachineAir<Val<SC>>,
+{
+    let selected =
+        |name: &str| chips.is_empty() || chips.iter().any(|c| c.eq_ignore_ascii_case(name));
+
+    shards
+        .iter()
+        .enumerate()
+        .flat_map(|(shard_index, shard)| {
+            machine
+                .shard_chips(shard)
+                .filter(|chip| selected(&chip.name()))
+                .collect::<Vec<_>>()
+                .into_par_iter()
+                .map(|chip| {
+                    let trace = chip.generate_trace(shard, &mut A::Record::default());
+                    let column_names = chip.column_names();
+                    debug_assert_eq!(column_names.len(), trace.width());
+                    ChipTrace {
+                        shard: shard_index,
+                        chip: chip.name(),
+                        column_names,
+                        trace,
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use p3_air::BaseAir;
+    use p3_matrix::Matrix;
+
+    use crate::{
+        air::MachineAir,
+        runtime::{Program, Runtime, ShardingConfig},
+        stark::RiscvAir,
+        utils::{tests::FIBONACCI_ELF, BabyBearPoseidon2, SP1CoreOpts},
+    };
+
+    use super::chip_traces;
+
+    #[test]
+    fn test_chip_traces() {
+        let program = Program::from(FIBONACCI_ELF);
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        for chip in machine.chips() {
+            let names = chip.column_names();
+            assert_eq!(names.len(), chip.width(), "{}", chip.name());
+            assert_eq!(
+                names.iter().collect::<HashSet<_>>().len(),
+                names.len(),
+                "{} has duplicate column names",
+                chip.name()
+            );
+        }
+
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let shards = machine.shard(runtime.record, &ShardingConfig::default());
+        let traces = chip_traces(&machine, &shards, &["cpu".to_string()]);
+        assert_eq!(traces.len(), shards.len());
+        let trace = &traces[0];
+        assert_eq!(trace.chip, "CPU");
+        assert!(trace
+            .column_names
+            .contains(&"unsigned_mem_val[0]".to_string()));
+
+        let mut csv = Vec::new();
+        trace.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), trace.trace.height() + 1);
+    }
+}
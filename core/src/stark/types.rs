@@ -13,7 +13,7 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use size::Size;
 use tracing::trace;
 
-use super::{Challenge, Com, OpeningProof, PcsProverData, StarkGenericConfig, Val};
+use super::{Challenge, Com, OpeningProof, PcsProverData, ShardStats, StarkGenericConfig, Val};
 
 pub type QuotientOpenedValues<T> = Vec<T>;
 
@@ -27,6 +27,7 @@ pub struct ShardMainData<SC: StarkGenericConfig> {
     pub chip_ordering: HashMap<String, usize>,
     pub index: usize,
     pub public_values: Vec<SC::Val>,
+    pub stats: ShardStats,
 }
 
 impl<SC: StarkGenericConfig> ShardMainData<SC> {
@@ -37,6 +38,7 @@ impl<SC: StarkGenericConfig> ShardMainData<SC> {
         chip_ordering: HashMap<String, usize>,
         index: usize,
         public_values: Vec<Val<SC>>,
+        stats: ShardStats,
     ) -> Self {
         Self {
             traces,
@@ -45,6 +47,7 @@ impl<SC: StarkGenericConfig> ShardMainData<SC> {
             chip_ordering,
             index,
             public_values,
+            stats,
         }
     }
 
@@ -51,7 +51,12 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> Verifier<SC, A> {
 
         let pcs = config.pcs();
 
-        assert_eq!(chips.len(), opened_values.chips.len());
+        if chips.len() != opened_values.chips.len() {
+            return Err(VerificationError::ChipOpeningLengthMismatch(
+                chips.len(),
+                opened_values.chips.len(),
+            ));
+        }
 
         let log_degrees = opened_values
             .chips
@@ -411,6 +416,8 @@ pub enum VerificationError<SC: StarkGenericConfig> {
     OodEvaluationMismatch(String),
     /// The shape of the opening arguments is invalid.
     OpeningShapeError(String, OpeningShapeError),
+    /// The number of chips opened in the proof does not match the number of chips of the shard.
+    ChipOpeningLengthMismatch(usize, usize),
 }
 
 impl Debug for OpeningShapeError {
@@ -474,6 +481,13 @@ impl<SC: StarkGenericConfig> Debug for VerificationError<SC> {
             VerificationError::OpeningShapeError(chip, e) => {
                 write!(f, "Invalid opening shape for chip {}: {:?}", chip, e)
             }
+            VerificationError::ChipOpeningLengthMismatch(expected, actual) => {
+                write!(
+                    f,
+                    "Chip opening length mismatch: expected {}, got {}",
+                    expected, actual
+                )
+            }
         }
     }
 }
@@ -481,14 +495,33 @@ impl<SC: StarkGenericConfig> Debug for VerificationError<SC> {
 impl<SC: StarkGenericConfig> Display for VerificationError<SC> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
-            VerificationError::InvalidopeningArgument(_) => {
-                write!(f, "Invalid opening argument")
+            VerificationError::InvalidopeningArgument(e) => {
+                write!(
+                    f,
+                    "the opening proof of the trace commitments is invalid: {:?}",
+                    e
+                )
             }
             VerificationError::OodEvaluationMismatch(chip) => {
-                write!(f, "Out-of-domain evaluation mismatch on chip {}", chip)
+                write!(
+                    f,
+                    "the constraints of chip {} do not match its quotient at the out-of-domain point",
+                    chip
+                )
             }
             VerificationError::OpeningShapeError(chip, e) => {
-                write!(f, "Invalid opening shape for chip {}: {}", chip, e)
+                write!(
+                    f,
+                    "the openings of chip {} have the wrong shape: {}",
+                    chip, e
+                )
+            }
+            VerificationError::ChipOpeningLengthMismatch(expected, actual) => {
+                write!(
+                    f,
+                    "the proof opens {} chips, but the shard has {} chips",
+                    actual, expected
+                )
             }
         }
     }
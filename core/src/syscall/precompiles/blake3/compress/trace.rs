@@ -8,6 +8,7 @@ use super::{
     G_INDEX, G_INPUT_SIZE, MSG_SCHEDULE, NUM_MSG_WORDS_PER_CALL, NUM_STATE_WORDS_PER_CALL,
     OPERATION_COUNT,
 };
+use crate::air::ColumnNames;
 use crate::air::MachineAir;
 use crate::bytes::event::ByteRecord;
 use crate::runtime::ExecutionRecord;
@@ -25,6 +26,10 @@ impl<F: PrimeField32> MachineAir<F> for Blake3CompressInnerChip {
         "Blake3CompressInner".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        Blake3CompressInnerCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -121,6 +126,10 @@ impl<F: PrimeField32> MachineAir<F> for Blake3CompressInnerChip {
         )
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.blake3_compress_inner_events.len() * ROUND_COUNT * OPERATION_COUNT)
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.blake3_compress_inner_events.is_empty()
     }
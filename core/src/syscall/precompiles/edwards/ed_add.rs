@@ -17,6 +17,7 @@ use sp1_derive::AlignedBorrow;
 
 use super::{NUM_LIMBS, WORDS_CURVE_POINT};
 use crate::air::BaseAirBuilder;
+use crate::air::ColumnNames;
 use crate::air::MachineAir;
 use crate::air::SP1AirBuilder;
 use crate::bytes::event::ByteRecord;
@@ -142,6 +143,10 @@ impl<F: PrimeField32, E: EllipticCurve + EdwardsParameters> MachineAir<F> for Ed
         "EdAddAssign".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        EdAddAssignCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -224,6 +229,10 @@ impl<F: PrimeField32, E: EllipticCurve + EdwardsParameters> MachineAir<F> for Ed
         )
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.ed_add_events.len())
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.ed_add_events.is_empty()
     }
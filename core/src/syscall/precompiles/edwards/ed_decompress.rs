@@ -18,6 +18,7 @@ use sp1_derive::AlignedBorrow;
 use typenum::U32;
 
 use crate::air::BaseAirBuilder;
+use crate::air::ColumnNames;
 use crate::air::MachineAir;
 use crate::air::SP1AirBuilder;
 use crate::bytes::event::ByteRecord;
@@ -328,6 +329,10 @@ impl<F: PrimeField32, E: EdwardsParameters> MachineAir<F> for EdDecompressChip<E
         "EdDecompress".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        EdDecompressCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -358,6 +363,10 @@ impl<F: PrimeField32, E: EdwardsParameters> MachineAir<F> for EdDecompressChip<E
         )
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.ed_decompress_events.len())
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.ed_decompress_events.is_empty()
     }
@@ -134,7 +134,9 @@ mod test {
     use crate::io::{SP1PublicValues, SP1Stdin};
     use crate::runtime::Program;
     use crate::stark::{RiscvAir, StarkGenericConfig};
-    use crate::utils::{prove, setup_logger, tests::KECCAK256_ELF, BabyBearPoseidon2};
+    use crate::utils::{
+        prove, setup_logger, tests::KECCAK256_ELF, BabyBearPoseidon2, ProofProgress, SP1CoreOpts,
+    };
 
     use rand::Rng;
     use rand::SeedableRng;
@@ -169,7 +171,14 @@ mod test {
         let config = BabyBearPoseidon2::new();
 
         let program = Program::from(KECCAK256_ELF);
-        let (proof, public_values) = prove(program, &stdin, config).unwrap();
+        let (proof, public_values) = prove(
+            program,
+            &stdin,
+            config,
+            SP1CoreOpts::default(),
+            &ProofProgress::default(),
+        )
+        .unwrap();
         let mut public_values = SP1PublicValues::from(&public_values);
 
         let config = BabyBearPoseidon2::new();
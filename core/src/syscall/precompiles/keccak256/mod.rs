@@ -42,7 +42,7 @@ pub mod permute_tests {
     use crate::utils::run_test;
     use crate::{
         runtime::{Instruction, Opcode, Program, Runtime},
-        utils::{self, tests::KECCAK_PERMUTE_ELF},
+        utils::{self, tests::KECCAK_PERMUTE_ELF, SP1CoreOpts},
     };
 
     pub fn keccak_permute_program() -> Program {
@@ -74,7 +74,7 @@ pub mod permute_tests {
     pub fn test_keccak_permute_program_execute() {
         utils::setup_logger();
         let program = keccak_permute_program();
-        let mut runtime = Runtime::new(program);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
         runtime.run().unwrap();
     }
 
@@ -9,7 +9,10 @@ use p3_maybe_rayon::prelude::{ParallelIterator, ParallelSlice};
 use crate::bytes::event::ByteRecord;
 use crate::{runtime::Program, stark::MachineRecord};
 
-use crate::{air::MachineAir, runtime::ExecutionRecord};
+use crate::{
+    air::{ColumnNames, MachineAir},
+    runtime::ExecutionRecord,
+};
 
 use super::{
     columns::{KeccakMemCols, NUM_KECCAK_MEM_COLS},
@@ -24,6 +27,10 @@ impl<F: PrimeField32> MachineAir<F> for KeccakPermuteChip {
         "KeccakPermute".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        KeccakMemCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -145,6 +152,10 @@ impl<F: PrimeField32> MachineAir<F> for KeccakPermuteChip {
         )
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.keccak_permute_events.len() * NUM_ROUNDS)
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.keccak_permute_events.is_empty()
     }
@@ -8,7 +8,7 @@ use super::{
     ShaCompressChip, SHA_COMPRESS_K,
 };
 use crate::{
-    air::{MachineAir, Word},
+    air::{ColumnNames, MachineAir, Word},
     bytes::event::ByteRecord,
     runtime::{ExecutionRecord, Program},
     utils::pad_rows,
@@ -23,6 +23,10 @@ impl<F: PrimeField32> MachineAir<F> for ShaCompressChip {
         "ShaCompress".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        ShaCompressCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -228,6 +232,10 @@ impl<F: PrimeField32> MachineAir<F> for ShaCompressChip {
         )
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.sha_compress_events.len() * 80)
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.sha_compress_events.is_empty()
     }
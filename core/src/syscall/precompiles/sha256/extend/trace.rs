@@ -4,7 +4,7 @@ use p3_field::PrimeField32;
 use p3_matrix::dense::RowMajorMatrix;
 
 use crate::{
-    air::MachineAir,
+    air::{ColumnNames, MachineAir},
     bytes::event::ByteRecord,
     runtime::{ExecutionRecord, Program},
 };
@@ -20,6 +20,10 @@ impl<F: PrimeField32> MachineAir<F> for ShaExtendChip {
         "ShaExtend".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        ShaExtendCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -125,6 +129,10 @@ impl<F: PrimeField32> MachineAir<F> for ShaExtendChip {
         )
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.sha_extend_events.len() * 48)
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.sha_extend_events.is_empty()
     }
@@ -1,3 +1,4 @@
+use crate::air::ColumnNames;
 use crate::air::{BaseAirBuilder, MachineAir, Polynomial, SP1AirBuilder, WORD_SIZE};
 use crate::bytes::event::ByteRecord;
 use crate::memory::{value_as_limbs, MemoryReadCols, MemoryWriteCols};
@@ -96,6 +97,10 @@ impl<F: PrimeField32> MachineAir<F> for Uint256MulChip {
         "Uint256MulMod".to_string()
     }
 
+    fn column_names(&self) -> Vec<String> {
+        Uint256MulCols::<u8>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -191,6 +196,10 @@ impl<F: PrimeField32> MachineAir<F> for Uint256MulChip {
         RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS)
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        Some(input.uint256_mul_events.len())
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         !shard.uint256_mul_events.is_empty()
     }
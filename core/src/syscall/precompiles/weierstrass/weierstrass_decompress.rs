@@ -15,6 +15,7 @@ use std::marker::PhantomData;
 use typenum::Unsigned;
 
 use crate::air::BaseAirBuilder;
+use crate::air::ColumnNames;
 use crate::air::MachineAir;
 use crate::air::SP1AirBuilder;
 use crate::bytes::event::ByteRecord;
@@ -142,6 +143,10 @@ where
         }
     }
 
+    fn column_names(&self) -> Vec<String> {
+        WeierstrassDecompressCols::<u8, E::BaseField>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -207,6 +212,15 @@ where
         )
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        let events = match E::CURVE_TYPE {
+            CurveType::Secp256k1 => &input.k256_decompress_events,
+            CurveType::Bls12381 => &input.bls12381_decompress_events,
+            _ => panic!("Unsupported curve"),
+        };
+        Some(events.len())
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         match E::CURVE_TYPE {
             CurveType::Secp256k1 => !shard.k256_decompress_events.is_empty(),
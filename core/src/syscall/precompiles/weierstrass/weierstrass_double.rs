@@ -16,6 +16,7 @@ use p3_maybe_rayon::prelude::ParallelIterator;
 use p3_maybe_rayon::prelude::ParallelSlice;
 use sp1_derive::AlignedBorrow;
 
+use crate::air::ColumnNames;
 use crate::air::MachineAir;
 use crate::air::SP1AirBuilder;
 use crate::bytes::event::ByteRecord;
@@ -207,6 +208,10 @@ where
         }
     }
 
+    fn column_names(&self) -> Vec<String> {
+        WeierstrassDoubleAssignCols::<u8, E::BaseField>::column_names()
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -291,6 +296,16 @@ where
         )
     }
 
+    fn num_rows(&self, input: &Self::Record) -> Option<usize> {
+        let events = match E::CURVE_TYPE {
+            CurveType::Secp256k1 => &input.secp256k1_double_events,
+            CurveType::Bn254 => &input.bn254_double_events,
+            CurveType::Bls12381 => &input.bls12381_double_events,
+            _ => panic!("Unsupported curve"),
+        };
+        Some(events.len())
+    }
+
     fn included(&self, shard: &Self::Record) -> bool {
         match E::CURVE_TYPE {
             CurveType::Secp256k1 => !shard.secp256k1_double_events.is_empty(),
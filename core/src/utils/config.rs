@@ -1,4 +1,4 @@
-use crate::stark::StarkGenericConfig;
+use crate::stark::{SecurityConfig, StarkGenericConfig};
 use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
 use p3_challenger::DuplexChallenger;
 use p3_commit::ExtensionMmcs;
@@ -149,4 +149,8 @@ impl StarkGenericConfig for BabyBearPoseidon2Inner {
     fn challenger(&self) -> Self::Challenger {
         InnerChallenger::new(self.perm.clone())
     }
+
+    fn security(&self) -> SecurityConfig {
+        SecurityConfig::from_fri_config(self.pcs.fri_config())
+    }
 }
@@ -1,4 +1,4 @@
-use crate::utils::log2_strict_usize;
+use crate::utils::validate_shard_size;
 
 pub const MAX_SHARD_CLK: usize = (1 << 24) - 1;
 
@@ -8,16 +8,15 @@ pub fn shard_size() -> usize {
         Ok(val) => val.parse().unwrap(),
         Err(_) => 1 << 22,
     };
+    check_shard_size(value);
+    value
+}
 
-    if value > MAX_SHARD_CLK {
-        panic!(
-            "Shard size must be at most 2^{} - 1",
-            log2_strict_usize(MAX_SHARD_CLK + 1)
-        );
+/// Checks that the shard size is a power of two that fits in a shard's clock, panicking if not.
+pub fn check_shard_size(value: usize) {
+    if let Err(err) = validate_shard_size(value) {
+        panic!("{}", err);
     }
-
-    assert!(value != 0 && (value & (value - 1)) == 0);
-    value
 }
 
 /// A constant used to determine how many shards get chunked per core on the CPU.
@@ -36,6 +35,15 @@ pub fn reconstruct_commitments() -> bool {
     }
 }
 
+/// Gets the number of shards whose main traces are committed ahead of the shard being proven. If
+/// set to 0, shards are committed and proven one after the other.
+pub fn pipeline_depth() -> usize {
+    match std::env::var("PIPELINE_DEPTH") {
+        Ok(val) => val.parse().unwrap(),
+        Err(_) => 1,
+    }
+}
+
 /// Gets the max number of shards that can go in one batch. If set to 0, there will only be 1 batch.
 ///
 /// The prover will generate the events for a whole batch at once, so this param should be the
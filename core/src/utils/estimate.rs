@@ -0,0 +1,533 @@
+use std::mem::size_of;
+
+use p3_air::BaseAir;
+use p3_baby_bear::BabyBear;
+use p3_field::PrimeField32;
+use p3_keccak_air::NUM_ROUNDS;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::air::MachineAir;
+use crate::alu::AluEvent;
+use crate::bytes::trace::NUM_ROWS as BYTE_NUM_ROWS;
+use crate::cpu::CpuEvent;
+use crate::io::SP1Stdin;
+use crate::runtime::{
+    ExecutionError, ExecutionReport, Opcode, Program, Runtime, ShardingConfig, SyscallCode,
+};
+use crate::stark::{Chip, RiscvAir, SecurityConfig};
+use crate::syscall::precompiles::blake3::{OPERATION_COUNT, ROUND_COUNT};
+use crate::utils::{SP1CoreOpts, SP1CoreOptsError};
+
+/// The degree of the extension field of the permutation and quotient traces.
+pub(crate) const EXTENSION_DEGREE: usize = 4;
+
+/// The minimum number of rows of a trace after padding.
+pub(crate) const MIN_TRACE_ROWS: usize = 16;
+
+/// The number of words of a Merkle tree digest.
+const DIGEST_WORDS: usize = 8;
+
+/// The number of event kinds split across shards by a [ShardingConfig].
+pub(crate) const NUM_CHUNKED_EVENTS: usize = 15;
+
+/// The number of event kinds of the ALU chips, which come first in [chunked_lens].
+pub(crate) const NUM_ALU_EVENTS: usize = 8;
+
+/// The estimated cost of proving the execution of a program with the core prover.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoreEstimate {
+    /// The FRI parameters of the core prover the estimate was made for.
+    pub security: SecurityConfig,
+    /// The estimated shards of the execution.
+    pub shards: Vec<ShardEstimate>,
+    /// The estimated peak memory of the prover, in bytes.
+    pub peak_memory: usize,
+}
+
+/// The estimated traces of a shard.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardEstimate {
+    /// The index of the shard, starting at 1.
+    pub index: u32,
+    /// The number of cycles executed in the shard.
+    pub cycles: u64,
+    /// The chips included in the shard.
+    pub chips: Vec<ChipEstimate>,
+}
+
+/// The estimated trace of a chip in a shard.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChipEstimate {
+    /// The name of the chip.
+    pub name: String,
+    /// The number of rows generated from the events of the shard.
+    pub rows: usize,
+    /// The number of rows of the trace after padding.
+    pub padded_rows: usize,
+    /// The number of columns of the preprocessed trace.
+    pub preprocessed_width: usize,
+    /// The number of columns of the main trace.
+    pub main_width: usize,
+    /// The number of extension field columns of the permutation trace.
+    pub permutation_width: usize,
+    /// The number of extension field columns of the quotient trace.
+    pub quotient_width: usize,
+}
+
+/// A measurement of the core prover taken by the `eval` binary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkSample {
+    /// The program that was proven.
+    pub program: String,
+    /// The hash function of the prover.
+    pub hashfn: String,
+    /// The shard size of the prover.
+    pub shard_size: usize,
+    /// The number of cycles of the program.
+    pub cycles: u64,
+    /// The estimated trace area of the program, see [ShardEstimate::trace_area].
+    pub trace_area: usize,
+    /// The duration of the prover in seconds.
+    pub prove_duration: f64,
+}
+
+/// Measurements of the core prover on the local machine, used to calibrate estimates.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkProfile {
+    pub samples: Vec<BenchmarkSample>,
+}
+
+impl BenchmarkProfile {
+    /// The number of trace cells proven per second with the given hash function, averaged over the
+    /// samples of the profile.
+    pub fn trace_area_per_second(&self, hashfn: &str) -> Option<f64> {
+        let (area, duration) = self
+            .samples
+            .iter()
+            .filter(|sample| sample.hashfn == hashfn)
+            .fold((0f64, 0f64), |(area, duration), sample| {
+                (
+                    area + sample.trace_area as f64,
+                    duration + sample.prove_duration,
+                )
+            });
+        (duration > 0.0).then(|| area / duration)
+    }
+}
+
+impl ChipEstimate {
+    fn new<F: PrimeField32>(chip: &Chip<F, RiscvAir<F>>, rows: usize) -> Self {
+        Self {
+            name: chip.name(),
+            rows,
+            padded_rows: padded_rows(rows),
+            preprocessed_width: chip.preprocessed_width(),
+            main_width: chip.width(),
+            permutation_width: chip.permutation_width(),
+            quotient_width: chip.quotient_width(),
+        }
+    }
+
+    /// The number of cells of the main, permutation and quotient traces, counted in base field
+    /// elements.
+    pub fn trace_area(&self) -> usize {
+        self.padded_rows
+            * (self.main_width + (self.permutation_width + self.quotient_width) * EXTENSION_DEGREE)
+    }
+}
+
+impl ShardEstimate {
+    /// The number of cells of the traces of the shard, counted in base field elements.
+    pub fn trace_area(&self) -> usize {
+        self.chips.iter().map(ChipEstimate::trace_area).sum()
+    }
+
+    /// The size in bytes of the traces of the shard and of their low-degree extensions with the
+    /// given blowup.
+    pub fn trace_bytes(&self, log_blowup: usize) -> usize {
+        self.trace_area() * (1 + (1 << log_blowup)) * size_of::<u32>()
+    }
+
+    /// The estimated size in bytes of the proof of the shard with the given FRI parameters.
+    ///
+    /// Counts the commitments, the opened values of every chip and, for every query, the opened
+    /// rows of each committed batch of traces and the Merkle paths of the FRI commit phase.
+    pub fn proof_size(&self, security: &SecurityConfig) -> usize {
+        let num_queries = security.num_queries;
+        let log_max_height = self
+            .chips
+            .iter()
+            .map(|chip| chip.padded_rows.trailing_zeros() as usize)
+            .max()
+            .unwrap_or(0)
+            + security.log_blowup;
+        let sum_widths =
+            |width: fn(&ChipEstimate) -> usize| -> usize { self.chips.iter().map(width).sum() };
+        let preprocessed = sum_widths(|chip| chip.preprocessed_width);
+        let main = sum_widths(|chip| chip.main_width);
+        let permutation = sum_widths(|chip| chip.permutation_width) * EXTENSION_DEGREE;
+        let quotient = sum_widths(|chip| chip.quotient_width) * EXTENSION_DEGREE;
+
+        let commitments = 3 * DIGEST_WORDS;
+        let opened_values = 2 * (preprocessed + main + permutation) + quotient;
+        let num_batches = if preprocessed > 0 { 4 } else { 3 };
+        let input_openings = preprocessed
+            + main
+            + permutation
+            + quotient
+            + num_batches * log_max_height * DIGEST_WORDS;
+        let num_rounds = log_max_height.saturating_sub(security.log_blowup);
+        let commit_phase = (0..num_rounds)
+            .map(|round| EXTENSION_DEGREE + (log_max_height - round - 1) * DIGEST_WORDS)
+            .sum::<usize>();
+        let fri = num_rounds * DIGEST_WORDS
+            + num_queries * (input_openings + commit_phase)
+            + EXTENSION_DEGREE
+            + 1;
+
+        (commitments + opened_values + fri) * size_of::<u32>()
+    }
+}
+
+/// An error returned when estimating the shards of a program.
+#[derive(Error, Debug)]
+pub enum EstimateError {
+    #[error("failed to execute program: {0}")]
+    ExecutionError(ExecutionError),
+    #[error("invalid prover options: {0}")]
+    InvalidOptions(SP1CoreOptsError),
+}
+
+/// Estimates the shards produced by the core prover for a program, without generating any trace.
+///
+/// The program is executed once without emitting events. The number of rows of each chip is then
+/// derived from the instructions and syscalls executed in each shard, following how the events of
+/// a checkpoint are split into shards by [ShardingConfig]. The counts of the ALU chips are upper
+/// bounds. The size of the low-degree extensions follows the blowup of `security`, which should
+/// be the FRI parameters of the core prover.
+pub fn estimate_core(
+    program: Program,
+    stdin: &SP1Stdin,
+    opts: SP1CoreOpts,
+    security: SecurityConfig,
+) -> Result<CoreEstimate, EstimateError> {
+    opts.validate().map_err(EstimateError::InvalidOptions)?;
+    let mut runtime = Runtime::new(program, opts);
+    runtime.collect_reports = true;
+    runtime.write_vecs(&stdin.buffer);
+    for (proof, vkey) in stdin.proofs.iter() {
+        runtime.write_proof(proof.clone(), vkey.clone());
+    }
+    runtime
+        .run_untraced()
+        .map_err(EstimateError::ExecutionError)?;
+
+    // The runtime starts a new report when it moves to the next shard, so the last one is empty
+    // if the execution ended on a shard boundary.
+    let mut reports = std::mem::take(&mut runtime.shard_reports);
+    if reports.len() > 1 && reports.last().unwrap().total_instruction_count() == 0 {
+        reports.pop();
+    }
+
+    let sharding_config = ShardingConfig::from(opts);
+    let lens = chunk_lens(&sharding_config);
+    let batch_size = if opts.shard_batch_size == 0 {
+        reports.len()
+    } else {
+        opts.shard_batch_size
+    };
+    let num_checkpoints = reports.len().div_ceil(batch_size);
+    let chips = RiscvAir::<BabyBear>::get_all()
+        .into_iter()
+        .map(Chip::new)
+        .collect::<Vec<_>>();
+
+    let mut shards = Vec::with_capacity(reports.len());
+    let mut max_batch_record_bytes = 0;
+    for (checkpoint_idx, checkpoint) in reports.chunks(batch_size).enumerate() {
+        // The chunked events of a checkpoint are split in chunks of the configured length, the
+        // other precompile events go to its first shard and the memory events to its last one.
+        let mut totals = ExecutionReport::default();
+        for report in checkpoint {
+            totals += report;
+        }
+        let total_events = chunked_events(&totals);
+        let mut batch_record_bytes = 0;
+        for (i, report) in checkpoint.iter().enumerate() {
+            let is_last = checkpoint_idx == num_checkpoints - 1 && i == checkpoint.len() - 1;
+            let mut chunked = [0; NUM_CHUNKED_EVENTS];
+            for (events, (total, len)) in chunked.iter_mut().zip(total_events.iter().zip(lens)) {
+                *events = std::cmp::min(len, total.saturating_sub(i * len));
+            }
+            let events = ShardEvents {
+                cycles: report.total_instruction_count() as usize,
+                chunked,
+                precompiles: if i == 0 {
+                    totals.clone()
+                } else {
+                    ExecutionReport::default()
+                },
+                memory_init: if is_last {
+                    runtime.record.memory_initialize_events.len()
+                } else {
+                    0
+                },
+                memory_finalize: if is_last {
+                    runtime.record.memory_finalize_events.len()
+                } else {
+                    0
+                },
+            };
+            batch_record_bytes += events.record_bytes();
+
+            let chips = chips
+                .iter()
+                .map(|chip| ChipEstimate::new(chip, events.rows(chip.air(), &runtime.program)))
+                .filter(|chip| chip.rows > 0)
+                .collect();
+            shards.push(ShardEstimate {
+                index: (shards.len() + 1) as u32,
+                cycles: report.total_instruction_count(),
+                chips,
+            });
+        }
+        max_batch_record_bytes = std::cmp::max(max_batch_record_bytes, batch_record_bytes);
+    }
+
+    // The events of a batch are kept in memory while its shards are proven in parallel. Unless the
+    // commitments are reconstructed, the main traces of every shard are kept until the end.
+    let parallelism = std::cmp::max(
+        num_cpus::get() / std::cmp::max(opts.shard_chunking_multiplier, 1),
+        1,
+    );
+    let max_trace_bytes = shards
+        .iter()
+        .map(|shard| shard.trace_bytes(security.log_blowup))
+        .max()
+        .unwrap_or(0);
+    let commitments = if opts.reconstruct_commitments {
+        0
+    } else {
+        shards
+            .iter()
+            .flat_map(|shard| shard.chips.iter())
+            .map(|chip| {
+                (chip.padded_rows * chip.main_width * size_of::<u32>()) << security.log_blowup
+            })
+            .sum()
+    };
+    let peak_memory = max_batch_record_bytes
+        + std::cmp::min(batch_size, parallelism) * max_trace_bytes
+        + commitments;
+
+    Ok(CoreEstimate {
+        security,
+        shards,
+        peak_memory,
+    })
+}
+
+/// The number of events of each kind in a shard.
+pub(crate) struct ShardEvents {
+    /// The number of cycles of the shard.
+    pub(crate) cycles: usize,
+    /// The number of events of each kind of [chunked_lens].
+    pub(crate) chunked: [usize; NUM_CHUNKED_EVENTS],
+    /// The syscalls whose events are not chunked.
+    pub(crate) precompiles: ExecutionReport,
+    /// The number of memory initialize events.
+    pub(crate) memory_init: usize,
+    /// The number of memory finalize events.
+    pub(crate) memory_finalize: usize,
+}
+
+impl ShardEvents {
+    /// The number of rows generated by a chip from the events of the shard, before padding.
+    ///
+    /// This mirrors the `included` and `generate_trace` methods of the chips: the program, byte
+    /// and program memory tables always have the same size, and the other chips have a fixed
+    /// number of rows per event.
+    pub(crate) fn rows<F: PrimeField32>(&self, air: &RiscvAir<F>, program: &Program) -> usize {
+        let chunked = &self.chunked;
+        let syscalls = |syscall| self.precompiles.syscall_count(syscall) as usize;
+        match air {
+            RiscvAir::Cpu(_) => self.cycles,
+            RiscvAir::Program(_) => program.instructions.len(),
+            RiscvAir::ByteLookup(_) => BYTE_NUM_ROWS,
+            RiscvAir::ProgramMemory(_) => program.memory_image.len(),
+            RiscvAir::MemoryInit(_) => self.memory_init,
+            RiscvAir::MemoryFinal(_) => self.memory_finalize,
+            RiscvAir::Add(_) => chunked[0] + chunked[1],
+            RiscvAir::Mul(_) => chunked[2],
+            RiscvAir::Bitwise(_) => chunked[3],
+            RiscvAir::ShiftLeft(_) => chunked[4],
+            RiscvAir::ShiftRight(_) => chunked[5],
+            RiscvAir::DivRem(_) => chunked[6],
+            RiscvAir::Lt(_) => chunked[7],
+            RiscvAir::KeccakP(_) => chunked[8] * NUM_ROUNDS,
+            RiscvAir::Secp256k1Add(_) => chunked[9],
+            RiscvAir::Secp256k1Double(_) => chunked[10],
+            RiscvAir::Bn254Add(_) => chunked[11],
+            RiscvAir::Bn254Double(_) => chunked[12],
+            RiscvAir::Bls12381Add(_) => chunked[13],
+            RiscvAir::Bls12381Double(_) => chunked[14],
+            RiscvAir::Sha256Extend(_) => 48 * syscalls(SyscallCode::SHA_EXTEND),
+            RiscvAir::Sha256Compress(_) => 80 * syscalls(SyscallCode::SHA_COMPRESS),
+            RiscvAir::Ed25519Add(_) => syscalls(SyscallCode::ED_ADD),
+            RiscvAir::Ed25519Decompress(_) => syscalls(SyscallCode::ED_DECOMPRESS),
+            RiscvAir::K256Decompress(_) => syscalls(SyscallCode::SECP256K1_DECOMPRESS),
+            RiscvAir::Blake3Compress(_) => {
+                ROUND_COUNT * OPERATION_COUNT * syscalls(SyscallCode::BLAKE3_COMPRESS_INNER)
+            }
+            RiscvAir::Uint256Mul(_) => syscalls(SyscallCode::UINT256_MUL),
+            RiscvAir::Bls12381Decompress(_) => syscalls(SyscallCode::BLS12381_DECOMPRESS),
+        }
+    }
+
+    /// The size in bytes of the CPU and ALU events of the shard.
+    pub(crate) fn record_bytes(&self) -> usize {
+        let num_alu_events = self.chunked[..NUM_ALU_EVENTS].iter().sum::<usize>();
+        self.cycles * size_of::<CpuEvent>() + num_alu_events * size_of::<AluEvent>()
+    }
+}
+
+/// The number of rows of a trace with the given number of real rows, after padding.
+pub(crate) fn padded_rows(rows: usize) -> usize {
+    std::cmp::max(rows, MIN_TRACE_ROWS).next_power_of_two()
+}
+
+/// The size in bytes of the main, permutation and quotient traces of a chip and of their
+/// low-degree extensions with the given blowup.
+pub(crate) fn trace_bytes(
+    chip: &Chip<BabyBear, RiscvAir<BabyBear>>,
+    rows: usize,
+    log_blowup: usize,
+) -> usize {
+    if rows == 0 {
+        return 0;
+    }
+    ChipEstimate::new(chip, rows).trace_area() * (1 + (1 << log_blowup)) * size_of::<u32>()
+}
+
+/// The lengths of a [ShardingConfig] used to split events across shards.
+pub(crate) fn chunked_lens(config: &mut ShardingConfig) -> [&mut usize; NUM_CHUNKED_EVENTS] {
+    [
+        &mut config.add_len,
+        &mut config.sub_len,
+        &mut config.mul_len,
+        &mut config.bitwise_len,
+        &mut config.shift_left_len,
+        &mut config.shift_right_len,
+        &mut config.divrem_len,
+        &mut config.lt_len,
+        &mut config.keccak_len,
+        &mut config.secp256k1_add_len,
+        &mut config.secp256k1_double_len,
+        &mut config.bn254_add_len,
+        &mut config.bn254_double_len,
+        &mut config.bls12381_add_len,
+        &mut config.bls12381_double_len,
+    ]
+}
+
+/// The values of [chunked_lens].
+pub(crate) fn chunk_lens(config: &ShardingConfig) -> [usize; NUM_CHUNKED_EVENTS] {
+    let mut config = *config;
+    chunked_lens(&mut config).map(|len| *len)
+}
+
+/// The number of events of each kind of [chunked_lens] emitted by the instructions and syscalls
+/// of a report.
+///
+/// The CPU emits ALU events for memory accesses, branches and jumps on top of the ALU
+/// instructions, and the division chip emits multiplication and comparison events, so the counts
+/// of the ALU events are upper bounds.
+pub(crate) fn chunked_events(report: &ExecutionReport) -> [usize; NUM_CHUNKED_EVENTS] {
+    use Opcode::*;
+    let branches = report.opcode_count(&[BEQ, BNE, BLT, BGE, BLTU, BGEU]);
+    let divrem = report.opcode_count(&[DIV, DIVU, REM, REMU]);
+    [
+        report.opcode_count(&[ADD, LB, LH, LW, LBU, LHU, SB, SH, SW, JAL, JALR, AUIPC]) + branches,
+        report.opcode_count(&[SUB, LB, LH]),
+        report.opcode_count(&[MUL, MULH, MULHU, MULHSU]) + 2 * divrem,
+        report.opcode_count(&[XOR, OR, AND]),
+        report.opcode_count(&[SLL]),
+        report.opcode_count(&[SRL, SRA]),
+        divrem,
+        report.opcode_count(&[SLT, SLTU]) + 2 * branches + divrem,
+        report.syscall_count(SyscallCode::KECCAK_PERMUTE),
+        report.syscall_count(SyscallCode::SECP256K1_ADD),
+        report.syscall_count(SyscallCode::SECP256K1_DOUBLE),
+        report.syscall_count(SyscallCode::BN254_ADD),
+        report.syscall_count(SyscallCode::BN254_DOUBLE),
+        report.syscall_count(SyscallCode::BLS12381_ADD),
+        report.syscall_count(SyscallCode::BLS12381_DOUBLE),
+    ]
+    .map(|count| count as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_matrix::Matrix;
+
+    use super::*;
+    use crate::runtime::ExecutionRecord;
+    use crate::stark::StarkGenericConfig;
+    use crate::utils::tests::FIBONACCI_ELF;
+    use crate::utils::{setup_logger, BabyBearPoseidon2};
+
+    #[test]
+    fn test_estimate_core() {
+        setup_logger();
+        let program = Program::from(FIBONACCI_ELF);
+        let stdin = SP1Stdin::new();
+        let opts = SP1CoreOpts::default();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let security = machine.config().security();
+        let estimate = estimate_core(program.clone(), &stdin, opts, security).unwrap();
+        assert_eq!(estimate.security, security);
+
+        // The estimate has the shards and CPU rows of the traced execution.
+        let mut runtime = Runtime::new(program, opts);
+        runtime.run().unwrap();
+        let shards = machine.shard(runtime.record, &ShardingConfig::from(opts));
+        assert_eq!(estimate.shards.len(), shards.len());
+        for (estimate, shard) in estimate.shards.iter().zip(shards.iter()) {
+            assert_eq!(estimate.index, shard.index);
+            assert_eq!(estimate.cycles as usize, shard.cpu_events.len());
+            for chip in machine.shard_chips(shard) {
+                let padded_rows = estimate
+                    .chips
+                    .iter()
+                    .find(|c| c.name == chip.name())
+                    .map_or(0, |c| c.padded_rows);
+                let trace = chip.generate_trace(shard, &mut ExecutionRecord::default());
+                assert!(padded_rows >= trace.height(), "{}", chip.name());
+            }
+        }
+        assert!(estimate.peak_memory > 0);
+
+        // A larger blowup makes the low-degree extensions and the peak memory larger.
+        let larger = estimate_core(
+            Program::from(FIBONACCI_ELF),
+            &stdin,
+            opts,
+            SecurityConfig::compressed(),
+        )
+        .unwrap();
+        assert!(larger.peak_memory > estimate.peak_memory);
+        let shard = &estimate.shards[0];
+        assert!(shard.trace_bytes(3) > shard.trace_bytes(1));
+
+        // Invalid options are rejected instead of panicking in the runtime.
+        let invalid = SP1CoreOpts {
+            shard_size: 1000,
+            ..opts
+        };
+        assert!(matches!(
+            estimate_core(Program::from(FIBONACCI_ELF), &stdin, invalid, security),
+            Err(EstimateError::InvalidOptions(_))
+        ));
+    }
+}
@@ -2,16 +2,24 @@ mod buffer;
 mod config;
 pub mod ec;
 pub mod env;
+mod estimate;
 mod logger;
+mod options;
 mod programs;
+mod progress;
 mod prove;
 mod tracer;
+mod tune;
 
 pub use buffer::*;
 pub use config::*;
+pub use estimate::*;
 pub use logger::*;
+pub use options::*;
+pub use progress::*;
 pub use prove::*;
 pub use tracer::*;
+pub use tune::*;
 
 #[cfg(test)]
 pub use programs::*;
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::runtime::ShardingConfig;
+use crate::utils::env::{self, MAX_SHARD_CLK};
+
+/// Options for the core prover.
+///
+/// The default values are read from the `SHARD_SIZE`, `SHARD_BATCH_SIZE`,
+/// `SHARD_CHUNKING_MULTIPLIER`, `RECONSTRUCT_COMMITMENTS` and `PIPELINE_DEPTH` environment
+/// variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SP1CoreOpts {
+    /// The number of rows which by default should be used for each chip. Must be a power of two.
+    pub shard_size: usize,
+    /// The max number of shards that are executed and proven in one batch. If set to 0, there
+    /// will only be 1 batch.
+    pub shard_batch_size: usize,
+    /// How many shards get chunked per core on the CPU.
+    pub shard_chunking_multiplier: usize,
+    /// Whether to recreate the shard commitments instead of keeping them in memory.
+    pub reconstruct_commitments: bool,
+    /// The number of shards whose main traces are generated and committed ahead of the shard being
+    /// proven when proving in batches. If set to 0, shards are committed and proven one after the
+    /// other.
+    pub pipeline_depth: usize,
+    /// The number of events of each chip that go in a shard. If `None`, every chip uses
+    /// `shard_size` rows.
+    pub sharding_config: Option<ShardingConfig>,
+}
+
+impl Default for SP1CoreOpts {
+    fn default() -> Self {
+        Self {
+            shard_size: env::shard_size(),
+            shard_batch_size: env::shard_batch_size() as usize,
+            shard_chunking_multiplier: env::shard_chunking_multiplier(),
+            reconstruct_commitments: env::reconstruct_commitments(),
+            pipeline_depth: env::pipeline_depth(),
+            sharding_config: None,
+        }
+    }
+}
+
+impl SP1CoreOpts {
+    /// The options used for proving recursion programs, which keep the shard commitments in
+    /// memory.
+    pub fn recursion() -> Self {
+        Self {
+            reconstruct_commitments: false,
+            ..Self::default()
+        }
+    }
+
+    /// Checks that the options can be used to execute and prove a program.
+    pub fn validate(&self) -> Result<(), SP1CoreOptsError> {
+        validate_shard_size(self.shard_size)?;
+        if let Some(config) = self.sharding_config {
+            validate_shard_size(config.shard_size)?;
+        }
+        Ok(())
+    }
+}
+
+/// An error returned when [SP1CoreOpts] cannot be used to execute and prove a program.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SP1CoreOptsError {
+    #[error("shard size must be a non-zero power of two, got {0}")]
+    ShardSizeNotPowerOfTwo(usize),
+    #[error("shard size must be at most {max}, got {size}")]
+    ShardSizeTooLarge { size: usize, max: usize },
+}
+
+/// Checks that the shard size is a power of two that fits in a shard's clock.
+pub fn validate_shard_size(value: usize) -> Result<(), SP1CoreOptsError> {
+    if value > MAX_SHARD_CLK {
+        return Err(SP1CoreOptsError::ShardSizeTooLarge {
+            size: value,
+            max: MAX_SHARD_CLK,
+        });
+    }
+    if !value.is_power_of_two() {
+        return Err(SP1CoreOptsError::ShardSizeNotPowerOfTwo(value));
+    }
+    Ok(())
+}
+
+/// Records that are not split by size, such as the records of the recursion programs, have no
+/// sharding configuration.
+impl From<SP1CoreOpts> for () {
+    fn from(_: SP1CoreOpts) -> Self {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let opts = SP1CoreOpts {
+            shard_size: 1 << 10,
+            ..SP1CoreOpts::default()
+        };
+        assert_eq!(opts.validate(), Ok(()));
+
+        let opts = SP1CoreOpts {
+            shard_size: 1000,
+            ..opts
+        };
+        assert_eq!(
+            opts.validate(),
+            Err(SP1CoreOptsError::ShardSizeNotPowerOfTwo(1000))
+        );
+
+        let opts = SP1CoreOpts {
+            shard_size: 1 << 24,
+            ..opts
+        };
+        assert!(matches!(
+            opts.validate(),
+            Err(SP1CoreOptsError::ShardSizeTooLarge { .. })
+        ));
+    }
+}
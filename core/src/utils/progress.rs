@@ -0,0 +1,263 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use web_time::Instant;
+
+use crate::stark::ShardStats;
+
+/// A phase of the generation of a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProofPhase {
+    /// Executing the program and saving checkpoints.
+    Execute,
+    /// Generating and committing to the main traces of the shards.
+    Commit,
+    /// Proving the shards of the core proof.
+    Prove,
+    /// Proving the nodes of the reduction tree of the compressed proof.
+    Compress,
+    /// Proving the shrink program.
+    Shrink,
+    /// Proving the wrap program over the BN254 field.
+    Wrap,
+}
+
+/// A progress update reported to a [ProgressSink].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgressEvent {
+    /// The phase the update belongs to.
+    pub phase: ProofPhase,
+    /// The number of steps of the phase done, such as shards proven or checkpoints executed.
+    pub index: usize,
+    /// The number of steps of the phase, if it is known in advance.
+    pub total: Option<usize>,
+    /// The time elapsed since the start of the proof.
+    pub elapsed: Duration,
+}
+
+/// Receives the progress of a proof.
+pub trait ProgressSink: Send + Sync {
+    fn on_progress(&self, event: &ProgressEvent);
+
+    /// Receives the statistics of each shard of the core proof once it is proven, in the order
+    /// the shards finish. Ignored by default.
+    fn on_shard_stats(&self, _stats: &ShardStats) {}
+}
+
+impl<F: Fn(&ProgressEvent) + Send + Sync> ProgressSink for F {
+    fn on_progress(&self, event: &ProgressEvent) {
+        self(event)
+    }
+}
+
+/// A token used to cancel a proof from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests the cancellation of the proofs using this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The error returned by a proof cancelled with a [CancellationToken].
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("the proof was cancelled")]
+pub struct ProofCancelled;
+
+/// Reports the progress of a proof and checks whether it was cancelled.
+///
+/// The provers check the token between shards and between the nodes of the reduction tree, so a
+/// cancelled proof stops once the work in flight is done.
+#[derive(Clone)]
+pub struct ProofProgress {
+    sink: Option<Arc<dyn ProgressSink>>,
+    token: CancellationToken,
+    start: Instant,
+}
+
+impl ProofProgress {
+    pub fn new() -> Self {
+        Self {
+            sink: None,
+            token: CancellationToken::new(),
+            start: Instant::now(),
+        }
+    }
+
+    /// Sets the sink receiving the progress updates.
+    pub fn with_sink(mut self, sink: impl ProgressSink + 'static) -> Self {
+        self.sink = Some(Arc::new(sink));
+        self
+    }
+
+    /// Sets the token used to cancel the proof.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Returns a copy of this handle whose elapsed time is measured from now, for reusing the same
+    /// sink and token across proofs.
+    pub fn restarted(&self) -> Self {
+        Self {
+            start: Instant::now(),
+            ..self.clone()
+        }
+    }
+
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Returns an error if the proof was cancelled.
+    pub fn check(&self) -> Result<(), ProofCancelled> {
+        if self.token.is_cancelled() {
+            Err(ProofCancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Reports that `index` steps of the given phase are done.
+    pub fn report(&self, phase: ProofPhase, index: usize, total: Option<usize>) {
+        if let Some(sink) = &self.sink {
+            sink.on_progress(&ProgressEvent {
+                phase,
+                index,
+                total,
+                elapsed: self.start.elapsed(),
+            });
+        }
+    }
+
+    /// Reports the statistics of a proven shard.
+    pub fn report_shard_stats(&self, stats: &ShardStats) {
+        if let Some(sink) = &self.sink {
+            sink.on_shard_stats(stats);
+        }
+    }
+}
+
+impl Default for ProofProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ProofProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProofProgress")
+            .field("has_sink", &self.sink.is_some())
+            .field("token", &self.token)
+            .field("start", &self.start)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::io::SP1Stdin;
+    use crate::runtime::Program;
+    use crate::utils::tests::FIBONACCI_ELF;
+    use crate::utils::{prove, setup_logger, BabyBearPoseidon2, SP1CoreOpts, SP1CoreProverError};
+
+    #[test]
+    fn test_progress_events() {
+        setup_logger();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink_events = events.clone();
+        let progress = ProofProgress::new()
+            .with_sink(move |event: &ProgressEvent| sink_events.lock().unwrap().push(*event));
+        let program = Program::from(FIBONACCI_ELF);
+        let (proof, _) = prove(
+            program,
+            &SP1Stdin::new(),
+            BabyBearPoseidon2::new(),
+            SP1CoreOpts::default(),
+            &progress,
+        )
+        .unwrap();
+
+        let events = events.lock().unwrap();
+        let last = events
+            .iter()
+            .filter(|event| event.phase == ProofPhase::Prove)
+            .last()
+            .unwrap();
+        assert_eq!(last.index, proof.shard_proofs.len());
+        assert_eq!(last.total, Some(proof.shard_proofs.len()));
+    }
+
+    struct StatsSink(Mutex<Vec<ShardStats>>);
+
+    impl ProgressSink for StatsSink {
+        fn on_progress(&self, _event: &ProgressEvent) {}
+
+        fn on_shard_stats(&self, stats: &ShardStats) {
+            self.0.lock().unwrap().push(stats.clone());
+        }
+    }
+
+    #[test]
+    fn test_shard_stats() {
+        setup_logger();
+        let sink = Arc::new(StatsSink(Mutex::new(Vec::new())));
+        let progress = ProofProgress {
+            sink: Some(sink.clone()),
+            ..ProofProgress::new()
+        };
+        let program = Program::from(FIBONACCI_ELF);
+        let (proof, _) = prove(
+            program,
+            &SP1Stdin::new(),
+            BabyBearPoseidon2::new(),
+            SP1CoreOpts::default(),
+            &progress,
+        )
+        .unwrap();
+
+        let stats = sink.0.lock().unwrap();
+        assert_eq!(stats.len(), proof.shard_proofs.len());
+        let cpu = stats[0]
+            .chips
+            .iter()
+            .find(|chip| chip.chip == "CPU")
+            .unwrap();
+        assert!(cpu.rows.is_some_and(|rows| rows > 0 && rows <= cpu.height));
+        assert!(cpu.permutation_width > 0 && cpu.quotient_width > 0);
+    }
+
+    #[test]
+    fn test_cancelled_proof() {
+        setup_logger();
+        let token = CancellationToken::new();
+        token.cancel();
+        let progress = ProofProgress::new().with_cancellation_token(token);
+        let program = Program::from(FIBONACCI_ELF);
+        let result = prove(
+            program,
+            &SP1Stdin::new(),
+            BabyBearPoseidon2::new(),
+            SP1CoreOpts::default(),
+            &progress,
+        );
+        assert!(matches!(result, Err(SP1CoreProverError::Cancelled(_))));
+    }
+}
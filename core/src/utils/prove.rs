@@ -1,6 +1,7 @@
 use std::fs::File;
 use std::io;
 use std::io::{Seek, Write};
+use std::path::{Path, PathBuf};
 use web_time::Instant;
 
 pub use baby_bear_blake3::BabyBearBlake3;
@@ -16,15 +17,18 @@ use crate::io::{SP1PublicValues, SP1Stdin};
 use crate::lookup::InteractionBuilder;
 use crate::runtime::ExecutionError;
 use crate::runtime::{ExecutionRecord, ShardingConfig};
+use crate::stark::chip_traces;
 use crate::stark::DebugConstraintBuilder;
 use crate::stark::MachineProof;
+use crate::stark::Prover;
 use crate::stark::ProverConstraintFolder;
+use crate::stark::SecurityConfig;
 use crate::stark::StarkVerifyingKey;
 use crate::stark::Val;
 use crate::stark::VerifierConstraintFolder;
-use crate::stark::{Com, PcsProverData, RiscvAir, ShardProof, StarkProvingKey, UniConfig};
+use crate::stark::{Com, PcsProverData, RiscvAir, StarkProvingKey, UniConfig};
 use crate::stark::{MachineRecord, StarkMachine};
-use crate::utils::env;
+use crate::utils::{ProofCancelled, ProofPhase, ProofProgress, SP1CoreOpts, SP1CoreOptsError};
 use crate::{
     runtime::{Program, Runtime},
     stark::StarkGenericConfig,
@@ -41,11 +45,22 @@ pub enum SP1CoreProverError {
     IoError(io::Error),
     #[error("serialization error: {0}")]
     SerializationError(bincode::Error),
+    #[error("{0}")]
+    Cancelled(ProofCancelled),
+    #[error("invalid prover options: {0}")]
+    InvalidOptions(SP1CoreOptsError),
+    #[error("the proving key was generated for {found} but the prover uses {expected}")]
+    SecurityMismatch {
+        expected: SecurityConfig,
+        found: SecurityConfig,
+    },
 }
 
 pub fn prove_simple<SC: StarkGenericConfig>(
     config: SC,
     runtime: Runtime,
+    opts: SP1CoreOpts,
+    progress: &ProofProgress,
 ) -> Result<MachineProof<SC>, SP1CoreProverError>
 where
     SC::Challenger: Clone,
@@ -58,11 +73,39 @@ where
     // Setup the machine.
     let machine = RiscvAir::machine(config);
     let (pk, _) = machine.setup(runtime.program.as_ref());
+    prove_simple_with_pk(&machine, &pk, runtime, opts, progress)
+}
+
+/// Proves an executed runtime like [prove_simple], reusing a proving key of its program instead of
+/// running the setup.
+pub fn prove_simple_with_pk<SC: StarkGenericConfig>(
+    machine: &StarkMachine<SC, RiscvAir<Val<SC>>>,
+    pk: &StarkProvingKey<SC>,
+    runtime: Runtime,
+    opts: SP1CoreOpts,
+    progress: &ProofProgress,
+) -> Result<MachineProof<SC>, SP1CoreProverError>
+where
+    SC::Challenger: Clone,
+    OpeningProof<SC>: Send + Sync,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    ShardMainData<SC>: Serialize + DeserializeOwned,
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+{
+    opts.validate()
+        .map_err(SP1CoreProverError::InvalidOptions)?;
 
-    // Prove the program.
+    // Shard the record with the sharding configuration of the options and prove the program.
     let mut challenger = machine.config().challenger();
     let proving_start = Instant::now();
-    let proof = machine.prove::<LocalProver<_, _>>(&pk, runtime.record, &mut challenger);
+    let shards = tracing::info_span!("shard_record")
+        .in_scope(|| machine.shard(runtime.record, &ShardingConfig::from(opts)));
+    let proof = tracing::info_span!("prove_shards")
+        .in_scope(|| {
+            LocalProver::prove_shards(machine, pk, shards, &mut challenger, opts, progress)
+        })
+        .map_err(SP1CoreProverError::Cancelled)?;
     let proving_duration = proving_start.elapsed().as_millis();
     let nb_bytes = bincode::serialize(&proof).unwrap().len();
 
@@ -82,6 +125,37 @@ pub fn prove<SC: StarkGenericConfig + Send + Sync>(
     program: Program,
     stdin: &SP1Stdin,
     config: SC,
+    opts: SP1CoreOpts,
+    progress: &ProofProgress,
+) -> Result<(MachineProof<SC>, Vec<u8>), SP1CoreProverError>
+where
+    SC::Challenger: Clone,
+    OpeningProof<SC>: Send + Sync,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    ShardMainData<SC>: Serialize + DeserializeOwned,
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+{
+    // Setup the machine.
+    opts.validate()
+        .map_err(SP1CoreProverError::InvalidOptions)?;
+    let machine = RiscvAir::machine(config);
+    let (pk, _) = machine.setup(&program);
+    prove_with_pk(&machine, &pk, program, stdin, opts, progress)
+}
+
+/// Proves a program like [prove], reusing a proving key of the program instead of running the
+/// setup.
+///
+/// The proving key must have been generated by `machine` for `program`, otherwise the proof will
+/// not verify.
+pub fn prove_with_pk<SC: StarkGenericConfig + Send + Sync>(
+    machine: &StarkMachine<SC, RiscvAir<Val<SC>>>,
+    pk: &StarkProvingKey<SC>,
+    program: Program,
+    stdin: &SP1Stdin,
+    opts: SP1CoreOpts,
+    progress: &ProofProgress,
 ) -> Result<(MachineProof<SC>, Vec<u8>), SP1CoreProverError>
 where
     SC::Challenger: Clone,
@@ -91,21 +165,19 @@ where
     ShardMainData<SC>: Serialize + DeserializeOwned,
     <SC as StarkGenericConfig>::Val: PrimeField32,
 {
+    opts.validate()
+        .map_err(SP1CoreProverError::InvalidOptions)?;
     let proving_start = Instant::now();
 
     // Execute the program.
-    let mut runtime = Runtime::new(program.clone());
+    let mut runtime = Runtime::new(program.clone(), opts);
     runtime.write_vecs(&stdin.buffer);
     for proof in stdin.proofs.iter() {
         runtime.write_proof(proof.0.clone(), proof.1.clone());
     }
 
-    // Setup the machine.
-    let machine = RiscvAir::machine(config);
-    let (pk, vk) = machine.setup(runtime.program.as_ref());
-
     // If we don't need to batch, we can just run the program normally and prove it.
-    if env::shard_batch_size() == 0 {
+    if opts.shard_batch_size == 0 {
         // Execute the runtime and collect all the events..
         runtime.run().map_err(SP1CoreProverError::ExecutionError)?;
 
@@ -113,12 +185,13 @@ where
         #[cfg(feature = "debug")]
         {
             let mut challenger = machine.config().challenger();
-            machine.debug_constraints(&pk, runtime.record.clone(), &mut challenger);
+            let report = machine.debug_constraints(pk, runtime.record.clone(), &mut challenger);
+            check_debug_report(&report);
         }
 
         // Generate the proof and return the proof and public values.
         let public_values = std::mem::take(&mut runtime.state.public_values_stream);
-        let proof = prove_simple(machine.config().clone(), runtime)?;
+        let proof = prove_simple_with_pk(machine, pk, runtime, opts, progress)?;
         return Ok((proof, public_values));
     }
 
@@ -126,6 +199,7 @@ where
     let mut checkpoints = Vec::new();
     let (public_values_stream, public_values) = loop {
         // Execute the runtime until we reach a checkpoint.
+        progress.check().map_err(SP1CoreProverError::Cancelled)?;
         let (checkpoint, done) = runtime
             .execute_state()
             .map_err(SP1CoreProverError::ExecutionError)?;
@@ -141,6 +215,7 @@ where
             .seek(std::io::SeekFrom::Start(0))
             .map_err(SP1CoreProverError::IoError)?;
         checkpoints.push(tempfile);
+        progress.report(ProofPhase::Execute, checkpoints.len(), None);
 
         // If we've reached the final checkpoint, break out of the loop.
         if done {
@@ -152,12 +227,14 @@ where
     };
 
     // For each checkpoint, generate events, shard them, commit shards, and observe in challenger.
-    let sharding_config = ShardingConfig::default();
+    let sharding_config = ShardingConfig::from(opts);
     let mut shard_main_datas = Vec::new();
     let mut challenger = machine.config().challenger();
-    vk.observe_into(&mut challenger);
+    let mut num_shards = 0;
+    pk.observe_into(&mut challenger);
     for checkpoint_file in checkpoints.iter_mut() {
-        let mut record = trace_checkpoint(program.clone(), checkpoint_file);
+        progress.check().map_err(SP1CoreProverError::Cancelled)?;
+        let mut record = trace_checkpoint(program.clone(), checkpoint_file, opts);
         record.public_values = public_values;
         reset_seek(&mut *checkpoint_file);
 
@@ -167,7 +244,7 @@ where
 
         // Commit to each shard.
         let (commitments, commit_data) = tracing::info_span!("commit")
-            .in_scope(|| LocalProver::commit_shards(&machine, &checkpoint_shards));
+            .in_scope(|| LocalProver::commit_shards(machine, &checkpoint_shards, opts));
         shard_main_datas.push(commit_data);
 
         // Observe the commitments.
@@ -175,40 +252,28 @@ where
             challenger.observe(commitment);
             challenger.observe_slice(&shard.public_values::<SC::Val>()[0..machine.num_pv_elts()]);
         }
+        num_shards += checkpoint_shards.len();
+        progress.report(ProofPhase::Commit, num_shards, None);
     }
 
-    // For each checkpoint, generate events and shard again, then prove the shards.
-    let mut shard_proofs = Vec::<ShardProof<SC>>::new();
-    for mut checkpoint_file in checkpoints.into_iter() {
-        let checkpoint_shards = {
-            let mut events = trace_checkpoint(program.clone(), &checkpoint_file);
-            events.public_values = public_values;
-            reset_seek(&mut checkpoint_file);
-            tracing::debug_span!("shard").in_scope(|| machine.shard(events, &sharding_config))
-        };
-        let mut checkpoint_proofs = checkpoint_shards
-            .into_iter()
-            .map(|shard| {
-                let config = machine.config();
-                let shard_data =
-                    LocalProver::commit_main(config, &machine, &shard, shard.index() as usize);
-
-                let chip_ordering = shard_data.chip_ordering.clone();
-                let ordered_chips = machine
-                    .shard_chips_ordered(&chip_ordering)
-                    .collect::<Vec<_>>()
-                    .to_vec();
-                LocalProver::prove_shard(
-                    config,
-                    &pk,
-                    &ordered_chips,
-                    shard_data,
-                    &mut challenger.clone(),
-                )
-            })
-            .collect::<Vec<_>>();
-        shard_proofs.append(&mut checkpoint_proofs);
-    }
+    // For each checkpoint, generate events and shard again, then prove the shards. The traces of
+    // the next shards are generated and committed while the current shard is proven.
+    let shards = checkpoints.into_iter().flat_map(|mut checkpoint_file| {
+        let mut events = trace_checkpoint(program.clone(), &checkpoint_file, opts);
+        events.public_values = public_values;
+        reset_seek(&mut checkpoint_file);
+        tracing::debug_span!("shard").in_scope(|| machine.shard(events, &sharding_config))
+    });
+    let shard_proofs = LocalProver::prove_shards_pipelined(
+        machine,
+        pk,
+        shards,
+        &challenger,
+        opts.pipeline_depth,
+        num_shards,
+        progress,
+    )
+    .map_err(SP1CoreProverError::Cancelled)?;
     let proof = MachineProof::<SC> { shard_proofs };
 
     // Print the summary.
@@ -224,13 +289,51 @@ where
     Ok((proof, public_values_stream))
 }
 
+/// Executes a program and saves the main traces of the selected chips of every shard as CSV files
+/// under `dir`, returning their paths. CSV is the only supported format.
+///
+/// Chips are selected by name (for example `CPU` or `MemoryInit`), and an empty selection saves
+/// every chip. See [`chip_traces`] for the traces themselves.
+pub fn dump_traces<SC: StarkGenericConfig>(
+    program: Program,
+    stdin: &SP1Stdin,
+    config: SC,
+    opts: SP1CoreOpts,
+    chips: &[String],
+    dir: &Path,
+) -> Result<Vec<PathBuf>, SP1CoreProverError>
+where
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+{
+    opts.validate()
+        .map_err(SP1CoreProverError::InvalidOptions)?;
+    let mut runtime = Runtime::new(program, opts);
+    runtime.write_vecs(&stdin.buffer);
+    for proof in stdin.proofs.iter() {
+        runtime.write_proof(proof.0.clone(), proof.1.clone());
+    }
+    runtime.run().map_err(SP1CoreProverError::ExecutionError)?;
+
+    let machine = RiscvAir::machine(config);
+    let shards = tracing::info_span!("shard_record")
+        .in_scope(|| machine.shard(runtime.record, &ShardingConfig::from(opts)));
+    let traces =
+        tracing::info_span!("generate traces").in_scope(|| chip_traces(&machine, &shards, chips));
+
+    traces
+        .iter()
+        .map(|trace| trace.save_csv(dir))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(SP1CoreProverError::IoError)
+}
+
 /// Runs a program and returns the public values stream.
 pub fn run_test_io(
     program: Program,
     inputs: SP1Stdin,
 ) -> Result<SP1PublicValues, crate::stark::MachineVerificationError<BabyBearPoseidon2>> {
     let runtime = tracing::info_span!("runtime.run(...)").in_scope(|| {
-        let mut runtime = Runtime::new(program);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
         runtime.write_vecs(&inputs.buffer);
         runtime.run().unwrap();
         runtime
@@ -247,7 +350,7 @@ pub fn run_test(
     crate::stark::MachineVerificationError<BabyBearPoseidon2>,
 > {
     let runtime = tracing::info_span!("runtime.run(...)").in_scope(|| {
-        let mut runtime = Runtime::new(program);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
         runtime.run().unwrap();
         runtime
     });
@@ -294,14 +397,16 @@ where
     {
         let mut challenger_clone = machine.config().challenger();
         let record_clone = record.clone();
-        machine.debug_constraints(&pk, record_clone, &mut challenger_clone);
+        let report = machine.debug_constraints(&pk, record_clone, &mut challenger_clone);
+        check_debug_report(&report);
     }
     let stats = record.stats().clone();
     let cycles = stats.get("cpu_events").unwrap();
 
     let start = Instant::now();
     let mut challenger = machine.config().challenger();
-    let proof = machine.prove::<LocalProver<SC, A>>(&pk, record, &mut challenger);
+    let proof =
+        machine.prove::<LocalProver<SC, A>>(&pk, record, &mut challenger, SP1CoreOpts::default());
     let time = start.elapsed().as_millis();
     let nb_bytes = bincode::serialize(&proof).unwrap().len();
 
@@ -319,10 +424,26 @@ where
     Ok(proof)
 }
 
-fn trace_checkpoint(program: Program, file: &File) -> ExecutionRecord {
+/// Panics with the report if a constraint or an interaction does not hold, after writing it to the
+/// JSON file at `SP1_DEBUG_REPORT` if it is set.
+#[cfg(feature = "debug")]
+fn check_debug_report(report: &crate::stark::ConstraintDebugReport) {
+    if report.is_ok() {
+        return;
+    }
+    if let Ok(path) = std::env::var("SP1_DEBUG_REPORT") {
+        match report.save_json(&path) {
+            Ok(()) => tracing::info!("wrote the constraint debug report to {}", path),
+            Err(e) => tracing::error!("failed to write the constraint debug report: {}", e),
+        }
+    }
+    panic!("constraints do not hold:\n{}", report);
+}
+
+fn trace_checkpoint(program: Program, file: &File, opts: SP1CoreOpts) -> ExecutionRecord {
     let mut reader = std::io::BufReader::new(file);
     let state = bincode::deserialize_from(&mut reader).expect("failed to deserialize state");
-    let mut runtime = Runtime::recover(program.clone(), state);
+    let mut runtime = Runtime::recover(program.clone(), state, opts);
     let (events, _) =
         tracing::debug_span!("runtime.trace").in_scope(|| runtime.execute_record().unwrap());
     events
@@ -418,7 +539,7 @@ pub mod baby_bear_poseidon2 {
     use serde::{Deserialize, Serialize};
     use sp1_primitives::RC_16_30;
 
-    use crate::stark::StarkGenericConfig;
+    use crate::stark::{SecurityConfig, StarkGenericConfig};
 
     pub type Val = BabyBear;
     pub type Challenge = BinomialExtensionField<Val, 4>;
@@ -460,90 +581,55 @@ pub mod baby_bear_poseidon2 {
     }
 
     pub fn default_fri_config() -> FriConfig<ChallengeMmcs> {
-        let perm = my_perm();
-        let hash = MyHash::new(perm.clone());
-        let compress = MyCompress::new(perm.clone());
-        let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
-        let num_queries = match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 100,
-        };
-        FriConfig {
-            log_blowup: 1,
-            num_queries,
-            proof_of_work_bits: 16,
-            mmcs: challenge_mmcs,
-        }
+        fri_config(&SecurityConfig::core())
     }
 
     pub fn compressed_fri_config() -> FriConfig<ChallengeMmcs> {
+        fri_config(&SecurityConfig::compressed())
+    }
+
+    /// The FRI configuration with the given parameters.
+    pub fn fri_config(security: &SecurityConfig) -> FriConfig<ChallengeMmcs> {
         let perm = my_perm();
         let hash = MyHash::new(perm.clone());
         let compress = MyCompress::new(perm.clone());
         let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
-        let num_queries = match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 33,
-        };
-        FriConfig {
-            log_blowup: 3,
-            num_queries,
-            proof_of_work_bits: 16,
-            mmcs: challenge_mmcs,
-        }
-    }
-
-    enum BabyBearPoseidon2Type {
-        Default,
-        Compressed,
+        security.fri_config(challenge_mmcs)
     }
 
     #[derive(Deserialize)]
-    #[serde(from = "std::marker::PhantomData<BabyBearPoseidon2>")]
+    #[serde(from = "SecurityConfig")]
     pub struct BabyBearPoseidon2 {
         pub perm: Perm,
         pcs: Pcs,
-        config_type: BabyBearPoseidon2Type,
     }
 
     impl BabyBearPoseidon2 {
         pub fn new() -> Self {
-            let perm = my_perm();
-            let hash = MyHash::new(perm.clone());
-            let compress = MyCompress::new(perm.clone());
-            let val_mmcs = ValMmcs::new(hash, compress);
-            let dft = Dft {};
-            let fri_config = default_fri_config();
-            let pcs = Pcs::new(27, dft, val_mmcs, fri_config);
-            Self {
-                pcs,
-                perm,
-                config_type: BabyBearPoseidon2Type::Default,
-            }
+            Self::with_security(SecurityConfig::core())
         }
 
         pub fn compressed() -> Self {
+            Self::with_security(SecurityConfig::compressed())
+        }
+
+        /// Creates a configuration whose FRI parameters are given by `security`, for example
+        /// `SecurityConfig::for_level(128, SoundnessAssumption::Conjectured, 1)`.
+        pub fn with_security(security: SecurityConfig) -> Self {
             let perm = my_perm();
             let hash = MyHash::new(perm.clone());
             let compress = MyCompress::new(perm.clone());
             let val_mmcs = ValMmcs::new(hash, compress);
             let dft = Dft {};
-            let fri_config = compressed_fri_config();
+            let fri_config = fri_config(&security);
             let pcs = Pcs::new(27, dft, val_mmcs, fri_config);
-            Self {
-                pcs,
-                perm,
-                config_type: BabyBearPoseidon2Type::Compressed,
-            }
+            Self { pcs, perm }
         }
     }
 
     impl Clone for BabyBearPoseidon2 {
         fn clone(&self) -> Self {
-            match self.config_type {
-                BabyBearPoseidon2Type::Default => Self::new(),
-                BabyBearPoseidon2Type::Compressed => Self::compressed(),
-            }
+            Self::with_security(self.security())
         }
     }
 
@@ -553,19 +639,20 @@ pub mod baby_bear_poseidon2 {
         }
     }
 
-    /// Implement serialization manually instead of using serde to avoid cloing the config.
+    /// Implement serialization manually instead of using serde to avoid cloing the config. Only
+    /// the [SecurityConfig] is serialized, from which the configuration is rebuilt.
     impl Serialize for BabyBearPoseidon2 {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
             S: serde::Serializer,
         {
-            std::marker::PhantomData::<BabyBearPoseidon2>.serialize(serializer)
+            self.security().serialize(serializer)
         }
     }
 
-    impl From<std::marker::PhantomData<BabyBearPoseidon2>> for BabyBearPoseidon2 {
-        fn from(_: std::marker::PhantomData<BabyBearPoseidon2>) -> Self {
-            Self::new()
+    impl From<SecurityConfig> for BabyBearPoseidon2 {
+        fn from(security: SecurityConfig) -> Self {
+            Self::with_security(security)
         }
     }
 
@@ -583,6 +670,10 @@ pub mod baby_bear_poseidon2 {
         fn challenger(&self) -> Self::Challenger {
             Challenger::new(self.perm.clone())
         }
+
+        fn security(&self) -> SecurityConfig {
+            SecurityConfig::from_fri_config(self.pcs.fri_config())
+        }
     }
 }
 
@@ -593,13 +684,13 @@ pub(super) mod baby_bear_keccak {
     use p3_commit::ExtensionMmcs;
     use p3_dft::Radix2DitParallel;
     use p3_field::extension::BinomialExtensionField;
-    use p3_fri::{FriConfig, TwoAdicFriPcs};
+    use p3_fri::TwoAdicFriPcs;
     use p3_keccak::Keccak256Hash;
     use p3_merkle_tree::FieldMerkleTreeMmcs;
     use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
     use serde::{Deserialize, Serialize};
 
-    use crate::stark::StarkGenericConfig;
+    use crate::stark::{SecurityConfig, StarkGenericConfig};
 
     use super::LOG_DEGREE_BOUND;
 
@@ -622,29 +713,34 @@ pub(super) mod baby_bear_keccak {
     type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
 
     #[derive(Deserialize)]
-    #[serde(from = "std::marker::PhantomData<BabyBearKeccak>")]
+    #[serde(from = "SecurityConfig")]
     pub struct BabyBearKeccak {
         pcs: Pcs,
     }
-    // Implement serialization manually instead of using serde(into) to avoid cloing the config
+    // Implement serialization manually instead of using serde(into) to avoid cloing the config.
+    // Only the security config is serialized, from which the configuration is rebuilt.
     impl Serialize for BabyBearKeccak {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
             S: serde::Serializer,
         {
-            std::marker::PhantomData::<BabyBearKeccak>.serialize(serializer)
+            self.security().serialize(serializer)
         }
     }
 
-    impl From<std::marker::PhantomData<BabyBearKeccak>> for BabyBearKeccak {
-        fn from(_: std::marker::PhantomData<BabyBearKeccak>) -> Self {
-            Self::new()
+    impl From<SecurityConfig> for BabyBearKeccak {
+        fn from(security: SecurityConfig) -> Self {
+            Self::with_security(security)
         }
     }
 
     impl BabyBearKeccak {
-        #[allow(dead_code)]
         pub fn new() -> Self {
+            Self::with_security(SecurityConfig::core())
+        }
+
+        /// Creates a configuration whose FRI parameters are given by `security`.
+        pub fn with_security(security: SecurityConfig) -> Self {
             let byte_hash = ByteHash {};
             let field_hash = FieldHash::new(byte_hash);
 
@@ -656,12 +752,7 @@ pub(super) mod baby_bear_keccak {
 
             let dft = Dft {};
 
-            let fri_config = FriConfig {
-                log_blowup: 1,
-                num_queries: 100,
-                proof_of_work_bits: 16,
-                mmcs: challenge_mmcs,
-            };
+            let fri_config = security.fri_config(challenge_mmcs);
             let pcs = Pcs::new(LOG_DEGREE_BOUND, dft, val_mmcs, fri_config);
 
             Self { pcs }
@@ -676,7 +767,7 @@ pub(super) mod baby_bear_keccak {
 
     impl Clone for BabyBearKeccak {
         fn clone(&self) -> Self {
-            Self::new()
+            Self::with_security(self.security())
         }
     }
 
@@ -697,6 +788,10 @@ pub(super) mod baby_bear_keccak {
             let byte_hash = ByteHash {};
             Challenger::from_hasher(vec![], byte_hash)
         }
+
+        fn security(&self) -> SecurityConfig {
+            SecurityConfig::from_fri_config(self.pcs.fri_config())
+        }
     }
 }
 
@@ -708,12 +803,12 @@ pub(super) mod baby_bear_blake3 {
     use p3_commit::ExtensionMmcs;
     use p3_dft::Radix2DitParallel;
     use p3_field::extension::BinomialExtensionField;
-    use p3_fri::{FriConfig, TwoAdicFriPcs};
+    use p3_fri::TwoAdicFriPcs;
     use p3_merkle_tree::FieldMerkleTreeMmcs;
     use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
     use serde::{Deserialize, Serialize};
 
-    use crate::stark::StarkGenericConfig;
+    use crate::stark::{SecurityConfig, StarkGenericConfig};
 
     use super::LOG_DEGREE_BOUND;
 
@@ -736,35 +831,41 @@ pub(super) mod baby_bear_blake3 {
     type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
 
     #[derive(Deserialize)]
-    #[serde(from = "std::marker::PhantomData<BabyBearBlake3>")]
+    #[serde(from = "SecurityConfig")]
     pub struct BabyBearBlake3 {
         pcs: Pcs,
     }
 
-    // Implement serialization manually instead of using serde(into) to avoid cloing the config
+    // Implement serialization manually instead of using serde(into) to avoid cloing the config.
+    // Only the security config is serialized, from which the configuration is rebuilt.
     impl Serialize for BabyBearBlake3 {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
             S: serde::Serializer,
         {
-            std::marker::PhantomData::<Self>.serialize(serializer)
+            self.security().serialize(serializer)
         }
     }
 
-    impl From<std::marker::PhantomData<BabyBearBlake3>> for BabyBearBlake3 {
-        fn from(_: std::marker::PhantomData<BabyBearBlake3>) -> Self {
-            Self::new()
+    impl From<SecurityConfig> for BabyBearBlake3 {
+        fn from(security: SecurityConfig) -> Self {
+            Self::with_security(security)
         }
     }
 
     impl Clone for BabyBearBlake3 {
         fn clone(&self) -> Self {
-            Self::new()
+            Self::with_security(self.security())
         }
     }
 
     impl BabyBearBlake3 {
         pub fn new() -> Self {
+            Self::with_security(SecurityConfig::core())
+        }
+
+        /// Creates a configuration whose FRI parameters are given by `security`.
+        pub fn with_security(security: SecurityConfig) -> Self {
             let byte_hash = ByteHash {};
             let field_hash = FieldHash::new(byte_hash);
 
@@ -776,16 +877,7 @@ pub(super) mod baby_bear_blake3 {
 
             let dft = Dft {};
 
-            let num_queries = match std::env::var("FRI_QUERIES") {
-                Ok(value) => value.parse().unwrap(),
-                Err(_) => 100,
-            };
-            let fri_config = FriConfig {
-                log_blowup: 1,
-                num_queries,
-                proof_of_work_bits: 16,
-                mmcs: challenge_mmcs,
-            };
+            let fri_config = security.fri_config(challenge_mmcs);
             let pcs = Pcs::new(LOG_DEGREE_BOUND, dft, val_mmcs, fri_config);
 
             Self { pcs }
@@ -815,5 +907,9 @@ pub(super) mod baby_bear_blake3 {
             let byte_hash = ByteHash {};
             Challenger::from_hasher(vec![], byte_hash)
         }
+
+        fn security(&self) -> SecurityConfig {
+            SecurityConfig::from_fri_config(self.pcs.fri_config())
+        }
     }
 }
@@ -0,0 +1,367 @@
+use std::mem::size_of;
+
+use p3_air::BaseAir;
+use p3_baby_bear::BabyBear;
+
+use super::estimate::{
+    chunk_lens, chunked_events, chunked_lens, padded_rows, trace_bytes, ShardEvents,
+    NUM_CHUNKED_EVENTS,
+};
+use crate::io::SP1Stdin;
+use crate::runtime::{ExecutionError, ExecutionReport, Program, Runtime, ShardingConfig};
+use crate::stark::{Chip, RiscvAir, SecurityConfig};
+use crate::utils::SP1CoreOpts;
+
+/// The smallest shard size considered by [auto_tune_opts], as a power of two.
+const MIN_LOG_SHARD_SIZE: usize = 16;
+
+/// The largest shard size considered by [auto_tune_opts], as a power of two.
+const MAX_LOG_SHARD_SIZE: usize = 22;
+
+/// The largest shard batch size picked by [auto_tune_opts].
+const MAX_SHARD_BATCH_SIZE: usize = 128;
+
+/// The resources available to the prover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProverResources {
+    /// The memory the prover may use, in bytes.
+    pub memory_budget: usize,
+    /// The number of CPUs the prover may use.
+    pub num_cpus: usize,
+}
+
+impl ProverResources {
+    /// Resources with the given memory budget, in bytes, and every CPU of the machine.
+    pub fn with_memory_budget(memory_budget: usize) -> Self {
+        Self {
+            memory_budget,
+            num_cpus: num_cpus::get(),
+        }
+    }
+}
+
+/// The options picked by [auto_tune_opts].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TunedOpts {
+    /// The options of the core prover, including the per-chip sharding configuration.
+    pub opts: SP1CoreOpts,
+    /// The estimated number of shards of the execution.
+    pub num_shards: usize,
+    /// The estimated peak memory of the prover with these options, in bytes.
+    pub estimated_memory: usize,
+}
+
+/// Picks the shard size, shard batch size and per-chip sharding configuration for proving a
+/// program within the given resources.
+///
+/// The program is executed once without emitting events to count the instructions and syscalls of
+/// every part of the execution. The size of the traces is then estimated from the widths of the
+/// chips, and the largest shard size whose estimated peak memory fits in the budget while keeping
+/// every CPU busy is picked. If no shard size fits, the options with the smallest estimated memory
+/// are returned. The size of the low-degree extensions follows the blowup of `security`, which
+/// should be the FRI parameters of the core prover.
+pub fn auto_tune_opts(
+    program: Program,
+    stdin: &SP1Stdin,
+    resources: ProverResources,
+    security: SecurityConfig,
+) -> Result<TunedOpts, ExecutionError> {
+    let default_opts = SP1CoreOpts::default();
+
+    // Execute the program with the smallest shard size, so that the reports of consecutive shards
+    // can be merged to get the reports of every larger shard size.
+    let mut runtime = Runtime::new(
+        program,
+        SP1CoreOpts {
+            shard_size: 1 << MIN_LOG_SHARD_SIZE,
+            ..default_opts
+        },
+    );
+    runtime.collect_reports = true;
+    runtime.write_vecs(&stdin.buffer);
+    for (proof, vkey) in stdin.proofs.iter() {
+        runtime.write_proof(proof.clone(), vkey.clone());
+    }
+    runtime.run_untraced()?;
+
+    let profile = ExecutionProfile {
+        shard_reports: &runtime.shard_reports,
+        program: &runtime.program,
+        num_memory_init_events: runtime.record.memory_initialize_events.len(),
+        num_memory_finalize_events: runtime.record.memory_finalize_events.len(),
+        log_blowup: security.log_blowup,
+    };
+    let chips = RiscvAir::<BabyBear>::get_all()
+        .into_iter()
+        .map(Chip::new)
+        .collect::<Vec<_>>();
+
+    let parallelism = std::cmp::max(
+        resources.num_cpus / std::cmp::max(default_opts.shard_chunking_multiplier, 1),
+        1,
+    );
+
+    let mut fallback: Option<TunedOpts> = None;
+    for log_shard_size in (MIN_LOG_SHARD_SIZE..=MAX_LOG_SHARD_SIZE).rev() {
+        let shard_size = 1 << log_shard_size;
+        let estimate = profile.estimate(&chips, shard_size);
+        let max_batch_size = std::cmp::min(estimate.num_shards, MAX_SHARD_BATCH_SIZE);
+
+        // Prefer keeping the shard commitments in memory, and the largest batch that fits.
+        let tuned = [false, true]
+            .into_iter()
+            .flat_map(|reconstruct_commitments| {
+                (1..=max_batch_size)
+                    .rev()
+                    .map(move |batch_size| (reconstruct_commitments, batch_size))
+            })
+            .map(|(reconstruct_commitments, batch_size)| TunedOpts {
+                opts: SP1CoreOpts {
+                    shard_size,
+                    shard_batch_size: batch_size,
+                    reconstruct_commitments,
+                    sharding_config: Some(estimate.sharding_config),
+                    ..default_opts
+                },
+                num_shards: estimate.num_shards,
+                estimated_memory: estimate.peak_memory(
+                    batch_size,
+                    parallelism,
+                    reconstruct_commitments,
+                ),
+            })
+            .find(|tuned| tuned.estimated_memory <= resources.memory_budget);
+
+        match tuned {
+            Some(tuned)
+                if tuned.opts.shard_batch_size
+                    >= std::cmp::min(estimate.num_shards, parallelism) =>
+            {
+                return Ok(tuned);
+            }
+            _ => {
+                let smallest = tuned.unwrap_or(TunedOpts {
+                    opts: SP1CoreOpts {
+                        shard_size,
+                        shard_batch_size: 1,
+                        reconstruct_commitments: true,
+                        sharding_config: Some(estimate.sharding_config),
+                        ..default_opts
+                    },
+                    num_shards: estimate.num_shards,
+                    estimated_memory: estimate.peak_memory(1, parallelism, true),
+                });
+                if fallback.map_or(true, |fallback| {
+                    smallest.estimated_memory < fallback.estimated_memory
+                }) {
+                    fallback = Some(smallest);
+                }
+            }
+        }
+    }
+
+    Ok(fallback.unwrap())
+}
+
+/// The counts collected from an untraced execution with the smallest shard size.
+struct ExecutionProfile<'a> {
+    shard_reports: &'a [ExecutionReport],
+    program: &'a Program,
+    num_memory_init_events: usize,
+    num_memory_finalize_events: usize,
+    /// The log of the blowup factor of the low-degree extensions of the traces.
+    log_blowup: usize,
+}
+
+/// The estimated size of the shards of an execution for a given shard size.
+struct ShardSizeEstimate {
+    num_shards: usize,
+    sharding_config: ShardingConfig,
+    /// The size of the traces of the largest shard, including their low-degree extensions.
+    max_trace_bytes: usize,
+    /// The size of the committed main traces of a shard.
+    main_commit_bytes: usize,
+    /// The size of the events of a shard.
+    record_bytes: usize,
+}
+
+impl ExecutionProfile<'_> {
+    fn estimate(
+        &self,
+        chips: &[Chip<BabyBear, RiscvAir<BabyBear>>],
+        shard_size: usize,
+    ) -> ShardSizeEstimate {
+        let num_reports = self.shard_reports.len();
+        let num_shards = num_reports.div_ceil(shard_size >> MIN_LOG_SHARD_SIZE);
+
+        // Bound the number of events of a shard by the events of the smallest range of reports
+        // with at least `shard_size` instructions, plus one report since shards do not start on a
+        // report boundary.
+        let instructions = self
+            .shard_reports
+            .iter()
+            .map(|report| report.total_instruction_count() as usize)
+            .collect::<Vec<_>>();
+        let events = self
+            .shard_reports
+            .iter()
+            .map(chunked_events)
+            .collect::<Vec<_>>();
+        let mut max_events = [0; NUM_CHUNKED_EVENTS];
+        let mut end = 0;
+        let mut range_instructions = 0;
+        for start in 0..num_reports {
+            while end < num_reports && range_instructions < shard_size {
+                range_instructions += instructions[end];
+                end += 1;
+            }
+            let range_end = std::cmp::min(end + 1, num_reports);
+            for (i, max) in max_events.iter_mut().enumerate() {
+                let sum = events[start..range_end].iter().map(|e| e[i]).sum();
+                *max = std::cmp::max(*max, sum);
+            }
+            range_instructions -= instructions[start];
+        }
+
+        // Size the chunks of each chip to the maximum number of events of a shard, without going
+        // over the default size.
+        let mut sharding_config = ShardingConfig::new(shard_size);
+        for (len, max) in chunked_lens(&mut sharding_config)
+            .into_iter()
+            .zip(max_events)
+        {
+            *len = std::cmp::min(max.next_power_of_two(), shard_size);
+        }
+
+        // The precompiles which are not chunked are proven in the first shard, and the memory
+        // tables in the last one.
+        let totals =
+            self.shard_reports
+                .iter()
+                .fold(ExecutionReport::default(), |mut total, report| {
+                    total += report;
+                    total
+                });
+        let base = ShardEvents {
+            cycles: shard_size,
+            chunked: chunk_lens(&sharding_config),
+            precompiles: ExecutionReport::default(),
+            memory_init: 0,
+            memory_finalize: 0,
+        };
+        let first = ShardEvents {
+            precompiles: totals,
+            ..base
+        };
+        let last = ShardEvents {
+            precompiles: ExecutionReport::default(),
+            memory_init: self.num_memory_init_events,
+            memory_finalize: self.num_memory_finalize_events,
+            ..base
+        };
+
+        let max_trace_bytes = [&base, &first, &last]
+            .iter()
+            .map(|events| {
+                chips
+                    .iter()
+                    .map(|chip| {
+                        trace_bytes(chip, events.rows(chip.air(), self.program), self.log_blowup)
+                    })
+                    .sum::<usize>()
+            })
+            .max()
+            .unwrap();
+        let main_commit_bytes = chips
+            .iter()
+            .map(|chip| (chip, base.rows(chip.air(), self.program)))
+            .filter(|(_, rows)| *rows > 0)
+            .map(|(chip, rows)| {
+                (padded_rows(rows) * chip.width() * size_of::<u32>()) << self.log_blowup
+            })
+            .sum();
+        let record_bytes = ShardEvents {
+            chunked: max_events,
+            ..base
+        }
+        .record_bytes();
+
+        ShardSizeEstimate {
+            num_shards,
+            sharding_config,
+            max_trace_bytes,
+            main_commit_bytes,
+            record_bytes,
+        }
+    }
+}
+
+impl ShardSizeEstimate {
+    /// The estimated peak memory of the prover, in bytes.
+    ///
+    /// The events of a whole batch are kept in memory while up to `parallelism` shards are proven
+    /// at once. If the commitments are not reconstructed, the main traces of every shard are kept
+    /// in memory until the end of the proof.
+    fn peak_memory(
+        &self,
+        batch_size: usize,
+        parallelism: usize,
+        reconstruct_commitments: bool,
+    ) -> usize {
+        let concurrent_shards = std::cmp::min(batch_size, parallelism);
+        let commitments = if reconstruct_commitments {
+            0
+        } else {
+            self.num_shards * self.main_commit_bytes
+        };
+        batch_size * self.record_bytes + concurrent_shards * self.max_trace_bytes + commitments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stark::StarkGenericConfig;
+    use crate::utils::tests::FIBONACCI_ELF;
+    use crate::utils::{prove, setup_logger, BabyBearPoseidon2, ProofProgress};
+
+    #[test]
+    fn test_auto_tune_opts() {
+        setup_logger();
+        let program = Program::from(FIBONACCI_ELF);
+        let stdin = SP1Stdin::new();
+        let resources = ProverResources {
+            memory_budget: 16 << 30,
+            num_cpus: 4,
+        };
+        let config = BabyBearPoseidon2::new();
+        let tuned = auto_tune_opts(program.clone(), &stdin, resources, config.security()).unwrap();
+        let sharding_config = tuned.opts.sharding_config.unwrap();
+        assert!(tuned.opts.shard_size.is_power_of_two());
+        assert!(sharding_config.add_len <= tuned.opts.shard_size);
+        assert!(tuned.opts.shard_batch_size >= 1);
+
+        // A smaller budget never picks a larger shard size.
+        let small_resources = ProverResources {
+            memory_budget: 1 << 30,
+            ..resources
+        };
+        let small =
+            auto_tune_opts(program.clone(), &stdin, small_resources, config.security()).unwrap();
+        assert!(small.opts.shard_size <= tuned.opts.shard_size);
+
+        // The tuned options produce a valid proof.
+        let machine = RiscvAir::machine(config.clone());
+        let (_, vk) = machine.setup(&program);
+        let (proof, _) = prove(
+            program,
+            &stdin,
+            config,
+            tuned.opts,
+            &ProofProgress::default(),
+        )
+        .unwrap();
+        let mut challenger = machine.config().challenger();
+        machine.verify(&vk, &proof, &mut challenger).unwrap();
+    }
+}
@@ -30,8 +30,10 @@ use syn::parse_macro_input;
 use syn::parse_quote;
 use syn::Data;
 use syn::DeriveInput;
+use syn::Fields;
 use syn::GenericParam;
 use syn::ItemFn;
+use syn::Type;
 
 #[proc_macro_derive(AlignedBorrow)]
 pub fn aligned_borrow_derive(input: TokenStream) -> TokenStream {
@@ -89,7 +91,104 @@ pub fn aligned_borrow_derive(input: TokenStream) -> TokenStream {
         }
     };
 
-    TokenStream::from(methods)
+    // Name the columns after the fields, descending into arrays and nested column structs.
+    let column_names = match &ast.data {
+        Data::Struct(data) => {
+            let other_type_generics = ast
+                .generics
+                .type_params()
+                .skip(1)
+                .map(|param| &param.ident)
+                .collect::<Vec<_>>();
+            let is_newtype = matches!(data.fields, Fields::Unnamed(_)) && data.fields.len() == 1;
+            let fields = data.fields.iter().enumerate().map(|(i, field)| {
+                let field_name = match &field.ident {
+                    Some(ident) => ident.to_string(),
+                    None => i.to_string(),
+                };
+                let prefix = if is_newtype {
+                    quote! { prefix.to_string() }
+                } else {
+                    quote! { sp1_core::air::column_name(prefix, #field_name) }
+                };
+                let push_names =
+                    column_names_for_type(&field.ty, type_generic, &other_type_generics);
+                quote! {
+                    {
+                        let prefix: &str = &#prefix;
+                        #push_names
+                    }
+                }
+            });
+            quote! {
+                impl #impl_generics sp1_core::air::ColumnNames for #name #type_generics #where_clause {
+                    fn column_names_with_prefix(prefix: &str, names: &mut Vec<String>) {
+                        #(#fields)*
+                    }
+                }
+            }
+        }
+        // The fields of a union overlap, so its columns are named by index.
+        _ => quote! {
+            impl #impl_generics sp1_core::air::ColumnNames for #name #type_generics #where_clause {
+                fn column_names_with_prefix(prefix: &str, names: &mut Vec<String>) {
+                    sp1_core::air::indexed_column_names(
+                        prefix,
+                        core::mem::size_of::<Self>() / core::mem::size_of::<#type_generic>(),
+                        names,
+                    );
+                }
+            }
+        },
+    };
+
+    TokenStream::from(quote! {
+        #methods
+
+        #column_names
+    })
+}
+
+/// Generates the code that appends the column names of a field of type `ty` to `names`.
+fn column_names_for_type(
+    ty: &Type,
+    type_generic: &syn::Ident,
+    other_type_generics: &[&syn::Ident],
+) -> proc_macro2::TokenStream {
+    match ty {
+        Type::Array(array) => {
+            let len = &array.len;
+            let push_names = column_names_for_type(&array.elem, type_generic, other_type_generics);
+            quote! {
+                for i in 0..#len {
+                    let prefix: &str = &format!("{}[{}]", prefix, i);
+                    #push_names
+                }
+            }
+        }
+        Type::Path(path) if path.qself.is_none() && path.path.is_ident(type_generic) => {
+            quote! { names.push(prefix.to_string()); }
+        }
+        Type::Path(path)
+            if path.qself.is_none()
+                && other_type_generics
+                    .iter()
+                    .any(|ident| path.path.is_ident(*ident)) =>
+        {
+            // The layout of a generic field is only known once it is instantiated, so its columns
+            // are named by index.
+            quote! {
+                sp1_core::air::indexed_column_names(
+                    prefix,
+                    core::mem::size_of::<#ty>() / core::mem::size_of::<#type_generic>(),
+                    names,
+                );
+            }
+        }
+        _ => quote! {
+            <#ty as sp1_core::air::ColumnNames>::column_names_with_prefix(prefix, names);
+        },
+    }
 }
 
 #[proc_macro_derive(
@@ -151,6 +250,13 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                 }
             });
 
+            let column_names_arms = variants.iter().map(|(variant_name, field)| {
+                let field_ty = &field.ty;
+                quote! {
+                    #name::#variant_name(x) => <#field_ty as #sp1_core_path::air::MachineAir<F>>::column_names(x)
+                }
+            });
+
             let preprocessed_width_arms = variants.iter().map(|(variant_name, field)| {
                 let field_ty = &field.ty;
                 quote! {
@@ -172,6 +278,13 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                 }
             });
 
+            let num_rows_arms = variants.iter().map(|(variant_name, field)| {
+                let field_ty = &field.ty;
+                quote! {
+                    #name::#variant_name(x) => <#field_ty as #sp1_core_path::air::MachineAir<F>>::num_rows(x, input)
+                }
+            });
+
             let generate_dependencies_arms = variants.iter().map(|(variant_name, field)| {
                 let field_ty = &field.ty;
                 quote! {
@@ -198,6 +311,12 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                         }
                     }
 
+                    fn column_names(&self) -> Vec<String> {
+                        match self {
+                            #(#column_names_arms,)*
+                        }
+                    }
+
                     fn preprocessed_width(&self) -> usize {
                         match self {
                             #(#preprocessed_width_arms,)*
@@ -223,6 +342,12 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                         }
                     }
 
+                    fn num_rows(&self, input: &#execution_record_path) -> Option<usize> {
+                        match self {
+                            #(#num_rows_arms,)*
+                        }
+                    }
+
                     fn generate_dependencies(
                         &self,
                         input: &#execution_record_path,
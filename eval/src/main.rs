@@ -5,7 +5,11 @@ use clap::{command, Parser};
 use csv::WriterBuilder;
 use serde::Serialize;
 use sp1_core::runtime::{Program, Runtime};
-use sp1_core::utils::{prove_simple, BabyBearBlake3, BabyBearKeccak, BabyBearPoseidon2};
+use sp1_core::stark::{SecurityConfig, StarkGenericConfig};
+use sp1_core::utils::{
+    estimate_core, prove_simple, BabyBearBlake3, BabyBearKeccak, BabyBearPoseidon2,
+    BenchmarkProfile, BenchmarkSample, ProofProgress, SP1CoreOpts,
+};
 use sp1_prover::utils::get_cycles;
 use sp1_prover::SP1Stdin;
 use std::fmt;
@@ -22,6 +26,18 @@ enum HashFnId {
     Keccak256,
 }
 
+impl HashFnId {
+    /// The FRI parameters of the core prover with this hash function.
+    fn security(&self) -> SecurityConfig {
+        match self {
+            HashFnId::Blake3 => BabyBearBlake3::new().security(),
+            HashFnId::Poseidon => BabyBearPoseidon2::new().security(),
+            HashFnId::Keccak256 => BabyBearKeccak::new().security(),
+            HashFnId::Sha256 => panic!("Unsupported hash function"),
+        }
+    }
+}
+
 impl fmt::Display for HashFnId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let hash_fn_str = match self {
@@ -82,6 +98,16 @@ struct EvalArgs {
 
     #[arg(long, default_value_t = 1)]
     pub runs: usize,
+
+    /// The path of a bincode-encoded `SP1Stdin` to run the program with. Defaults to an empty
+    /// input.
+    #[arg(long)]
+    pub stdin_path: Option<String>,
+
+    /// The path of a benchmark profile to append the measurements to, which can be used to
+    /// calibrate proof estimates in the SDK.
+    #[arg(long)]
+    pub profile_path: Option<String>,
 }
 
 fn main() {
@@ -90,7 +116,17 @@ fn main() {
     // Load the program.
     let elf_path = &args.elf_path;
     let elf = fs::read(elf_path).expect("Failed to read ELF file");
-    let cycles = get_cycles(&elf, &SP1Stdin::new());
+    let stdin = match &args.stdin_path {
+        Some(path) => bincode::deserialize(&fs::read(path).expect("Failed to read stdin file"))
+            .expect("Failed to decode stdin file"),
+        None => SP1Stdin::new(),
+    };
+    let opts = SP1CoreOpts {
+        shard_size: args.shard_size as usize,
+        ..SP1CoreOpts::default()
+    };
+    opts.validate().expect("Invalid shard size");
+    let cycles = get_cycles(&elf, &stdin);
 
     // Initialize total duration counters.
     let mut total_execution_duration = 0f64;
@@ -100,9 +136,8 @@ fn main() {
     // Perform runs.
     let program = Program::from(&elf);
     for _ in 0..args.runs {
-        let elf = fs::read(elf_path).expect("Failed to read ELF file");
         let (execution_duration, prove_duration, verify_duration) =
-            run_evaluation(&args.hashfn, &program, &elf);
+            run_evaluation(&args.hashfn, &program, &stdin, opts);
 
         // Accumulate durations.
         total_execution_duration += execution_duration;
@@ -115,6 +150,23 @@ fn main() {
     let avg_prove_duration = total_prove_duration / args.runs as f64;
     let avg_verify_duration = total_verify_duration / args.runs as f64;
 
+    // Append the measurements to the profile.
+    if let Some(profile_path) = &args.profile_path {
+        let estimate = estimate_core(program.clone(), &stdin, opts, args.hashfn.security())
+            .expect("failed to estimate program");
+        let sample = BenchmarkSample {
+            program: args.program.clone(),
+            hashfn: args.hashfn.to_string(),
+            shard_size: opts.shard_size,
+            cycles,
+            trace_area: estimate.shards.iter().map(|shard| shard.trace_area()).sum(),
+            prove_duration: avg_prove_duration,
+        };
+        if let Err(e) = write_profile(sample, profile_path) {
+            eprintln!("Failed to write profile: {}", e);
+        }
+    }
+
     let report = PerformanceReport {
         program: args.program,
         hashfn: args.hashfn.to_string(),
@@ -132,19 +184,32 @@ fn main() {
     }
 }
 
-fn run_evaluation(hashfn: &HashFnId, program: &Program, _elf: &[u8]) -> (f64, f64, f64) {
+fn run_evaluation(
+    hashfn: &HashFnId,
+    program: &Program,
+    stdin: &SP1Stdin,
+    opts: SP1CoreOpts,
+) -> (f64, f64, f64) {
     // Note: While these benchmarks are useful for core proving, they are not useful for recursion
     // or end to end proving as we only support Poseidon for now.
+    let new_runtime = || {
+        let mut runtime = Runtime::new(program.clone(), opts);
+        runtime.write_vecs(&stdin.buffer);
+        for (proof, vkey) in stdin.proofs.iter() {
+            runtime.write_proof(proof.clone(), vkey.clone());
+        }
+        runtime
+    };
     match hashfn {
         HashFnId::Blake3 => {
-            let mut runtime = Runtime::new(program.clone());
+            let mut runtime = new_runtime();
             let execution_start = Instant::now();
             runtime.run().unwrap();
             let execution_duration = execution_start.elapsed().as_secs_f64();
 
             let config = BabyBearBlake3::new();
             let prove_start = Instant::now();
-            let _proof = prove_simple(config.clone(), runtime);
+            let _proof = prove_simple(config.clone(), runtime, opts, &ProofProgress::default());
             let prove_duration = prove_start.elapsed().as_secs_f64();
 
             let verify_start = Instant::now();
@@ -154,14 +219,14 @@ fn run_evaluation(hashfn: &HashFnId, program: &Program, _elf: &[u8]) -> (f64, f6
             (execution_duration, prove_duration, verify_duration)
         }
         HashFnId::Poseidon => {
-            let mut runtime = Runtime::new(program.clone());
+            let mut runtime = new_runtime();
             let execution_start = Instant::now();
             runtime.run().unwrap();
             let execution_duration = execution_start.elapsed().as_secs_f64();
 
             let config = BabyBearPoseidon2::new();
             let prove_start = Instant::now();
-            let _proof = prove_simple(config.clone(), runtime);
+            let _proof = prove_simple(config.clone(), runtime, opts, &ProofProgress::default());
             let prove_duration = prove_start.elapsed().as_secs_f64();
 
             let verify_start = Instant::now();
@@ -171,14 +236,14 @@ fn run_evaluation(hashfn: &HashFnId, program: &Program, _elf: &[u8]) -> (f64, f6
             (execution_duration, prove_duration, verify_duration)
         }
         HashFnId::Keccak256 => {
-            let mut runtime = Runtime::new(program.clone());
+            let mut runtime = new_runtime();
             let execution_start = Instant::now();
             runtime.run().unwrap();
             let execution_duration = execution_start.elapsed().as_secs_f64();
 
             let config = BabyBearKeccak::new();
             let prove_start = Instant::now();
-            let _proof = prove_simple(config.clone(), runtime);
+            let _proof = prove_simple(config.clone(), runtime, opts, &ProofProgress::default());
             let prove_duration = prove_start.elapsed().as_secs_f64();
 
             let verify_start = Instant::now();
@@ -222,3 +287,19 @@ fn write_report(report: PerformanceReport, benchmark_path: &str) -> io::Result<(
 
     Ok(())
 }
+
+fn write_profile(sample: BenchmarkSample, profile_path: &str) -> io::Result<()> {
+    // Load the existing samples, if any.
+    let mut profile = match fs::read(profile_path) {
+        Ok(bytes) if !bytes.is_empty() => serde_json::from_slice(&bytes)?,
+        Ok(_) => BenchmarkProfile::default(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => BenchmarkProfile::default(),
+        Err(e) => return Err(e),
+    };
+    profile.samples.push(sample);
+
+    let file = fs::File::create(profile_path)?;
+    serde_json::to_writer_pretty(file, &profile)?;
+
+    Ok(())
+}
@@ -26,7 +26,7 @@ fn main() {
 
     // Save proof.
     proof
-        .save("proof-with-io.json")
+        .save("proof-with-io.json", &vk)
         .expect("saving proof failed");
 
     println!("successfully generated and verified proof for the program!")
@@ -35,7 +35,7 @@ fn main() {
 
     // Save the proof.
     proof
-        .save("proof-with-pis.json")
+        .save("proof-with-pis.json", &vk)
         .expect("saving proof failed");
 
     println!("successfully generated and verified proof for the program!")
@@ -1,16 +1,97 @@
 use chrono::Local;
 use std::{
+    fs,
     io::{BufRead, BufReader},
+    path::{Path, PathBuf},
     process::{Command, Stdio},
+    sync::Mutex,
     thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+/// The suffix of the build manifest written by `cargo prove build` next to each ELF.
+const MANIFEST_SUFFIX: &str = ".manifest.json";
+
+/// The name of the file generated in `OUT_DIR` with a constant for each ELF built by the build
+/// script.
+pub const ELFS_FILE: &str = "sp1_elfs.rs";
+
+/// The ELFs built so far by the build script, as (name, path) pairs.
+static BUILT_ELFS: Mutex<Vec<(String, PathBuf)>> = Mutex::new(Vec::new());
+
+/// Options for building programs with `cargo prove build`, mirroring its flags.
+///
+/// To build programs with different features or profiles, call [build_program_with_args] once
+/// for each of them.
+#[derive(Debug, Clone, Default)]
+pub struct BuildArgs {
+    /// Builds in the docker image instead of with the local toolchain.
+    pub docker: bool,
+    /// The binaries to build, each written to `elf/<name>`.
+    pub binaries: Vec<String>,
+    /// The packages whose binaries to build, instead of the root package.
+    pub packages: Vec<String>,
+    /// The features to enable, as `feature` or `package/feature`.
+    pub features: Vec<String>,
+    pub no_default_features: bool,
+    /// The cargo profile to build with. Defaults to `release`.
+    pub profile: Option<String>,
+    /// The name of the ELF to write, when a single program is built.
+    pub elf_name: Option<String>,
+}
+
+impl BuildArgs {
+    fn prove_build_args(&self) -> Vec<String> {
+        let mut args = vec!["prove".to_string(), "build".to_string()];
+        if self.docker {
+            args.push("--docker".to_string());
+        }
+        for binary in self.binaries.iter() {
+            args.extend(["--bin".to_string(), binary.clone()]);
+        }
+        for package in self.packages.iter() {
+            args.extend(["--package".to_string(), package.clone()]);
+        }
+        if !self.features.is_empty() {
+            args.extend(["--features".to_string(), self.features.join(",")]);
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".to_string());
+        }
+        if let Some(ref profile) = self.profile {
+            args.extend(["--build-profile".to_string(), profile.clone()]);
+        }
+        if let Some(ref elf_name) = self.elf_name {
+            args.extend(["--elf-name".to_string(), elf_name.clone()]);
+        }
+        args
+    }
+}
+
 fn current_datetime() -> String {
     let now = Local::now();
     now.format("%Y-%m-%d %H:%M:%S").to_string()
 }
 
+/// Builds the program in the given directory with `cargo prove build`.
 pub fn build_program(path: &str) {
+    build_program_with_args(path, BuildArgs::default())
+}
+
+/// Builds the programs in the given directory with `cargo prove build` and the given options.
+///
+/// Each built ELF can then be included in the crate with `sp1_sdk::include_elf!("<name>")`, or
+/// through the constants generated in [ELFS_FILE]:
+///
+/// ```ignore
+/// include!(concat!(env!("OUT_DIR"), "/sp1_elfs.rs"));
+///
+/// let elf = elfs::FIBONACCI_PROGRAM;
+/// ```
+///
+/// The name of an ELF is the name of its binary, which is the name of the package unless the
+/// package defines several binaries.
+pub fn build_program_with_args(path: &str, args: BuildArgs) {
     println!("path: {:?}", path);
     let program_dir = std::path::Path::new(path);
 
@@ -41,30 +122,114 @@ pub fn build_program(path: &str) {
         current_datetime()
     );
 
-    let status = execute_build_cmd(&program_dir)
+    // When the build is skipped, the ELFs of the previous builds are used.
+    let started_at = if is_clippy_driver() {
+        UNIX_EPOCH
+    } else {
+        build_start_time()
+    };
+    let status = execute_build_cmd(&program_dir, &args)
         .unwrap_or_else(|_| panic!("Failed to build `{}`.", root_package_name));
     if !status.success() {
         panic!("Failed to build `{}`.", root_package_name);
     }
+
+    let elf_dir = metadata.target_directory.parent().unwrap().join("elf");
+    let mut built_elfs = BUILT_ELFS.lock().unwrap();
+    for (name, elf_path) in built_elfs_since(elf_dir.as_std_path(), started_at) {
+        println!("cargo:rustc-env=SP1_ELF_{}={}", name, elf_path.display());
+        built_elfs.retain(|(built, _)| built != &name);
+        built_elfs.push((name, elf_path));
+    }
+    write_elfs_file(&built_elfs);
+}
+
+/// Returns the time a build starts at, rounded down to the second so that it is not later than the
+/// modification times of the files it writes on filesystems with a coarse time resolution.
+fn build_start_time() -> SystemTime {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Invalid time");
+    UNIX_EPOCH + Duration::from_secs(since_epoch.as_secs())
+}
+
+/// Returns the name of the binary and the path of each ELF in `elf_dir` whose build manifest was
+/// written since `started_at`.
+///
+/// `cargo prove build` writes a manifest next to each ELF it builds, recording the binary it was
+/// built from, so the ELFs of a build are found without selecting the programs again.
+fn built_elfs_since(elf_dir: &Path, started_at: SystemTime) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(elf_dir) else {
+        return Vec::new();
+    };
+    let mut elfs = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(elf_name) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(MANIFEST_SUFFIX))
+        else {
+            continue;
+        };
+        let written = entry.metadata().and_then(|metadata| metadata.modified());
+        if !written.is_ok_and(|written| written >= started_at) {
+            continue;
+        }
+        let manifest: serde_json::Value = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_else(|| panic!("Failed to read build manifest {}", path.display()));
+        let binary = manifest["binary"]
+            .as_str()
+            .unwrap_or_else(|| panic!("Build manifest {} has no binary", path.display()));
+        elfs.push((binary.to_string(), elf_dir.join(elf_name)));
+    }
+    elfs.sort();
+    elfs
+}
+
+/// Writes a module `elfs` with a constant including each built ELF to [ELFS_FILE] in `OUT_DIR`.
+fn write_elfs_file(elfs: &[(String, PathBuf)]) {
+    let Ok(out_dir) = std::env::var("OUT_DIR") else {
+        return;
+    };
+    let mut contents = String::from("#[allow(dead_code)]\npub mod elfs {\n");
+    for (name, path) in elfs.iter() {
+        contents.push_str(&format!(
+            "    pub const {}: &[u8] = include_bytes!({:?});\n",
+            name.to_uppercase().replace('-', "_"),
+            path
+        ));
+    }
+    contents.push_str("}\n");
+    fs::write(Path::new(&out_dir).join(ELFS_FILE), contents)
+        .unwrap_or_else(|err| panic!("Failed to write {}: {}", ELFS_FILE, err));
+}
+
+/// Checks if RUSTC_WORKSPACE_WRAPPER is set to clippy-driver (i.e. if `cargo clippy` is the current
+/// compiler).
+fn is_clippy_driver() -> bool {
+    std::env::var("RUSTC_WORKSPACE_WRAPPER")
+        .map(|val| val.contains("clippy-driver"))
+        .unwrap_or(false)
 }
 
 /// Executes the `cargo prove build` command in the program directory
 fn execute_build_cmd(
     program_dir: &impl AsRef<std::path::Path>,
+    args: &BuildArgs,
 ) -> Result<std::process::ExitStatus, std::io::Error> {
-    // Check if RUSTC_WORKSPACE_WRAPPER is set to clippy-driver (i.e. if `cargo clippy` is the current
-    // compiler). If so, don't execute `cargo prove build` because it breaks rust-analyzer's `cargo clippy` feature.
-    let is_clippy_driver = std::env::var("RUSTC_WORKSPACE_WRAPPER")
-        .map(|val| val.contains("clippy-driver"))
-        .unwrap_or(false);
-    if is_clippy_driver {
+    // Don't execute `cargo prove build` under clippy, because it breaks rust-analyzer's
+    // `cargo clippy` feature.
+    if is_clippy_driver() {
         println!("cargo:warning=Skipping build due to clippy invocation.");
         return Ok(std::process::ExitStatus::default());
     }
 
     let mut cmd = Command::new("cargo");
     cmd.current_dir(program_dir)
-        .args(["prove", "build"])
+        .args(args.prove_build_args())
         .env_remove("RUSTC")
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
@@ -8,8 +8,9 @@ use clap::Parser;
 use p3_baby_bear::BabyBear;
 use p3_field::PrimeField;
 use sp1_core::io::SP1Stdin;
+use sp1_core::utils::ProofProgress;
 use sp1_prover::utils::{babybear_bytes_to_bn254, babybears_to_bn254, words_to_bytes};
-use sp1_prover::SP1Prover;
+use sp1_prover::{SP1Prover, SP1ProverOpts};
 use sp1_recursion_circuit::stark::build_wrap_circuit;
 use sp1_recursion_circuit::witness::Witnessable;
 use sp1_recursion_compiler::ir::Witness;
@@ -26,7 +27,6 @@ struct Args {
 
 pub fn main() {
     sp1_core::utils::setup_logger();
-    std::env::set_var("RECONSTRUCT_COMMITMENTS", "false");
 
     let args = Args::parse();
     let build_dir: PathBuf = args.build_dir.into();
@@ -35,22 +35,29 @@ pub fn main() {
 
     tracing::info!("initializing prover");
     let prover = SP1Prover::new();
+    let mut opts = SP1ProverOpts::default();
+    opts.core_opts.reconstruct_commitments = false;
+    let progress = ProofProgress::default();
 
     tracing::info!("setup elf");
     let (pk, vk) = prover.setup(elf);
 
     tracing::info!("prove core");
     let stdin = SP1Stdin::new();
-    let core_proof = prover.prove_core(&pk, &stdin).unwrap();
+    let core_proof = prover.prove_core(&pk, &stdin, opts, &progress).unwrap();
 
     tracing::info!("Compress");
-    let reduced_proof = prover.compress(&vk, core_proof, vec![]).unwrap();
+    let reduced_proof = prover
+        .compress(&vk, core_proof, vec![], opts, &progress)
+        .unwrap();
 
     tracing::info!("Shrink");
-    let compressed_proof = prover.shrink(reduced_proof).unwrap();
+    let compressed_proof = prover.shrink(reduced_proof, opts, &progress).unwrap();
 
     tracing::info!("wrap");
-    let wrapped_proof = prover.wrap_bn254(compressed_proof).unwrap();
+    let wrapped_proof = prover
+        .wrap_bn254(compressed_proof, opts, &progress)
+        .unwrap();
 
     tracing::info!("building verifier constraints");
     let constraints = tracing::info_span!("wrap circuit")
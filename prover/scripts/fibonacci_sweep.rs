@@ -7,7 +7,8 @@ use std::{fs::File, io::BufWriter, io::Write, time::Instant};
 
 use itertools::iproduct;
 use sp1_core::io::SP1Stdin;
-use sp1_prover::SP1Prover;
+use sp1_core::utils::ProofProgress;
+use sp1_prover::{SP1Prover, SP1ProverOpts};
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::{fmt::format::FmtSpan, util::SubscriberInitExt};
 
@@ -33,9 +34,6 @@ fn main() {
         .finish()
         .init();
 
-    // Setup enviroment variables.
-    std::env::set_var("RECONSTRUCT_COMMITMENTS", "false");
-
     // Initialize prover.
     let prover = SP1Prover::new();
 
@@ -57,7 +55,11 @@ fn main() {
             iterations,
             batch_size
         );
-        std::env::set_var("SHARD_SIZE", shard_size.to_string());
+        let mut opts = SP1ProverOpts::default();
+        opts.core_opts.shard_size = shard_size;
+        opts.core_opts.reconstruct_commitments = false;
+        opts.compress_opts.batch_size = batch_size;
+        let progress = ProofProgress::default();
 
         let stdin = SP1Stdin {
             buffer: vec![bincode::serialize::<u32>(&iterations).unwrap()],
@@ -65,11 +67,11 @@ fn main() {
             proofs: vec![],
         };
         let leaf_proving_start = Instant::now();
-        let proof = prover.prove_core(&pk, &stdin).unwrap();
+        let proof = prover.prove_core(&pk, &stdin, opts, &progress).unwrap();
         let leaf_proving_duration = leaf_proving_start.elapsed().as_secs_f64();
 
         let recursion_proving_start = Instant::now();
-        let _ = prover.compress(&vk, proof, vec![]);
+        let _ = prover.compress(&vk, proof, vec![], opts, &progress);
         let recursion_proving_duration = recursion_proving_start.elapsed().as_secs_f64();
 
         lines.push(format!(
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 
 use p3_baby_bear::BabyBear;
 use sp1_core::stark::StarkVerifyingKey;
+use sp1_core::utils::ProofProgress;
 use sp1_core::{io::SP1Stdin, stark::ShardProof};
 pub use sp1_recursion_circuit::stark::build_wrap_circuit;
 pub use sp1_recursion_circuit::witness::Witnessable;
@@ -14,7 +15,7 @@ use sp1_recursion_gnark_ffi::Groth16Prover;
 
 use crate::install::{install_groth16_artifacts, GROTH16_ARTIFACTS_COMMIT};
 use crate::utils::{babybear_bytes_to_bn254, babybears_to_bn254, words_to_bytes};
-use crate::{OuterSC, SP1Prover};
+use crate::{OuterSC, SP1Prover, SP1ProverOpts};
 
 /// Tries to install the Groth16 artifacts if they are not already installed.
 pub fn try_install_groth16_artifacts() -> PathBuf {
@@ -121,6 +122,8 @@ pub fn dummy_proof() -> (StarkVerifyingKey<OuterSC>, ShardProof<OuterSC>) {
 
     tracing::info!("initializing prover");
     let prover = SP1Prover::new();
+    let opts = SP1ProverOpts::default();
+    let progress = ProofProgress::default();
 
     tracing::info!("setup elf");
     let (pk, vk) = prover.setup(elf);
@@ -128,16 +131,18 @@ pub fn dummy_proof() -> (StarkVerifyingKey<OuterSC>, ShardProof<OuterSC>) {
     tracing::info!("prove core");
     let mut stdin = SP1Stdin::new();
     stdin.write(&500u32);
-    let core_proof = prover.prove_core(&pk, &stdin).unwrap();
+    let core_proof = prover.prove_core(&pk, &stdin, opts, &progress).unwrap();
 
     tracing::info!("compress");
-    let compressed_proof = prover.compress(&vk, core_proof, vec![]).unwrap();
+    let compressed_proof = prover
+        .compress(&vk, core_proof, vec![], opts, &progress)
+        .unwrap();
 
     tracing::info!("shrink");
-    let shrink_proof = prover.shrink(compressed_proof).unwrap();
+    let shrink_proof = prover.shrink(compressed_proof, opts, &progress).unwrap();
 
     tracing::info!("wrap");
-    let wrapped_proof = prover.wrap_bn254(shrink_proof).unwrap();
+    let wrapped_proof = prover.wrap_bn254(shrink_proof, opts, &progress).unwrap();
 
     (prover.wrap_vk, wrapped_proof.proof)
 }
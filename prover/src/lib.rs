@@ -19,27 +19,33 @@ pub mod utils;
 pub mod verify;
 
 use std::borrow::Borrow;
-use std::env;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
 
-use crate::utils::RECONSTRUCT_COMMITMENTS_ENV_VAR;
 use p3_baby_bear::BabyBear;
 use p3_challenger::CanObserve;
-use p3_field::{AbstractField, PrimeField};
+use p3_field::{AbstractField, PrimeField, PrimeField32};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use rayon::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
 use sp1_core::air::{PublicValues, Word};
 pub use sp1_core::io::{SP1PublicValues, SP1Stdin};
 use sp1_core::runtime::{ExecutionError, Runtime};
-use sp1_core::stark::{Challenge, StarkProvingKey};
+use sp1_core::stark::{
+    Challenge, Com, MachineProof, OpeningProof, PcsProverData, ShardMainData, StarkProvingKey,
+};
 use sp1_core::stark::{Challenger, MachineVerificationError};
-use sp1_core::utils::DIGEST_SIZE;
+use sp1_core::utils::{ProofCancelled, ProofPhase, ProofProgress, SP1CoreOpts, DIGEST_SIZE};
 use sp1_core::{
     runtime::Program,
     stark::{
         LocalProver, RiscvAir, ShardProof, StarkGenericConfig, StarkMachine, StarkVerifyingKey, Val,
     },
-    utils::{BabyBearPoseidon2, SP1CoreProverError},
+    utils::{BabyBearBlake3, BabyBearKeccak, BabyBearPoseidon2, SP1CoreProverError},
 };
 use sp1_primitives::hash_deferred_proof;
 use sp1_recursion_circuit::witness::Witnessable;
@@ -47,7 +53,7 @@ use sp1_recursion_compiler::config::InnerConfig;
 use sp1_recursion_compiler::ir::Witness;
 use sp1_recursion_core::{
     air::RecursionPublicValues,
-    runtime::{RecursionProgram, Runtime as RecursionRuntime},
+    runtime::{ExecutionRecord as RecursionRecord, RecursionProgram, Runtime as RecursionRuntime},
     stark::{config::BabyBearPoseidon2Outer, RecursionAir},
 };
 pub use sp1_recursion_gnark_ffi::plonk_bn254::PlonkBn254Proof;
@@ -133,7 +139,10 @@ pub struct SP1Prover {
     /// The machine used for proving the core step.
     pub core_machine: StarkMachine<CoreSC, RiscvAir<<CoreSC as StarkGenericConfig>::Val>>,
 
-    /// The machine used for proving the recursive and reduction steps.
+    /// The machine used for proving the first layer of recursion.
+    pub recursion_machine: StarkMachine<InnerSC, ReduceAir<<InnerSC as StarkGenericConfig>::Val>>,
+
+    /// The machine used for proving the reduction steps.
     pub compress_machine: StarkMachine<InnerSC, ReduceAir<<InnerSC as StarkGenericConfig>::Val>>,
 
     /// The machine used for proving the shrink step.
@@ -145,22 +154,32 @@ pub struct SP1Prover {
 
 impl SP1Prover {
     /// Initializes a new [SP1Prover].
-    #[instrument(name = "initialize prover", level = "info", skip_all)]
     pub fn new() -> Self {
-        let core_machine = RiscvAir::machine(CoreSC::default());
+        Self::with_stage_configs(SP1StageConfigs::default())
+    }
+
+    /// Initializes a new [SP1Prover] whose stages use the given FRI parameters.
+    ///
+    /// The recursive verifier programs are built for these parameters, so the recursion keys
+    /// differ from those of [SP1Prover::new] whenever the parameters do.
+    #[instrument(name = "initialize prover", level = "info", skip_all)]
+    pub fn with_stage_configs(configs: SP1StageConfigs) -> Self {
+        let core_machine = RiscvAir::machine(CoreSC::with_security(configs.core));
 
         // Get the recursive verifier and setup the proving and verifying keys.
         let recursion_program = SP1RecursiveVerifier::<InnerConfig, _>::build(&core_machine);
-        let compress_machine = ReduceAir::machine(InnerSC::default());
-        let (rec_pk, rec_vk) = compress_machine.setup(&recursion_program);
+        let recursion_machine = ReduceAir::machine(InnerSC::with_security(configs.recursion));
+        let (rec_pk, rec_vk) = recursion_machine.setup(&recursion_program);
 
-        // Get the deferred program and keys.
+        // Get the deferred program and keys. Deferred proofs are compressed proofs.
+        let compress_machine = ReduceAir::machine(InnerSC::with_security(configs.compress));
         let deferred_program = SP1DeferredVerifier::<InnerConfig, _, _>::build(&compress_machine);
-        let (deferred_pk, deferred_vk) = compress_machine.setup(&deferred_program);
+        let (deferred_pk, deferred_vk) = recursion_machine.setup(&deferred_program);
 
         // Make the reduce program and keys.
         let compress_program = SP1CompressVerifier::<InnerConfig, _, _>::build(
             &compress_machine,
+            &recursion_machine,
             &rec_vk,
             &deferred_vk,
         );
@@ -169,7 +188,7 @@ impl SP1Prover {
         // Get the compress program, machine, and keys.
         let shrink_program =
             SP1RootVerifier::<InnerConfig, _, _>::build(&compress_machine, &compress_vk, true);
-        let shrink_machine = CompressAir::machine(InnerSC::compressed());
+        let shrink_machine = CompressAir::machine(InnerSC::with_security(configs.shrink));
         let (shrink_pk, shrink_vk) = shrink_machine.setup(&shrink_program);
 
         // Get the wrap program, machine, and keys.
@@ -195,6 +214,7 @@ impl SP1Prover {
             wrap_pk,
             wrap_vk,
             core_machine,
+            recursion_machine,
             compress_machine,
             shrink_machine,
             wrap_machine,
@@ -219,7 +239,7 @@ impl SP1Prover {
     #[instrument(name = "execute", level = "info", skip_all)]
     pub fn execute(elf: &[u8], stdin: &SP1Stdin) -> Result<SP1PublicValues, ExecutionError> {
         let program = Program::from(elf);
-        let mut runtime = Runtime::new(program);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
         runtime.write_vecs(&stdin.buffer);
         for (proof, vkey) in stdin.proofs.iter() {
             runtime.write_proof(proof.clone(), vkey.clone());
@@ -235,10 +255,25 @@ impl SP1Prover {
         &self,
         pk: &SP1ProvingKey,
         stdin: &SP1Stdin,
+        opts: SP1ProverOpts,
+        progress: &ProofProgress,
     ) -> Result<SP1CoreProof, SP1CoreProverError> {
-        let config = CoreSC::default();
+        let security = self.core_machine.config().security();
+        if pk.vk.vk.security != security {
+            return Err(SP1CoreProverError::SecurityMismatch {
+                expected: security,
+                found: pk.vk.vk.security,
+            });
+        }
         let program = Program::from(&pk.elf);
-        let (proof, public_values_stream) = sp1_core::utils::prove(program, stdin, config)?;
+        let (proof, public_values_stream) = sp1_core::utils::prove_with_pk(
+            &self.core_machine,
+            &pk.pk,
+            program,
+            stdin,
+            opts.core_opts,
+            progress,
+        )?;
         let public_values = SP1PublicValues::from(&public_values_stream);
         Ok(SP1CoreProof {
             proof: SP1CoreProofData(proof.shard_proofs),
@@ -247,6 +282,93 @@ impl SP1Prover {
         })
     }
 
+    /// Generate shard proofs of a RISC-V program with the core prover, using the given STARK
+    /// configuration instead of [CoreSC].
+    ///
+    /// Only proofs generated with [CoreSC] can be compressed, as the recursion programs verify
+    /// Poseidon2 commitments.
+    #[instrument(name = "prove_core_with_config", level = "info", skip_all)]
+    pub fn prove_core_with_config<SC: StarkGenericConfig + Send + Sync>(
+        config: SC,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        opts: SP1ProverOpts,
+        progress: &ProofProgress,
+    ) -> Result<SP1ProofWithMetadata<SP1CoreProofData<SC>>, SP1CoreProverError>
+    where
+        SC::Challenger: Clone,
+        OpeningProof<SC>: Send + Sync,
+        Com<SC>: Send + Sync,
+        PcsProverData<SC>: Send + Sync,
+        ShardMainData<SC>: Serialize + DeserializeOwned,
+        SC::Val: PrimeField32,
+    {
+        let program = Program::from(elf);
+        let (proof, public_values_stream) =
+            sp1_core::utils::prove(program, stdin, config, opts.core_opts, progress)?;
+        let public_values = SP1PublicValues::from(&public_values_stream);
+        Ok(SP1ProofWithMetadata {
+            proof: SP1CoreProofData(proof.shard_proofs),
+            stdin: stdin.clone(),
+            public_values,
+        })
+    }
+
+    /// Generate shard proofs of a RISC-V program with the core prover, committing to the traces
+    /// with the given hash.
+    pub fn prove_core_with_hash(
+        hash: CoreHash,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        opts: SP1ProverOpts,
+        progress: &ProofProgress,
+    ) -> Result<SP1AnyCoreProof, SP1CoreProverError> {
+        fn erase<SC: StarkGenericConfig>(
+            proof: SP1ProofWithMetadata<SP1CoreProofData<SC>>,
+            wrap: fn(SP1CoreProofData<SC>) -> SP1AnyCoreProofData,
+        ) -> SP1AnyCoreProof {
+            SP1ProofWithMetadata {
+                proof: wrap(proof.proof),
+                stdin: proof.stdin,
+                public_values: proof.public_values,
+            }
+        }
+
+        Ok(match hash {
+            CoreHash::Poseidon2 => erase(
+                Self::prove_core_with_config(BabyBearPoseidon2::new(), elf, stdin, opts, progress)?,
+                SP1AnyCoreProofData::Poseidon2,
+            ),
+            CoreHash::Keccak256 => erase(
+                Self::prove_core_with_config(BabyBearKeccak::new(), elf, stdin, opts, progress)?,
+                SP1AnyCoreProofData::Keccak256,
+            ),
+            CoreHash::Blake3 => erase(
+                Self::prove_core_with_config(BabyBearBlake3::new(), elf, stdin, opts, progress)?,
+                SP1AnyCoreProofData::Blake3,
+            ),
+        })
+    }
+
+    /// Set up the verifying key of a RISC-V program for the configuration selected by `hash`, to
+    /// verify the proofs generated with [SP1Prover::prove_core_with_hash].
+    pub fn setup_core_with_hash(hash: CoreHash, elf: &[u8]) -> SP1AnyCoreVerifyingKey {
+        let program = Program::from(elf);
+        match hash {
+            CoreHash::Poseidon2 => SP1AnyCoreVerifyingKey::Poseidon2(
+                RiscvAir::machine(BabyBearPoseidon2::new())
+                    .setup(&program)
+                    .1,
+            ),
+            CoreHash::Keccak256 => SP1AnyCoreVerifyingKey::Keccak256(
+                RiscvAir::machine(BabyBearKeccak::new()).setup(&program).1,
+            ),
+            CoreHash::Blake3 => SP1AnyCoreVerifyingKey::Blake3(
+                RiscvAir::machine(BabyBearBlake3::new()).setup(&program).1,
+            ),
+        }
+    }
+
     pub fn get_recursion_core_inputs<'a>(
         &'a self,
         vk: &'a StarkVerifyingKey<CoreSC>,
@@ -370,9 +492,17 @@ impl SP1Prover {
         vk: &SP1VerifyingKey,
         proof: SP1CoreProof,
         deferred_proofs: Vec<ShardProof<InnerSC>>,
+        opts: SP1ProverOpts,
+        progress: &ProofProgress,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        let recursion_opts = opts.recursion_opts;
+        let compress_opts = opts.compress_opts;
+
         // Set the batch size for the reduction tree.
-        let batch_size = 2;
+        let batch_size = compress_opts.batch_size;
+        if batch_size < 2 {
+            return Err(SP1RecursionProverError::InvalidBatchSize(batch_size));
+        }
 
         let shard_proofs = &proof.proof.0;
         // Get the leaf challenger.
@@ -383,15 +513,7 @@ impl SP1Prover {
             leaf_challenger.observe_slice(&proof.public_values[0..self.core_machine.num_pv_elts()]);
         });
 
-        // Setup the reconstruct commitments flags to false and save its state.
-        let rc = env::var(RECONSTRUCT_COMMITMENTS_ENV_VAR).unwrap_or_default();
-        env::set_var(RECONSTRUCT_COMMITMENTS_ENV_VAR, "false");
-
         // Run the recursion and reduce programs.
-
-        // Run the recursion programs.
-        let mut records = Vec::new();
-
         let (core_inputs, deferred_inputs) = self.get_first_layer_inputs(
             vk,
             &leaf_challenger,
@@ -400,61 +522,61 @@ impl SP1Prover {
             batch_size,
         );
 
-        for input in core_inputs {
-            let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
-                &self.recursion_program,
-                self.compress_machine.config().perm.clone(),
-            );
-
-            let mut witness_stream = Vec::new();
-            witness_stream.extend(input.write());
-
-            runtime.witness_stream = witness_stream.into();
-            runtime.run();
-            runtime.print_stats();
+        // Run the recursion programs and the deferred proofs programs in parallel.
+        let records = core_inputs
+            .into_par_iter()
+            .map(|input| {
+                let record = self.run_recursion_program(&self.recursion_program, input);
+                (record, ReduceProgramType::Core)
+            })
+            .chain(deferred_inputs.into_par_iter().map(|input| {
+                let record = self.run_recursion_program(&self.deferred_program, input);
+                (record, ReduceProgramType::Deferred)
+            }))
+            .collect::<Vec<_>>();
 
-            records.push((runtime.record, ReduceProgramType::Core));
+        // The unbalanced tree blocks the calling thread while it waits for proofs, so it needs at
+        // least one other thread to make progress.
+        let reduce_proof = match compress_opts.shape {
+            ReduceTreeShape::Unbalanced if rayon::current_num_threads() > 1 => {
+                self.reduce_unbalanced(records, batch_size, recursion_opts, progress)
+            }
+            _ => self.reduce_balanced(records, batch_size, recursion_opts, progress),
         }
+        .map_err(SP1RecursionProverError::Cancelled)?;
 
-        // Run the deferred proofs programs.
-        for input in deferred_inputs {
-            let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
-                &self.deferred_program,
-                self.compress_machine.config().perm.clone(),
-            );
-
-            let mut witness_stream = Vec::new();
-            witness_stream.extend(input.write());
-
-            runtime.witness_stream = witness_stream.into();
-            runtime.run();
-            runtime.print_stats();
-
-            records.push((runtime.record, ReduceProgramType::Deferred));
-        }
+        Ok(SP1ReduceProof {
+            proof: reduce_proof,
+        })
+    }
 
-        // Prove all recursion programs and recursion deferred programs and verify the proofs.
+    /// Prove the first layer of recursion programs and reduce the proofs layer by layer until
+    /// there is one proof remaining.
+    fn reduce_balanced(
+        &self,
+        records: Vec<(RecursionRecord<Val<InnerSC>>, ReduceProgramType)>,
+        batch_size: usize,
+        opts: SP1CoreOpts,
+        progress: &ProofProgress,
+    ) -> Result<ShardProof<InnerSC>, ProofCancelled> {
+        // Every node of the tree is a step of the progress.
+        let done = AtomicUsize::new(0);
+        let mut num_nodes = num_balanced_tree_nodes(records.len(), batch_size);
+        let report = |total| {
+            let index = done.fetch_add(1, Ordering::Relaxed) + 1;
+            progress.report(ProofPhase::Compress, index, Some(total));
+        };
 
         // Make the recursive proofs for core and deferred proofs.
         let first_layer_proofs = records
             .into_par_iter()
             .map(|(record, kind)| {
-                let pk = match kind {
-                    ReduceProgramType::Core => &self.rec_pk,
-                    ReduceProgramType::Deferred => &self.deferred_pk,
-                    ReduceProgramType::Reduce => unreachable!(),
-                };
-                let mut recursive_challenger = self.compress_machine.config().challenger();
-                (
-                    self.compress_machine.prove::<LocalProver<_, _>>(
-                        pk,
-                        record,
-                        &mut recursive_challenger,
-                    ),
-                    kind,
-                )
+                progress.check()?;
+                let proof = self.prove_first_layer(record, kind, opts);
+                report(num_nodes);
+                Ok((proof, kind))
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, ProofCancelled>>()?;
 
         // Chain all the individual shard proofs.
         let mut reduce_proofs = first_layer_proofs
@@ -462,6 +584,11 @@ impl SP1Prover {
             .flat_map(|(proof, kind)| proof.shard_proofs.into_iter().map(move |p| (p, kind)))
             .collect::<Vec<_>>();
 
+        // A first layer proof can have several shards, so count the nodes again.
+        num_nodes = done.load(Ordering::Relaxed)
+            + num_balanced_tree_nodes(reduce_proofs.len(), batch_size)
+            - reduce_proofs.len();
+
         // Iterate over the recursive proof batches until there is one proof remaining.
         let mut is_complete;
         loop {
@@ -470,47 +597,171 @@ impl SP1Prover {
             reduce_proofs = reduce_proofs
                 .par_chunks(batch_size)
                 .map(|batch| {
+                    progress.check()?;
                     let (shard_proofs, kinds) =
                         batch.iter().cloned().unzip::<_, _, Vec<_>, Vec<_>>();
-
-                    let input = SP1ReduceMemoryLayout {
-                        compress_vk: &self.compress_vk,
-                        recursive_machine: &self.compress_machine,
-                        shard_proofs,
-                        kinds,
-                        is_complete,
-                    };
-
-                    let proof = self.compress_machine_proof(
-                        input,
-                        &self.compress_program,
-                        &self.compress_pk,
-                    );
-                    (proof, ReduceProgramType::Reduce)
+                    let proof = self.reduce_batch(shard_proofs, kinds, is_complete, opts);
+                    report(num_nodes);
+                    Ok((proof, ReduceProgramType::Reduce))
                 })
-                .collect();
+                .collect::<Result<_, ProofCancelled>>()?;
 
             if reduce_proofs.len() == 1 {
                 break;
             }
         }
         debug_assert_eq!(reduce_proofs.len(), 1);
-        let reduce_proof = reduce_proofs.pop().unwrap();
+        Ok(reduce_proofs.pop().unwrap().0)
+    }
 
-        // Restore the prover parameters.
-        env::set_var(RECONSTRUCT_COMMITMENTS_ENV_VAR, rc);
+    /// Prove the first layer of recursion programs and reduce contiguous proofs as soon as
+    /// `batch_size` of them are available, without waiting for the rest of the layer.
+    fn reduce_unbalanced(
+        &self,
+        records: Vec<(RecursionRecord<Val<InnerSC>>, ReduceProgramType)>,
+        batch_size: usize,
+        opts: SP1CoreOpts,
+        progress: &ProofProgress,
+    ) -> Result<ShardProof<InnerSC>, ProofCancelled> {
+        let num_leaves = records.len();
+        // The channel carries the panic of a job, if any, so that it is resumed here instead of
+        // leaving the loop waiting for a proof that never comes.
+        let (tx, rx) = mpsc::channel::<std::thread::Result<Result<ReduceNode, ProofCancelled>>>();
+        let mut ready = BTreeMap::new();
+
+        // The shape of the tree depends on the order in which proofs finish, so the number of
+        // nodes is not known in advance.
+        let done = AtomicUsize::new(0);
+        let report = || {
+            let index = done.fetch_add(1, Ordering::Relaxed) + 1;
+            progress.report(ProofPhase::Compress, index, None);
+        };
 
-        Ok(SP1ReduceProof {
-            proof: reduce_proof.0,
-        })
+        let result: Result<(), ProofCancelled> = rayon::in_place_scope(|s| {
+            // Prove the leaves of the tree, sending each proof back as soon as it is done.
+            for (index, (record, kind)) in records.into_iter().enumerate() {
+                let tx = tx.clone();
+                let report = &report;
+                s.spawn(move |_| {
+                    let node = panic::catch_unwind(AssertUnwindSafe(|| {
+                        progress.check().map(|_| {
+                            let proof = self
+                                .prove_first_layer(record, kind, opts)
+                                .shard_proofs
+                                .pop()
+                                .unwrap();
+                            report();
+                            ReduceNode {
+                                range: index..index + 1,
+                                proof,
+                                kind,
+                            }
+                        })
+                    }));
+                    tx.send(node).unwrap();
+                });
+            }
+
+            let mut in_flight = num_leaves;
+            loop {
+                // Stop at the first cancelled node. The nodes in flight still finish, but their
+                // proofs are dropped.
+                let node = rx
+                    .recv()
+                    .unwrap()
+                    .unwrap_or_else(|payload| panic::resume_unwind(payload))?;
+                in_flight -= 1;
+                ready.insert(node.range.start, node);
+
+                // The tree is done once a single reduce proof covers every leaf.
+                if in_flight == 0 && ready.len() == 1 {
+                    let root = ready.values().next().unwrap();
+                    if matches!(root.kind, ReduceProgramType::Reduce) {
+                        break;
+                    }
+                }
+
+                for batch in take_reduce_batches(&mut ready, batch_size, in_flight == 0) {
+                    let range = batch[0].range.start..batch[batch.len() - 1].range.end;
+                    let is_complete = range.start == 0 && range.end == num_leaves;
+                    tracing::debug!("Reducing proofs {:?}", range);
+
+                    in_flight += 1;
+                    let tx = tx.clone();
+                    let report = &report;
+                    s.spawn(move |_| {
+                        let node = panic::catch_unwind(AssertUnwindSafe(|| {
+                            progress.check().map(|_| {
+                                let (shard_proofs, kinds) = batch
+                                    .into_iter()
+                                    .map(|node| (node.proof, node.kind))
+                                    .unzip::<_, _, Vec<_>, Vec<_>>();
+                                let proof =
+                                    self.reduce_batch(shard_proofs, kinds, is_complete, opts);
+                                report();
+                                ReduceNode {
+                                    range,
+                                    proof,
+                                    kind: ReduceProgramType::Reduce,
+                                }
+                            })
+                        }));
+                        tx.send(node).unwrap();
+                    });
+                }
+            }
+            Ok(())
+        });
+        result?;
+
+        Ok(ready.pop_first().unwrap().1.proof)
     }
 
-    pub fn compress_machine_proof(
+    /// Prove the record of a first layer recursion program of the given kind.
+    fn prove_first_layer(
         &self,
-        input: impl Hintable<InnerConfig>,
-        program: &RecursionProgram<BabyBear>,
-        pk: &StarkProvingKey<InnerSC>,
+        record: RecursionRecord<Val<InnerSC>>,
+        kind: ReduceProgramType,
+        opts: SP1CoreOpts,
+    ) -> MachineProof<InnerSC> {
+        let pk = match kind {
+            ReduceProgramType::Core => &self.rec_pk,
+            ReduceProgramType::Deferred => &self.deferred_pk,
+            ReduceProgramType::Reduce => unreachable!(),
+        };
+        let mut recursive_challenger = self.recursion_machine.config().challenger();
+        self.recursion_machine.prove::<LocalProver<_, _>>(
+            pk,
+            record,
+            &mut recursive_challenger,
+            opts,
+        )
+    }
+
+    /// Reduce a batch of contiguous proofs into a single proof with the compress program.
+    fn reduce_batch(
+        &self,
+        shard_proofs: Vec<ShardProof<InnerSC>>,
+        kinds: Vec<ReduceProgramType>,
+        is_complete: bool,
+        opts: SP1CoreOpts,
     ) -> ShardProof<InnerSC> {
+        let input = SP1ReduceMemoryLayout {
+            compress_vk: &self.compress_vk,
+            recursive_machine: &self.compress_machine,
+            shard_proofs,
+            kinds,
+            is_complete,
+        };
+        self.compress_machine_proof(input, &self.compress_program, &self.compress_pk, opts)
+    }
+
+    /// Execute a recursion program on the given input and return its execution record.
+    fn run_recursion_program(
+        &self,
+        program: &RecursionProgram<BabyBear>,
+        input: impl Hintable<InnerConfig>,
+    ) -> RecursionRecord<Val<InnerSC>> {
         let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
             program,
             self.compress_machine.config().perm.clone(),
@@ -522,10 +773,21 @@ impl SP1Prover {
         runtime.witness_stream = witness_stream.into();
         runtime.run();
         runtime.print_stats();
+        runtime.record
+    }
+
+    pub fn compress_machine_proof(
+        &self,
+        input: impl Hintable<InnerConfig>,
+        program: &RecursionProgram<BabyBear>,
+        pk: &StarkProvingKey<InnerSC>,
+        opts: SP1CoreOpts,
+    ) -> ShardProof<InnerSC> {
+        let record = self.run_recursion_program(program, input);
 
         let mut recursive_challenger = self.compress_machine.config().challenger();
         self.compress_machine
-            .prove::<LocalProver<_, _>>(pk, runtime.record, &mut recursive_challenger)
+            .prove::<LocalProver<_, _>>(pk, record, &mut recursive_challenger, opts)
             .shard_proofs
             .pop()
             .unwrap()
@@ -536,10 +798,12 @@ impl SP1Prover {
     pub fn shrink(
         &self,
         reduced_proof: SP1ReduceProof<InnerSC>,
+        opts: SP1ProverOpts,
+        progress: &ProofProgress,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
-        // Setup the prover parameters.
-        let rc = env::var(RECONSTRUCT_COMMITMENTS_ENV_VAR).unwrap_or_default();
-        env::set_var(RECONSTRUCT_COMMITMENTS_ENV_VAR, "false");
+        progress
+            .check()
+            .map_err(SP1RecursionProverError::Cancelled)?;
 
         // Make the compress proof.
         let input = SP1RootMemoryLayout {
@@ -568,10 +832,9 @@ impl SP1Prover {
             &self.shrink_pk,
             runtime.record,
             &mut compress_challenger,
+            opts.recursion_opts,
         );
-
-        // Restore the prover parameters.
-        env::set_var(RECONSTRUCT_COMMITMENTS_ENV_VAR, rc);
+        progress.report(ProofPhase::Shrink, 1, Some(1));
 
         Ok(SP1ReduceProof {
             proof: compress_proof.shard_proofs.pop().unwrap(),
@@ -583,10 +846,12 @@ impl SP1Prover {
     pub fn wrap_bn254(
         &self,
         compressed_proof: SP1ReduceProof<InnerSC>,
+        opts: SP1ProverOpts,
+        progress: &ProofProgress,
     ) -> Result<SP1ReduceProof<OuterSC>, SP1RecursionProverError> {
-        // Setup the prover parameters.
-        let rc = env::var(RECONSTRUCT_COMMITMENTS_ENV_VAR).unwrap_or_default();
-        env::set_var(RECONSTRUCT_COMMITMENTS_ENV_VAR, "false");
+        progress
+            .check()
+            .map_err(SP1RecursionProverError::Cancelled)?;
 
         let input = SP1RootMemoryLayout {
             machine: &self.shrink_machine,
@@ -615,6 +880,7 @@ impl SP1Prover {
             &self.wrap_pk,
             runtime.record,
             &mut wrap_challenger,
+            opts.recursion_opts,
         );
         let elapsed = time.elapsed();
         tracing::debug!("Wrap proving time: {:?}", elapsed);
@@ -630,9 +896,7 @@ impl SP1Prover {
             e => panic!("Proof verification failed: {:?}", e),
         }
         tracing::info!("Wrapping successful");
-
-        // Restore the prover parameters.
-        env::set_var(RECONSTRUCT_COMMITMENTS_ENV_VAR, rc);
+        progress.report(ProofPhase::Wrap, 1, Some(1));
 
         Ok(SP1ReduceProof {
             proof: wrap_proof.shard_proofs.pop().unwrap(),
@@ -690,9 +954,66 @@ impl SP1Prover {
     }
 }
 
+/// A proof in the reduction tree covering the contiguous range of first layer proofs `range`.
+struct ReduceNode<P = ShardProof<InnerSC>> {
+    range: Range<usize>,
+    proof: P,
+    kind: ReduceProgramType,
+}
+
+/// The number of nodes of a balanced reduction tree over `num_leaves` proofs, counting the leaves.
+fn num_balanced_tree_nodes(num_leaves: usize, batch_size: usize) -> usize {
+    let mut num_nodes = num_leaves;
+    let mut layer_size = num_leaves;
+    loop {
+        layer_size = layer_size.div_ceil(batch_size);
+        num_nodes += layer_size;
+        if layer_size <= 1 {
+            return num_nodes;
+        }
+    }
+}
+
+/// Take the batches of contiguous proofs from `ready` which can be reduced right away.
+///
+/// A batch is formed from `batch_size` proofs covering adjacent ranges. If `flush` is set, no more
+/// proofs are coming and the remaining proofs, if they fit in one batch, are reduced together.
+fn take_reduce_batches<P>(
+    ready: &mut BTreeMap<usize, ReduceNode<P>>,
+    batch_size: usize,
+    flush: bool,
+) -> Vec<Vec<ReduceNode<P>>> {
+    if flush && ready.len() <= batch_size {
+        return vec![std::mem::take(ready).into_values().collect()];
+    }
+
+    let mut batches = Vec::new();
+    let mut run: Vec<ReduceNode<P>> = Vec::new();
+    for (_, node) in std::mem::take(ready) {
+        // If the node is not adjacent to the current run, put the run back.
+        if run
+            .last()
+            .is_some_and(|last| last.range.end != node.range.start)
+        {
+            for pending in run.drain(..) {
+                ready.insert(pending.range.start, pending);
+            }
+        }
+        run.push(node);
+        if run.len() == batch_size {
+            batches.push(std::mem::take(&mut run));
+        }
+    }
+    for pending in run {
+        ready.insert(pending.range.start, pending);
+    }
+    batches
+}
+
 #[cfg(test)]
 mod tests {
 
+    use std::collections::VecDeque;
     use std::fs::File;
     use std::io::{Read, Write};
 
@@ -703,6 +1024,7 @@ mod tests {
     use p3_field::PrimeField32;
     use serial_test::serial;
     use sp1_core::io::SP1Stdin;
+    use sp1_core::stark::SecurityConfig;
     use sp1_core::utils::setup_logger;
 
     /// Tests an end-to-end workflow of proving a program across the entire proof generation
@@ -719,32 +1041,34 @@ mod tests {
 
         tracing::info!("initializing prover");
         let prover = SP1Prover::new();
+        let opts = SP1ProverOpts::default();
+        let progress = ProofProgress::default();
 
         tracing::info!("setup elf");
         let (pk, vk) = prover.setup(elf);
 
         tracing::info!("prove core");
         let stdin = SP1Stdin::new();
-        let core_proof = prover.prove_core(&pk, &stdin)?;
+        let core_proof = prover.prove_core(&pk, &stdin, opts, &progress)?;
         let public_values = core_proof.public_values.clone();
 
         tracing::info!("verify core");
         prover.verify(&core_proof.proof, &vk)?;
 
         tracing::info!("compress");
-        let compressed_proof = prover.compress(&vk, core_proof, vec![])?;
+        let compressed_proof = prover.compress(&vk, core_proof, vec![], opts, &progress)?;
 
         tracing::info!("verify compressed");
         prover.verify_compressed(&compressed_proof, &vk)?;
 
         tracing::info!("shrink");
-        let shrink_proof = prover.shrink(compressed_proof)?;
+        let shrink_proof = prover.shrink(compressed_proof, opts, &progress)?;
 
         tracing::info!("verify shrink");
         prover.verify_shrink(&shrink_proof, &vk)?;
 
         tracing::info!("wrap bn254");
-        let wrapped_bn254_proof = prover.wrap_bn254(shrink_proof)?;
+        let wrapped_bn254_proof = prover.wrap_bn254(shrink_proof, opts, &progress)?;
         let bytes = bincode::serialize(&wrapped_bn254_proof).unwrap();
 
         // Save the proof.
@@ -795,6 +1119,8 @@ mod tests {
 
         tracing::info!("initializing prover");
         let prover = SP1Prover::new();
+        let opts = SP1ProverOpts::default();
+        let progress = ProofProgress::default();
 
         tracing::info!("setup keccak elf");
         let (keccak_pk, keccak_vk) = prover.setup(keccak_elf);
@@ -806,7 +1132,7 @@ mod tests {
         let mut stdin = SP1Stdin::new();
         stdin.write(&1usize);
         stdin.write(&vec![0u8, 0, 0]);
-        let deferred_proof_1 = prover.prove_core(&keccak_pk, &stdin)?;
+        let deferred_proof_1 = prover.prove_core(&keccak_pk, &stdin, opts, &progress)?;
         let pv_1 = deferred_proof_1.public_values.as_slice().to_vec().clone();
 
         // Generate a second proof of keccak of various inputs.
@@ -816,16 +1142,18 @@ mod tests {
         stdin.write(&vec![0u8, 1, 2]);
         stdin.write(&vec![2, 3, 4]);
         stdin.write(&vec![5, 6, 7]);
-        let deferred_proof_2 = prover.prove_core(&keccak_pk, &stdin)?;
+        let deferred_proof_2 = prover.prove_core(&keccak_pk, &stdin, opts, &progress)?;
         let pv_2 = deferred_proof_2.public_values.as_slice().to_vec().clone();
 
         // Generate recursive proof of first subproof.
         tracing::info!("compress subproof 1");
-        let deferred_reduce_1 = prover.compress(&keccak_vk, deferred_proof_1, vec![])?;
+        let deferred_reduce_1 =
+            prover.compress(&keccak_vk, deferred_proof_1, vec![], opts, &progress)?;
 
         // Generate recursive proof of second subproof.
         tracing::info!("compress subproof 2");
-        let deferred_reduce_2 = prover.compress(&keccak_vk, deferred_proof_2, vec![])?;
+        let deferred_reduce_2 =
+            prover.compress(&keccak_vk, deferred_proof_2, vec![], opts, &progress)?;
 
         // Run verify program with keccak vkey, subproofs, and their committed values.
         let mut stdin = SP1Stdin::new();
@@ -843,7 +1171,7 @@ mod tests {
         stdin.write_proof(deferred_reduce_2.proof.clone(), keccak_vk.vk.clone());
 
         tracing::info!("proving verify program (core)");
-        let verify_proof = prover.prove_core(&verify_pk, &stdin)?;
+        let verify_proof = prover.prove_core(&verify_pk, &stdin, opts, &progress)?;
 
         // Generate recursive proof of verify program
         tracing::info!("compress verify program");
@@ -855,6 +1183,8 @@ mod tests {
                 deferred_reduce_2.proof.clone(),
                 deferred_reduce_2.proof,
             ],
+            opts,
+            &progress,
         )?;
         let reduce_pv: &RecursionPublicValues<_> =
             verify_reduce.proof.public_values.as_slice().borrow();
@@ -866,4 +1196,164 @@ mod tests {
 
         Ok(())
     }
+
+    fn leaf(index: usize) -> ReduceNode<()> {
+        ReduceNode {
+            range: index..index + 1,
+            proof: (),
+            kind: ReduceProgramType::Core,
+        }
+    }
+
+    #[test]
+    fn test_take_reduce_batches() {
+        let mut ready = [0, 1, 3, 4, 5]
+            .into_iter()
+            .map(|i| (i, leaf(i)))
+            .collect::<BTreeMap<_, _>>();
+        let batches = take_reduce_batches(&mut ready, 2, false);
+        let ranges = batches
+            .iter()
+            .map(|batch| batch.iter().map(|n| n.range.clone()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec![vec![0..1, 1..2], vec![3..4, 4..5]]);
+        assert_eq!(ready.keys().copied().collect::<Vec<_>>(), vec![5]);
+
+        // Without flushing, a run shorter than the batch size waits for more proofs.
+        assert!(take_reduce_batches(&mut ready, 2, false).is_empty());
+        assert_eq!(ready.len(), 1);
+
+        // When flushing, the remaining proofs are reduced together.
+        ready.insert(6, leaf(6));
+        let batches = take_reduce_batches(&mut ready, 3, true);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].len(), 2);
+        assert!(ready.is_empty());
+    }
+
+    /// Runs the scheduling of [SP1Prover::reduce_unbalanced] with the leaves finishing in the
+    /// given order and every reduction finishing after the leaves in flight, returning the ranges
+    /// of the reduce nodes in the order they are created.
+    fn simulate_unbalanced_tree(order: &[usize], batch_size: usize) -> Vec<Range<usize>> {
+        let num_leaves = order.len();
+        let mut finished = order.iter().map(|&i| leaf(i)).collect::<VecDeque<_>>();
+        let mut ready = BTreeMap::new();
+        let mut in_flight = num_leaves;
+        let mut reduced = Vec::new();
+        loop {
+            let node = finished.pop_front().expect("the tree never completed");
+            in_flight -= 1;
+            ready.insert(node.range.start, node);
+
+            if in_flight == 0 && ready.len() == 1 {
+                let root = ready.values().next().unwrap();
+                if matches!(root.kind, ReduceProgramType::Reduce) {
+                    assert_eq!(root.range, 0..num_leaves);
+                    return reduced;
+                }
+            }
+
+            for batch in take_reduce_batches(&mut ready, batch_size, in_flight == 0) {
+                assert!(!batch.is_empty() && batch.len() <= batch_size);
+                for pair in batch.windows(2) {
+                    assert_eq!(pair[0].range.end, pair[1].range.start);
+                }
+                let range = batch[0].range.start..batch[batch.len() - 1].range.end;
+                reduced.push(range.clone());
+                in_flight += 1;
+                finished.push_back(ReduceNode {
+                    range,
+                    proof: (),
+                    kind: ReduceProgramType::Reduce,
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn test_unbalanced_reduce_tree() {
+        // Early contiguous leaves are reduced before the later leaves are done.
+        let reduced = simulate_unbalanced_tree(&[3, 0, 1, 2, 6, 4, 5], 3);
+        assert_eq!(reduced[0], 0..3);
+        assert_eq!(reduced.last().unwrap().clone(), 0..7);
+
+        // A single leaf is still wrapped in a reduce proof.
+        assert_eq!(simulate_unbalanced_tree(&[0], 2), vec![0..1]);
+
+        for batch_size in 2..5 {
+            let order = (0..13).rev().collect::<Vec<_>>();
+            let reduced = simulate_unbalanced_tree(&order, batch_size);
+            assert_eq!(reduced.last().unwrap().clone(), 0..13);
+        }
+    }
+
+    /// Tests that a proving key saved to disk can be loaded and used to prove without a setup.
+    #[test]
+    #[serial]
+    fn test_proving_key_reuse() -> Result<()> {
+        let elf = include_bytes!("../../tests/fibonacci/elf/riscv32im-succinct-zkvm-elf");
+        let prover = SP1Prover::new();
+        let (pk, vk) = prover.setup(elf);
+
+        let file = tempfile::NamedTempFile::new()?;
+        pk.save(file.path())?;
+        let loaded = SP1ProvingKey::load(file.path())?;
+        assert_eq!(loaded.elf, pk.elf);
+        assert_eq!(loaded.pk.traces.len(), pk.pk.traces.len());
+        assert_eq!(loaded.vk.hash_babybear(), vk.hash_babybear());
+
+        let proof = prover.prove_core(
+            &loaded,
+            &SP1Stdin::new(),
+            SP1ProverOpts::default(),
+            &ProofProgress::default(),
+        )?;
+        prover.verify(&proof.proof, &vk)?;
+
+        std::fs::write(file.path(), b"not a proving key")?;
+        let err = SP1ProvingKey::load(file.path()).err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<ProvingKeyFileError>(),
+            Some(ProvingKeyFileError::InvalidMagic)
+        ));
+        Ok(())
+    }
+
+    /// Tests that proving with a key generated for other FRI parameters than the core prover's
+    /// fails instead of generating an unverifiable proof.
+    #[test]
+    #[serial]
+    fn test_proving_key_security_mismatch() {
+        let elf = include_bytes!("../../tests/fibonacci/elf/riscv32im-succinct-zkvm-elf");
+        let prover = SP1Prover::new();
+        let (mut pk, _) = prover.setup(elf);
+        pk.vk.vk.security = SecurityConfig::new(2, 50, 16);
+
+        let result = prover.prove_core(
+            &pk,
+            &SP1Stdin::new(),
+            SP1ProverOpts::default(),
+            &ProofProgress::default(),
+        );
+        assert!(matches!(
+            result,
+            Err(SP1CoreProverError::SecurityMismatch { .. })
+        ));
+    }
+
+    /// Tests that the keys of each stage are generated for the FRI parameters chosen for it.
+    #[test]
+    #[serial]
+    fn test_stage_configs() {
+        let configs = SP1StageConfigs {
+            recursion: SecurityConfig::new(2, 50, 16),
+            ..SP1StageConfigs::default()
+        };
+        let prover = SP1Prover::with_stage_configs(configs);
+        assert_eq!(prover.core_machine.config().security(), configs.core);
+        assert_eq!(prover.rec_vk.security, configs.recursion);
+        assert_eq!(prover.deferred_vk.security, configs.recursion);
+        assert_eq!(prover.compress_vk.security, configs.compress);
+        assert_eq!(prover.shrink_vk.security, configs.shrink);
+    }
 }
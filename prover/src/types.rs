@@ -1,7 +1,10 @@
 use std::borrow::Borrow;
+use std::fmt;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::str::FromStr;
 use std::{fs::File, path::Path};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use p3_baby_bear::BabyBear;
 use p3_bn254_fr::Bn254Fr;
 use p3_commit::{Pcs, TwoAdicMultiplicativeCoset};
@@ -10,11 +13,16 @@ use p3_field::{AbstractField, PrimeField32, TwoAdicField};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sp1_core::{
     io::{SP1PublicValues, SP1Stdin},
-    stark::{ShardProof, StarkGenericConfig, StarkProvingKey, StarkVerifyingKey},
-    utils::DIGEST_SIZE,
+    stark::{
+        MachineVerificationError, SecurityConfig, ShardProof, StarkGenericConfig, StarkProvingKey,
+        StarkVerifyingKey,
+    },
+    utils::{
+        BabyBearBlake3, BabyBearKeccak, BabyBearPoseidon2, ProofCancelled, SP1CoreOpts, DIGEST_SIZE,
+    },
 };
 use sp1_primitives::poseidon2_hash;
-use sp1_recursion_core::{air::RecursionPublicValues, stark::config::BabyBearPoseidon2Outer};
+use sp1_recursion_core::air::RecursionPublicValues;
 use sp1_recursion_gnark_ffi::{plonk_bn254::PlonkBn254Proof, Groth16Proof};
 use thiserror::Error;
 
@@ -31,6 +39,90 @@ pub struct SP1ProvingKey {
     pub vk: SP1VerifyingKey,
 }
 
+/// The bytes at the start of every proving key file.
+pub const PROVING_KEY_MAGIC: [u8; 8] = *b"SP1PRVKY";
+
+/// The version of the layout of proving key files, incremented when the layout of the file or of
+/// the serialized [SP1ProvingKey] changes.
+pub const PROVING_KEY_FORMAT_VERSION: u32 = 1;
+
+/// The version of SP1 recorded in the proving keys saved by this crate.
+pub const SP1_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// An error returned when loading a proving key file.
+#[derive(Error, Debug)]
+pub enum ProvingKeyFileError {
+    #[error(
+        "not an SP1 proving key file (keys saved before the key file format are not supported)"
+    )]
+    InvalidMagic,
+    #[error("unsupported proving key file format version {found}, expected {expected}")]
+    FormatVersionMismatch { found: u32, expected: u32 },
+    #[error("the proving key was saved by SP1 {found}, but this is SP1 {expected}")]
+    Sp1VersionMismatch { found: String, expected: String },
+}
+
+impl SP1ProvingKey {
+    /// Saves the key to a file, including the preprocessed traces and their commitment data, so
+    /// that it can be loaded later instead of running the setup again.
+    ///
+    /// Like proof files, the file starts with the [PROVING_KEY_MAGIC] bytes and the
+    /// [PROVING_KEY_FORMAT_VERSION] as a little-endian u32, followed by the bincode-encoded
+    /// [SP1_VERSION] and the bincode-encoded key.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("failed to create proving key file {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(&PROVING_KEY_MAGIC)?;
+        writer.write_all(&PROVING_KEY_FORMAT_VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut writer, SP1_VERSION)?;
+        bincode::serialize_into(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Loads a key saved with [SP1ProvingKey::save], checking that it was saved by the same
+    /// version of SP1, as the preprocessed traces depend on the chips of the machine.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("failed to open proving key file {}", path.display()))?;
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0u8; PROVING_KEY_MAGIC.len()];
+        reader
+            .read_exact(&mut magic)
+            .map_err(|_| ProvingKeyFileError::InvalidMagic)?;
+        if magic != PROVING_KEY_MAGIC {
+            return Err(ProvingKeyFileError::InvalidMagic.into());
+        }
+
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != PROVING_KEY_FORMAT_VERSION {
+            return Err(ProvingKeyFileError::FormatVersionMismatch {
+                found: version,
+                expected: PROVING_KEY_FORMAT_VERSION,
+            }
+            .into());
+        }
+
+        let sp1_version: String = bincode::deserialize_from(&mut reader)
+            .context("failed to deserialize proving key header")?;
+        if sp1_version != SP1_VERSION {
+            return Err(ProvingKeyFileError::Sp1VersionMismatch {
+                found: sp1_version,
+                expected: SP1_VERSION.to_string(),
+            }
+            .into());
+        }
+
+        bincode::deserialize_from(&mut reader).context("failed to deserialize proving key")
+    }
+}
+
 /// The information necessary to verify a proof for a given RISC-V program.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct SP1VerifyingKey {
@@ -149,8 +241,11 @@ pub type SP1Groth16Proof = SP1ProofWithMetadata<SP1Groth16ProofData>;
 /// An SP1 proof that has been wrapped into a single Plonk proof and can be verified onchain.
 pub type SP1PlonkProof = SP1ProofWithMetadata<SP1PlonkProofData>;
 
+/// The shard proofs of a core proof. Defaults to the [CoreSC] configuration, which is the only one
+/// the recursion programs can verify.
 #[derive(Serialize, Deserialize, Clone)]
-pub struct SP1CoreProofData(pub Vec<ShardProof<CoreSC>>);
+#[serde(bound = "")]
+pub struct SP1CoreProofData<SC: StarkGenericConfig = CoreSC>(pub Vec<ShardProof<SC>>);
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SP1ReducedProofData(pub ShardProof<InnerSC>);
 
@@ -160,6 +255,106 @@ pub struct SP1Groth16ProofData(pub Groth16Proof);
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SP1PlonkProofData(pub PlonkBn254Proof);
 
+/// The hash used to commit to the traces of a core proof, which selects its configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CoreHash {
+    /// Poseidon2 over BabyBear ([BabyBearPoseidon2]). Only these proofs can be compressed.
+    #[default]
+    Poseidon2,
+    /// Keccak-256 ([BabyBearKeccak]), which is cheap to verify on the EVM.
+    Keccak256,
+    /// BLAKE3 ([BabyBearBlake3]).
+    Blake3,
+}
+
+impl CoreHash {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            CoreHash::Poseidon2 => "poseidon2",
+            CoreHash::Keccak256 => "keccak256",
+            CoreHash::Blake3 => "blake3",
+        }
+    }
+}
+
+impl fmt::Display for CoreHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for CoreHash {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "poseidon2" => Ok(CoreHash::Poseidon2),
+            "keccak256" => Ok(CoreHash::Keccak256),
+            "blake3" => Ok(CoreHash::Blake3),
+            _ => Err(format!(
+                "unknown hash `{}`, expected one of poseidon2, keccak256 or blake3",
+                s
+            )),
+        }
+    }
+}
+
+/// The shard proofs of a core proof whose configuration was selected at runtime with a
+/// [CoreHash].
+#[derive(Serialize, Deserialize, Clone)]
+pub enum SP1AnyCoreProofData {
+    Poseidon2(SP1CoreProofData<BabyBearPoseidon2>),
+    Keccak256(SP1CoreProofData<BabyBearKeccak>),
+    Blake3(SP1CoreProofData<BabyBearBlake3>),
+}
+
+impl SP1AnyCoreProofData {
+    /// The hash the proof was generated with.
+    pub const fn hash(&self) -> CoreHash {
+        match self {
+            SP1AnyCoreProofData::Poseidon2(_) => CoreHash::Poseidon2,
+            SP1AnyCoreProofData::Keccak256(_) => CoreHash::Keccak256,
+            SP1AnyCoreProofData::Blake3(_) => CoreHash::Blake3,
+        }
+    }
+}
+
+/// A core proof generated with the configuration selected by a [CoreHash].
+pub type SP1AnyCoreProof = SP1ProofWithMetadata<SP1AnyCoreProofData>;
+
+/// The verifying key of a program for the configuration selected by a [CoreHash], which records
+/// the [SecurityConfig] its proofs are verified with.
+#[derive(Serialize, Deserialize, Clone)]
+pub enum SP1AnyCoreVerifyingKey {
+    Poseidon2(StarkVerifyingKey<BabyBearPoseidon2>),
+    Keccak256(StarkVerifyingKey<BabyBearKeccak>),
+    Blake3(StarkVerifyingKey<BabyBearBlake3>),
+}
+
+impl SP1AnyCoreVerifyingKey {
+    /// The hash the key was set up with.
+    pub const fn hash(&self) -> CoreHash {
+        match self {
+            SP1AnyCoreVerifyingKey::Poseidon2(_) => CoreHash::Poseidon2,
+            SP1AnyCoreVerifyingKey::Keccak256(_) => CoreHash::Keccak256,
+            SP1AnyCoreVerifyingKey::Blake3(_) => CoreHash::Blake3,
+        }
+    }
+}
+
+/// An error returned when verifying a [SP1AnyCoreProof].
+#[derive(Error, Debug)]
+pub enum SP1AnyCoreVerificationError {
+    #[error("the proof was generated with {proof}, but the verifying key is for {vk}")]
+    HashMismatch { proof: CoreHash, vk: CoreHash },
+    #[error(transparent)]
+    Poseidon2(MachineVerificationError<BabyBearPoseidon2>),
+    #[error(transparent)]
+    Keccak256(MachineVerificationError<BabyBearKeccak>),
+    #[error(transparent)]
+    Blake3(MachineVerificationError<BabyBearBlake3>),
+}
+
 /// An intermediate proof which proves the execution over a range of shards.
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(bound(serialize = "ShardProof<SC>: Serialize"))]
@@ -168,7 +363,7 @@ pub struct SP1ReduceProof<SC: StarkGenericConfig> {
     pub proof: ShardProof<SC>,
 }
 
-impl SP1ReduceProof<BabyBearPoseidon2Outer> {
+impl<SC: StarkGenericConfig<Val = BabyBear>> SP1ReduceProof<SC> {
     pub fn sp1_vkey_digest_babybear(&self) -> [BabyBear; 8] {
         let proof = &self.proof;
         let pv: &RecursionPublicValues<BabyBear> = proof.public_values.as_slice().borrow();
@@ -197,5 +392,89 @@ pub enum SP1ReduceProofWrapper {
     Recursive(SP1ReduceProof<InnerSC>),
 }
 
+/// The shape of the reduction tree built by [crate::SP1Prover::compress].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReduceTreeShape {
+    /// Reduce the proofs layer by layer, waiting for every proof of a layer before starting the
+    /// next one.
+    Balanced,
+    /// Reduce contiguous proofs as soon as `batch_size` of them are available, so that early
+    /// proofs are reduced while later ones are still being proven.
+    Unbalanced,
+}
+
+/// Options for the reduction tree built by [crate::SP1Prover::compress].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CompressOptions {
+    /// The maximum number of proofs verified by a single node of the reduction tree. Must be at
+    /// least 2.
+    pub batch_size: usize,
+    /// The shape of the reduction tree.
+    pub shape: ReduceTreeShape,
+}
+
+impl Default for CompressOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 2,
+            shape: ReduceTreeShape::Balanced,
+        }
+    }
+}
+
+/// Options for the stages of [crate::SP1Prover].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SP1ProverOpts {
+    /// The options used to execute and prove the RISC-V program.
+    pub core_opts: SP1CoreOpts,
+    /// The options used to prove the recursion programs.
+    pub recursion_opts: SP1CoreOpts,
+    /// The options for the reduction tree built by [crate::SP1Prover::compress].
+    pub compress_opts: CompressOptions,
+}
+
+impl Default for SP1ProverOpts {
+    fn default() -> Self {
+        Self {
+            core_opts: SP1CoreOpts::default(),
+            recursion_opts: SP1CoreOpts::recursion(),
+            compress_opts: CompressOptions::default(),
+        }
+    }
+}
+
+/// The FRI parameters of each stage of [crate::SP1Prover].
+///
+/// A larger blowup with fewer queries makes a stage slower to prove but its proofs cheaper to
+/// verify recursively, and a smaller blowup the opposite. The recursive verifier programs are
+/// built to match these parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SP1StageConfigs {
+    /// The parameters of the core shard proofs.
+    pub core: SecurityConfig,
+    /// The parameters of the first layer of recursion, which verifies the core and deferred proofs.
+    pub recursion: SecurityConfig,
+    /// The parameters of the reduction tree built by [crate::SP1Prover::compress].
+    pub compress: SecurityConfig,
+    /// The parameters of the shrink proof built by [crate::SP1Prover::shrink].
+    pub shrink: SecurityConfig,
+}
+
+impl Default for SP1StageConfigs {
+    fn default() -> Self {
+        Self {
+            core: SecurityConfig::core(),
+            recursion: SecurityConfig::core(),
+            compress: SecurityConfig::core(),
+            shrink: SecurityConfig::compressed(),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
-pub enum SP1RecursionProverError {}
+pub enum SP1RecursionProverError {
+    #[error("{0}")]
+    Cancelled(ProofCancelled),
+    #[error("reduce batch size must be at least 2, got {0}")]
+    InvalidBatchSize(usize),
+}
@@ -2,20 +2,27 @@ use std::{borrow::Borrow, path::Path, str::FromStr};
 
 use anyhow::Result;
 use num_bigint::BigUint;
+use p3_air::Air;
 use p3_baby_bear::BabyBear;
 use p3_field::{AbstractField, PrimeField};
+use sha2::{Digest, Sha256};
 use sp1_core::{
-    air::PublicValues,
+    air::{PublicValues, Word},
     io::SP1PublicValues,
-    stark::{MachineProof, MachineVerificationError, StarkGenericConfig},
-    utils::BabyBearPoseidon2,
+    stark::{
+        MachineProof, MachineVerificationError, RiscvAir, ShardProof, StarkGenericConfig,
+        StarkMachine, StarkVerifyingKey, VerifierConstraintFolder,
+    },
+    utils::{BabyBearBlake3, BabyBearKeccak, BabyBearPoseidon2, DIGEST_SIZE},
 };
 use sp1_recursion_core::{air::RecursionPublicValues, stark::config::BabyBearPoseidon2Outer};
 use sp1_recursion_gnark_ffi::{Groth16Proof, Groth16Prover};
 use thiserror::Error;
 
 use crate::{
-    CoreSC, HashableKey, OuterSC, SP1CoreProofData, SP1Prover, SP1ReduceProof, SP1VerifyingKey,
+    utils::words_to_bytes, CoreSC, HashableKey, InnerSC, OuterSC, SP1AnyCoreProof,
+    SP1AnyCoreProofData, SP1AnyCoreVerificationError, SP1AnyCoreVerifyingKey, SP1CoreProofData,
+    SP1Prover, SP1ReduceProof, SP1VerifyingKey,
 };
 
 #[derive(Error, Debug)]
@@ -24,6 +31,8 @@ pub enum Groth16VerificationError {
     InvalidVerificationKey,
     #[error("the public values in the sp1 proof do not match the public values in the inner groth16 proof")]
     InvalidPublicValues,
+    #[error("the public inputs of the groth16 proof are malformed: {0}")]
+    MalformedPublicInputs(String),
 }
 
 impl SP1Prover {
@@ -34,69 +43,92 @@ impl SP1Prover {
         proof: &SP1CoreProofData,
         vk: &SP1VerifyingKey,
     ) -> Result<(), MachineVerificationError<CoreSC>> {
-        let mut challenger = self.core_machine.config().challenger();
-        let machine_proof = MachineProof {
-            shard_proofs: proof.0.to_vec(),
-        };
-        self.core_machine
-            .verify(&vk.vk, &machine_proof, &mut challenger)?;
+        verify_core_shards(&self.core_machine, proof, &vk.vk)
+    }
 
-        // Verify shard transitions
-        for (i, shard_proof) in proof.0.iter().enumerate() {
-            let public_values = PublicValues::from_vec(shard_proof.public_values.clone());
-            // Verify shard transitions
-            if i == 0 {
-                // If it's the first shard, index should be 1.
-                if public_values.shard != BabyBear::one() {
-                    return Err(MachineVerificationError::InvalidPublicValues(
-                        "first shard not 1",
-                    ));
-                }
-                if public_values.start_pc != vk.vk.pc_start {
-                    return Err(MachineVerificationError::InvalidPublicValues(
-                        "wrong pc_start",
-                    ));
-                }
-            } else {
-                let prev_shard_proof = &proof.0[i - 1];
-                let prev_public_values =
-                    PublicValues::from_vec(prev_shard_proof.public_values.clone());
-                // For non-first shards, the index should be the previous index + 1.
-                if public_values.shard != prev_public_values.shard + BabyBear::one() {
-                    return Err(MachineVerificationError::InvalidPublicValues(
-                        "non incremental shard index",
-                    ));
-                }
-                // Start pc should be what the next pc declared in the previous shard was.
-                if public_values.start_pc != prev_public_values.next_pc {
-                    return Err(MachineVerificationError::InvalidPublicValues("pc mismatch"));
-                }
-                // Digests and exit code should be the same in all shards.
-                if public_values.committed_value_digest != prev_public_values.committed_value_digest
-                    || public_values.deferred_proofs_digest
-                        != prev_public_values.deferred_proofs_digest
-                    || public_values.exit_code != prev_public_values.exit_code
-                {
-                    return Err(MachineVerificationError::InvalidPublicValues(
-                        "digest or exit code mismatch",
-                    ));
-                }
-                // The last shard should be halted. Halt is signaled with next_pc == 0.
-                if i == proof.0.len() - 1 && public_values.next_pc != BabyBear::zero() {
-                    return Err(MachineVerificationError::InvalidPublicValues(
-                        "last shard isn't halted",
-                    ));
-                }
-                // All non-last shards should not be halted.
-                if i != proof.0.len() - 1 && public_values.next_pc == BabyBear::zero() {
-                    return Err(MachineVerificationError::InvalidPublicValues(
-                        "non-last shard is halted",
-                    ));
-                }
-            }
+    /// Verify a core proof generated with the given STARK configuration, as
+    /// [SP1Prover::verify] does for [CoreSC].
+    pub fn verify_core_with_config<SC>(
+        config: SC,
+        proof: &SP1CoreProofData<SC>,
+        vk: &StarkVerifyingKey<SC>,
+    ) -> Result<(), MachineVerificationError<SC>>
+    where
+        SC: StarkGenericConfig<Val = BabyBear>,
+        SC::Challenger: Clone,
+        RiscvAir<BabyBear>: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+    {
+        verify_core_shards(&RiscvAir::machine(config), proof, vk)
+    }
+
+    /// Verify a core proof generated with [SP1Prover::prove_core_with_hash], given the verifying
+    /// key returned by [SP1Prover::setup_core_with_hash].
+    ///
+    /// The proof is verified with the configuration of the hash it was generated with and the
+    /// [SecurityConfig](sp1_core::stark::SecurityConfig) recorded in the verifying key. The
+    /// public values and the deferred proofs carried with the proof are checked against the
+    /// digests committed in its shards.
+    pub fn verify_core_with_hash(
+        proof: &SP1AnyCoreProof,
+        vk: &SP1AnyCoreVerifyingKey,
+    ) -> Result<(), SP1AnyCoreVerificationError> {
+        fn verify_any<SC>(
+            config: SC,
+            proof: &SP1CoreProofData<SC>,
+            vk: &StarkVerifyingKey<SC>,
+            public_values: &SP1PublicValues,
+            deferred_proofs: &[ShardProof<InnerSC>],
+        ) -> Result<(), MachineVerificationError<SC>>
+        where
+            SC: StarkGenericConfig<Val = BabyBear>,
+            SC::Challenger: Clone,
+            RiscvAir<BabyBear>: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+        {
+            verify_core_shards(&RiscvAir::machine(config), proof, vk)?;
+            verify_core_public_values(proof, public_values, deferred_proofs)
         }
 
-        Ok(())
+        let deferred_proofs = proof
+            .stdin
+            .proofs
+            .iter()
+            .map(|(proof, _)| proof.clone())
+            .collect::<Vec<_>>();
+        let public_values = &proof.public_values;
+        match (&proof.proof, vk) {
+            (SP1AnyCoreProofData::Poseidon2(data), SP1AnyCoreVerifyingKey::Poseidon2(vk)) => {
+                verify_any(
+                    BabyBearPoseidon2::with_security(vk.security),
+                    data,
+                    vk,
+                    public_values,
+                    &deferred_proofs,
+                )
+                .map_err(SP1AnyCoreVerificationError::Poseidon2)
+            }
+            (SP1AnyCoreProofData::Keccak256(data), SP1AnyCoreVerifyingKey::Keccak256(vk)) => {
+                verify_any(
+                    BabyBearKeccak::with_security(vk.security),
+                    data,
+                    vk,
+                    public_values,
+                    &deferred_proofs,
+                )
+                .map_err(SP1AnyCoreVerificationError::Keccak256)
+            }
+            (SP1AnyCoreProofData::Blake3(data), SP1AnyCoreVerifyingKey::Blake3(vk)) => verify_any(
+                BabyBearBlake3::with_security(vk.security),
+                data,
+                vk,
+                public_values,
+                &deferred_proofs,
+            )
+            .map_err(SP1AnyCoreVerificationError::Blake3),
+            (data, vk) => Err(SP1AnyCoreVerificationError::HashMismatch {
+                proof: data.hash(),
+                vk: vk.hash(),
+            }),
+        }
     }
 
     /// Verify a compressed proof.
@@ -219,11 +251,11 @@ impl SP1Prover {
         vk: &SP1VerifyingKey,
         public_values: &SP1PublicValues,
         build_dir: &Path,
-    ) -> Result<()> {
+    ) -> Result<(), Groth16VerificationError> {
         let prover = Groth16Prover::new();
 
-        let vkey_hash = BigUint::from_str(&proof.public_inputs[0])?;
-        let committed_values_digest = BigUint::from_str(&proof.public_inputs[1])?;
+        let vkey_hash = parse_public_input(&proof.public_inputs, 0)?;
+        let committed_values_digest = parse_public_input(&proof.public_inputs, 1)?;
 
         // Verify the proof with the corresponding public inputs.
         prover.verify(proof, &vkey_hash, &committed_values_digest, build_dir);
@@ -234,24 +266,246 @@ impl SP1Prover {
     }
 }
 
+/// Verify the shards of a core proof with the given machine, and that the shards are contiguous
+/// and complete.
+fn verify_core_shards<SC>(
+    machine: &StarkMachine<SC, RiscvAir<BabyBear>>,
+    proof: &SP1CoreProofData<SC>,
+    vk: &StarkVerifyingKey<SC>,
+) -> Result<(), MachineVerificationError<SC>>
+where
+    SC: StarkGenericConfig<Val = BabyBear>,
+    SC::Challenger: Clone,
+    RiscvAir<BabyBear>: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let mut challenger = machine.config().challenger();
+    let machine_proof = MachineProof {
+        shard_proofs: proof.0.to_vec(),
+    };
+    machine.verify(vk, &machine_proof, &mut challenger)?;
+
+    // Verify shard transitions
+    for (i, shard_proof) in proof.0.iter().enumerate() {
+        let public_values = PublicValues::from_vec(shard_proof.public_values.clone());
+        // Verify shard transitions
+        if i == 0 {
+            // If it's the first shard, index should be 1.
+            if public_values.shard != BabyBear::one() {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "first shard not 1",
+                ));
+            }
+            if public_values.start_pc != vk.pc_start {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "wrong pc_start",
+                ));
+            }
+        } else {
+            let prev_shard_proof = &proof.0[i - 1];
+            let prev_public_values = PublicValues::from_vec(prev_shard_proof.public_values.clone());
+            // For non-first shards, the index should be the previous index + 1.
+            if public_values.shard != prev_public_values.shard + BabyBear::one() {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "non incremental shard index",
+                ));
+            }
+            // Start pc should be what the next pc declared in the previous shard was.
+            if public_values.start_pc != prev_public_values.next_pc {
+                return Err(MachineVerificationError::InvalidPublicValues("pc mismatch"));
+            }
+            // Digests and exit code should be the same in all shards.
+            if public_values.committed_value_digest != prev_public_values.committed_value_digest
+                || public_values.deferred_proofs_digest != prev_public_values.deferred_proofs_digest
+                || public_values.exit_code != prev_public_values.exit_code
+            {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "digest or exit code mismatch",
+                ));
+            }
+            // The last shard should be halted. Halt is signaled with next_pc == 0.
+            if i == proof.0.len() - 1 && public_values.next_pc != BabyBear::zero() {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "last shard isn't halted",
+                ));
+            }
+            // All non-last shards should not be halted.
+            if i != proof.0.len() - 1 && public_values.next_pc == BabyBear::zero() {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "non-last shard is halted",
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Verify that the public values of a core proof hash to the digest committed in its shards, and that
+/// the deferred proofs hash to the deferred proofs digest committed in its shards.
+///
+/// [SP1Prover::verify] only checks that the shards agree on the digests, so this binds the public
+/// values and the deferred proofs carried with a proof to it.
+pub fn verify_core_public_values<SC: StarkGenericConfig<Val = BabyBear>>(
+    proof: &SP1CoreProofData<SC>,
+    public_values: &SP1PublicValues,
+    deferred_proofs: &[ShardProof<InnerSC>],
+) -> Result<(), MachineVerificationError<SC>> {
+    let last_proof = proof.0.last().ok_or(MachineVerificationError::EmptyProof)?;
+    let pv = PublicValues::from_vec(last_proof.public_values.clone());
+    verify_public_values_digest(&pv.committed_value_digest, public_values)?;
+    verify_deferred_proofs_digest(&pv.deferred_proofs_digest, deferred_proofs)
+}
+
+/// Verify that the public values of a compressed proof hash to its committed digest, and that the
+/// deferred proofs hash to its deferred proofs digest.
+pub fn verify_compressed_public_values(
+    proof: &SP1ReduceProof<BabyBearPoseidon2>,
+    public_values: &SP1PublicValues,
+    deferred_proofs: &[ShardProof<InnerSC>],
+) -> Result<(), MachineVerificationError<CoreSC>> {
+    let pv: &RecursionPublicValues<_> = proof.proof.public_values.as_slice().borrow();
+    verify_public_values_digest(&pv.committed_value_digest, public_values)?;
+    verify_deferred_proofs_digest(&pv.deferred_proofs_digest, deferred_proofs)
+}
+
+/// Verify that the SHA-256 digest of the public values matches the committed digest, whose words
+/// hold one byte per element.
+fn verify_public_values_digest<SC: StarkGenericConfig>(
+    committed_value_digest: &[Word<BabyBear>],
+    public_values: &SP1PublicValues,
+) -> Result<(), MachineVerificationError<SC>> {
+    let digest = Sha256::digest(public_values.as_slice());
+    let committed_bytes = words_to_bytes(committed_value_digest);
+    if committed_bytes.len() != digest.len()
+        || committed_bytes
+            .iter()
+            .zip(digest.iter())
+            .any(|(committed, byte)| *committed != BabyBear::from_canonical_u8(*byte))
+    {
+        return Err(MachineVerificationError::InvalidPublicValuesDigest);
+    }
+    Ok(())
+}
+
+/// Verify that the deferred proofs hash to the committed deferred proofs digest.
+///
+/// The digest only covers the proofs verified by the program, each call to `verify_sp1_proof`
+/// consuming the next proof written to the stdin. A program may verify fewer proofs than were
+/// written, so the digest is checked against the digest of every prefix of `deferred_proofs`.
+fn verify_deferred_proofs_digest<SC: StarkGenericConfig>(
+    deferred_proofs_digest: &[BabyBear],
+    deferred_proofs: &[ShardProof<InnerSC>],
+) -> Result<(), MachineVerificationError<SC>> {
+    let mut digest = [BabyBear::zero(); DIGEST_SIZE];
+    if deferred_proofs_digest == digest {
+        return Ok(());
+    }
+    for proof in deferred_proofs.iter() {
+        digest = SP1Prover::hash_deferred_proofs(digest, std::slice::from_ref(proof));
+        if deferred_proofs_digest == digest {
+            return Ok(());
+        }
+    }
+    Err(MachineVerificationError::InvalidDeferredProofsDigest)
+}
+
 /// Verify the vk_hash and public_values_hash in the public inputs of the Groth16Proof match the expected values.
 pub fn verify_groth16_public_inputs(
     vk: &SP1VerifyingKey,
     public_values: &SP1PublicValues,
     groth16_public_inputs: &[String],
-) -> Result<()> {
-    let expected_vk_hash = BigUint::from_str(&groth16_public_inputs[0])?;
-    let expected_public_values_hash = BigUint::from_str(&groth16_public_inputs[1])?;
+) -> Result<(), Groth16VerificationError> {
+    let expected_vk_hash = parse_public_input(groth16_public_inputs, 0)?;
+    let expected_public_values_hash = parse_public_input(groth16_public_inputs, 1)?;
 
     let vk_hash = vk.hash_bn254().as_canonical_biguint();
     if vk_hash != expected_vk_hash {
-        return Err(Groth16VerificationError::InvalidVerificationKey.into());
+        return Err(Groth16VerificationError::InvalidVerificationKey);
     }
 
     let public_values_hash = public_values.hash();
     if public_values_hash != expected_public_values_hash {
-        return Err(Groth16VerificationError::InvalidPublicValues.into());
+        return Err(Groth16VerificationError::InvalidPublicValues);
     }
 
     Ok(())
 }
+
+/// Parse the public input at `index` of a Groth16 proof as a decimal integer.
+fn parse_public_input(
+    public_inputs: &[String],
+    index: usize,
+) -> Result<BigUint, Groth16VerificationError> {
+    let input = public_inputs.get(index).ok_or_else(|| {
+        Groth16VerificationError::MalformedPublicInputs(format!("missing public input {}", index))
+    })?;
+    BigUint::from_str(input).map_err(|e| {
+        Groth16VerificationError::MalformedPublicInputs(format!("public input {}: {}", index, e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sp1_core::{io::SP1Stdin, utils::ProofProgress};
+
+    use super::*;
+    use crate::{CoreHash, SP1ProverOpts};
+
+    #[test]
+    fn test_verify_public_values_digest() {
+        let public_values = SP1PublicValues::from(&[1, 2, 3, 4]);
+        let digest = Sha256::digest(public_values.as_slice());
+        let committed_value_digest = digest
+            .chunks_exact(4)
+            .map(|bytes| {
+                Word(core::array::from_fn(|i| {
+                    BabyBear::from_canonical_u8(bytes[i])
+                }))
+            })
+            .collect::<Vec<_>>();
+        verify_public_values_digest::<CoreSC>(&committed_value_digest, &public_values).unwrap();
+
+        let tampered = SP1PublicValues::from(&[1, 2, 3, 5]);
+        assert!(matches!(
+            verify_public_values_digest::<CoreSC>(&committed_value_digest, &tampered),
+            Err(MachineVerificationError::InvalidPublicValuesDigest)
+        ));
+
+        verify_deferred_proofs_digest::<CoreSC>(&[BabyBear::zero(); DIGEST_SIZE], &[]).unwrap();
+        assert!(matches!(
+            verify_deferred_proofs_digest::<CoreSC>(&[BabyBear::one(); DIGEST_SIZE], &[]),
+            Err(MachineVerificationError::InvalidDeferredProofsDigest)
+        ));
+    }
+
+    #[test]
+    fn test_verify_core_with_hash() {
+        let elf = include_bytes!("../../tests/fibonacci/elf/riscv32im-succinct-zkvm-elf");
+        let stdin = SP1Stdin::new();
+        let mut proof = SP1Prover::prove_core_with_hash(
+            CoreHash::Keccak256,
+            elf,
+            &stdin,
+            SP1ProverOpts::default(),
+            &ProofProgress::default(),
+        )
+        .unwrap();
+        assert_eq!(proof.proof.hash(), CoreHash::Keccak256);
+        let vk = SP1Prover::setup_core_with_hash(CoreHash::Keccak256, elf);
+        SP1Prover::verify_core_with_hash(&proof, &vk).unwrap();
+
+        let other_vk = SP1Prover::setup_core_with_hash(CoreHash::Blake3, elf);
+        assert!(matches!(
+            SP1Prover::verify_core_with_hash(&proof, &other_vk),
+            Err(SP1AnyCoreVerificationError::HashMismatch { .. })
+        ));
+
+        proof.public_values = SP1PublicValues::from(&[0]);
+        assert!(matches!(
+            SP1Prover::verify_core_with_hash(&proof, &vk),
+            Err(SP1AnyCoreVerificationError::Keccak256(
+                MachineVerificationError::InvalidPublicValuesDigest
+            ))
+        ));
+    }
+}
@@ -172,6 +172,7 @@ mod tests {
         Chip, Com, Dom, LocalProver, OpeningProof, PcsProverData, ShardCommitment, ShardMainData,
         ShardProof, StarkGenericConfig, StarkMachine,
     };
+    use sp1_core::utils::SP1CoreOpts;
     use sp1_recursion_compiler::{
         config::OuterConfig,
         constraints::ConstraintCompiler,
@@ -295,7 +296,12 @@ mod tests {
         let machine = A::machine(config);
         let (pk, vk) = machine.setup(&program);
         let mut challenger = machine.config().challenger();
-        let proof = machine.prove::<LocalProver<_, _>>(&pk, runtime.record, &mut challenger);
+        let proof = machine.prove::<LocalProver<_, _>>(
+            &pk,
+            runtime.record,
+            &mut challenger,
+            SP1CoreOpts::recursion(),
+        );
 
         let mut challenger = machine.config().challenger();
         vk.observe_into(&mut challenger);
@@ -5,7 +5,7 @@ use p3_air::{Air, AirBuilder, BaseAir};
 use p3_field::PrimeField32;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
-use sp1_core::air::{BaseAirBuilder, MachineAir};
+use sp1_core::air::{indexed_column_names, BaseAirBuilder, ColumnNames, MachineAir};
 use sp1_core::utils::pad_rows_fixed;
 use sp1_derive::AlignedBorrow;
 
@@ -42,6 +42,17 @@ pub union InstructionSpecificCols<T: Copy> {
     poseidon2: Poseidon2Cols<T>,
 }
 
+// The views overlap, so the columns are named by index.
+impl<T: Copy> ColumnNames for InstructionSpecificCols<T> {
+    fn column_names_with_prefix(prefix: &str, names: &mut Vec<String>) {
+        indexed_column_names(
+            prefix,
+            core::mem::size_of::<Self>() / core::mem::size_of::<T>(),
+            names,
+        );
+    }
+}
+
 impl<F, const DEGREE: usize> BaseAir<F> for MultiChip<DEGREE> {
     fn width(&self) -> usize {
         NUM_MULTI_COLS
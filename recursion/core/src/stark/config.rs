@@ -15,7 +15,7 @@ use p3_symmetric::Hash;
 use p3_symmetric::{MultiField32PaddingFreeSponge, TruncatedPermutation};
 use serde::Deserialize;
 use serde::Serialize;
-use sp1_core::stark::StarkGenericConfig;
+use sp1_core::stark::{SecurityConfig, StarkGenericConfig};
 
 use super::poseidon2::bn254_poseidon2_rc3;
 use super::utils;
@@ -146,6 +146,10 @@ impl StarkGenericConfig for BabyBearPoseidon2Outer {
     fn challenger(&self) -> Self::Challenger {
         OuterChallenger::new(self.perm.clone()).unwrap()
     }
+
+    fn security(&self) -> SecurityConfig {
+        SecurityConfig::from_fri_config(self.pcs.fri_config())
+    }
 }
 
 /// The FRI config for testing recursion.
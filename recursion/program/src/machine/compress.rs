@@ -74,8 +74,12 @@ where
     A: MachineAir<BabyBear> + for<'a> Air<RecursiveVerifierConstraintFolder<'a, InnerConfig>>,
 {
     /// Create a new instance of the program for the [BabyBearPoseidon2] config.
+    ///
+    /// Reduce proofs are verified with the FRI parameters of `machine`, and the proofs of the
+    /// first layer, proven by `recursive_machine`, with its parameters.
     pub fn build(
         machine: &StarkMachine<BabyBearPoseidon2, A>,
+        recursive_machine: &StarkMachine<BabyBearPoseidon2, A>,
         recursive_vk: &StarkVerifyingKey<BabyBearPoseidon2>,
         deferred_vk: &StarkVerifyingKey<BabyBearPoseidon2>,
     ) -> RecursionProgram<BabyBear> {
@@ -87,9 +91,13 @@ where
         let pcs = TwoAdicFriPcsVariable {
             config: const_fri_config(&mut builder, machine.config().pcs().fri_config()),
         };
+        let recursive_pcs = TwoAdicFriPcsVariable {
+            config: const_fri_config(&mut builder, recursive_machine.config().pcs().fri_config()),
+        };
         SP1CompressVerifier::verify(
             &mut builder,
             &pcs,
+            &recursive_pcs,
             machine,
             input,
             recursive_vk,
@@ -123,9 +131,12 @@ where
     /// - Compress proofs: these are proofs which refer to a prove of this program. The key for
     ///   it is part of public values will be propagated accross all levels of recursion and will
     ///   be checked against itself as in [sp1_prover::Prover] or as in [super::SP1RootVerifier].
+    ///
+    /// Core and deferred proofs are verified with `recursive_pcs`, and compress proofs with `pcs`.
     pub fn verify(
         builder: &mut Builder<C>,
         pcs: &TwoAdicFriPcsVariable<C>,
+        recursive_pcs: &TwoAdicFriPcsVariable<C>,
         machine: &StarkMachine<SC, A>,
         input: SP1ReduceMemoryLayoutVariable<C>,
         recursive_vk: &StarkVerifyingKey<SC>,
@@ -191,23 +202,27 @@ where
 
             // Verify the shard proof.
 
-            // Initialize values for verifying key and proof data.
+            // Initialize values for verifying key, proof data and FRI parameters.
             let vk: VerifyingKeyVariable<_> = builder.uninit();
+            let proof_pcs: TwoAdicFriPcsVariable<_> = builder.uninit();
             // Set the correct value given the value of kind, and assert it must be one of the
             // valid values. We can do that by nested `if-else` statements.
             builder.if_eq(kind, core_kind).then_or_else(
                 |builder| {
                     builder.assign(vk.clone(), recursive_vk_variable.clone());
+                    builder.assign(proof_pcs.clone(), recursive_pcs.clone());
                 },
                 |builder| {
                     builder.if_eq(kind, deferred_kind).then_or_else(
                         |builder| {
                             builder.assign(vk.clone(), deferred_vk_variable.clone());
+                            builder.assign(proof_pcs.clone(), recursive_pcs.clone());
                         },
                         |builder| {
                             builder.if_eq(kind, reduce_kind).then_or_else(
                                 |builder| {
                                     builder.assign(vk.clone(), compress_vk.clone());
+                                    builder.assign(proof_pcs.clone(), pcs.clone());
                                 },
                                 |builder| {
                                     // If the kind is not one of the valid values, raise
@@ -237,7 +252,7 @@ where
             StarkVerifier::<C, SC>::verify_shard(
                 builder,
                 &vk,
-                pcs,
+                &proof_pcs,
                 machine,
                 &mut challenger,
                 &proof,
@@ -17,7 +17,7 @@ mod tests {
     use p3_challenger::CanObserve;
     use p3_maybe_rayon::prelude::*;
     use sp1_core::stark::{MachineVerificationError, RiscvAir, StarkGenericConfig};
-    use sp1_core::utils::BabyBearPoseidon2;
+    use sp1_core::utils::{BabyBearPoseidon2, ProofProgress, SP1CoreOpts};
     use sp1_core::{
         io::SP1Stdin,
         runtime::Program,
@@ -63,6 +63,7 @@ mod tests {
 
         // Make the compress program.
         let reduce_program = SP1CompressVerifier::<InnerConfig, _, _>::build(
+            &recursive_machine,
             &recursive_machine,
             &rec_vk,
             &deferred_vk,
@@ -83,7 +84,14 @@ mod tests {
 
         let mut challenger = machine.config().challenger();
         let time = std::time::Instant::now();
-        let (proof, _) = sp1_core::utils::prove(program, &SP1Stdin::new(), SC::default()).unwrap();
+        let (proof, _) = sp1_core::utils::prove(
+            program,
+            &SP1Stdin::new(),
+            SC::default(),
+            SP1CoreOpts::default(),
+            &ProofProgress::default(),
+        )
+        .unwrap();
         machine.verify(&vk, &proof, &mut challenger).unwrap();
         tracing::info!("Proof generated successfully");
         let elapsed = time.elapsed();
@@ -166,6 +174,7 @@ mod tests {
                     &rec_pk,
                     record,
                     &mut recursive_challenger,
+                    SP1CoreOpts::recursion(),
                 )
             })
             .collect::<Vec<_>>();
@@ -238,6 +247,7 @@ mod tests {
                         &reduce_pk,
                         runtime.record,
                         &mut recursive_challenger,
+                        SP1CoreOpts::recursion(),
                     );
                     let mut recursive_challenger = recursive_machine.config().challenger();
                     let result =
@@ -297,6 +307,7 @@ mod tests {
             &compress_pk,
             runtime.record,
             &mut compress_challenger,
+            SP1CoreOpts::recursion(),
         );
         let elapsed = time.elapsed();
         tracing::info!("Compress proving time: {:?}", elapsed);
@@ -341,8 +352,12 @@ mod tests {
         // Prove the wrap program.
         let mut wrap_challenger = wrap_machine.config().challenger();
         let time = std::time::Instant::now();
-        let wrap_proof =
-            wrap_machine.prove::<LocalProver<_, _>>(&wrap_pk, runtime.record, &mut wrap_challenger);
+        let wrap_proof = wrap_machine.prove::<LocalProver<_, _>>(
+            &wrap_pk,
+            runtime.record,
+            &mut wrap_challenger,
+            SP1CoreOpts::recursion(),
+        );
         let elapsed = time.elapsed();
         tracing::info!("Wrap proving time: {:?}", elapsed);
         let mut wrap_challenger = wrap_machine.config().challenger();
@@ -417,6 +417,7 @@ pub(crate) mod tests {
     use sp1_core::utils::setup_logger;
     use sp1_core::utils::InnerChallenge;
     use sp1_core::utils::InnerVal;
+    use sp1_core::utils::{ProofProgress, SP1CoreOpts};
     use sp1_core::{
         stark::{RiscvAir, StarkGenericConfig},
         utils::BabyBearPoseidon2,
@@ -458,8 +459,14 @@ pub(crate) mod tests {
         let machine = A::machine(SC::default());
         let (_, vk) = machine.setup(&Program::from(elf));
         let mut challenger_val = machine.config().challenger();
-        let (proof, _) =
-            sp1_core::utils::prove(Program::from(elf), &SP1Stdin::new(), SC::default()).unwrap();
+        let (proof, _) = sp1_core::utils::prove(
+            Program::from(elf),
+            &SP1Stdin::new(),
+            SC::default(),
+            SP1CoreOpts::default(),
+            &ProofProgress::default(),
+        )
+        .unwrap();
         let proofs = proof.shard_proofs;
         println!("Proof generated successfully");
 
@@ -557,8 +564,12 @@ pub(crate) mod tests {
         let record = runtime.record.clone();
 
         let mut challenger = machine.config().challenger();
-        let mut proof =
-            machine.prove::<LocalProver<SC, RecursionAir<_, 3>>>(&pk, record, &mut challenger);
+        let mut proof = machine.prove::<LocalProver<SC, RecursionAir<_, 3>>>(
+            &pk,
+            record,
+            &mut challenger,
+            SP1CoreOpts::recursion(),
+        );
 
         let mut challenger = machine.config().challenger();
         let verification_result = machine.verify(&vk, &proof, &mut challenger);
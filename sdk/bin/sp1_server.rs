@@ -0,0 +1,54 @@
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
+
+use clap::Parser;
+use sp1_sdk::{
+    server::{ProverServer, ServerOpts, DEFAULT_CLAIM_TIMEOUT},
+    utils::setup_logger,
+    LocalProver,
+};
+
+/// Serves the prover network API on top of a local prover.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The address to listen on.
+    #[clap(long, env = "SP1_SERVER_ADDR", default_value = "0.0.0.0:3000")]
+    addr: SocketAddr,
+
+    /// The directory storing the jobs and artifacts.
+    #[clap(long, env = "SP1_SERVER_DIR", default_value = "sp1-server")]
+    dir: PathBuf,
+
+    /// The URL under which clients reach the server. Defaults to `http://<addr>`.
+    #[clap(long, env = "SP1_SERVER_URL")]
+    public_url: Option<String>,
+
+    /// The number of requests proven concurrently by the server. Use zero to only serve external
+    /// provers.
+    #[clap(long, env = "SP1_SERVER_LOCAL_PROVERS", default_value_t = 1)]
+    local_provers: usize,
+
+    /// The number of seconds an external prover can keep a request claimed before it is requested
+    /// again.
+    #[clap(long, env = "SP1_SERVER_CLAIM_TIMEOUT", default_value_t = DEFAULT_CLAIM_TIMEOUT.as_secs())]
+    claim_timeout: u64,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    setup_logger();
+    let args = Args::parse();
+    let opts = ServerOpts {
+        dir: args.dir,
+        public_url: args
+            .public_url
+            .unwrap_or_else(|| format!("http://{}", args.addr)),
+        local_provers: args.local_provers,
+        claim_timeout: Duration::from_secs(args.claim_timeout),
+    };
+    let server = ProverServer::new(LocalProver::new(), opts)?;
+    Arc::new(server).serve(args.addr).await
+}
@@ -0,0 +1,27 @@
+//! The program used by `ProverClient::aggregate` to aggregate compressed proofs into one.
+//!
+//! It reads a list of (vkey hash, public values digest) pairs, verifies a proof for each pair and
+//! commits to the list as `count || vkey_hash[0] || pv_digest[0] || ...`, with the count as a
+//! big-endian u32 like `abi.encodePacked`, the vkey hashes as the little-endian bytes of their
+//! words and the digests as the SHA-256 digests of the public values.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use sp1_zkvm::precompiles::verify::verify_sp1_proof;
+
+pub fn main() {
+    let pairs = sp1_zkvm::io::read::<Vec<([u32; 8], [u8; 32])>>();
+
+    let mut commitment = Vec::with_capacity(4 + pairs.len() * 64);
+    commitment.extend_from_slice(&(pairs.len() as u32).to_be_bytes());
+    for (vkey, pv_digest) in pairs.iter() {
+        verify_sp1_proof(vkey, pv_digest);
+        for word in vkey.iter() {
+            commitment.extend_from_slice(&word.to_le_bytes());
+        }
+        commitment.extend_from_slice(pv_digest);
+    }
+
+    sp1_zkvm::io::commit_slice(&commitment);
+}
@@ -0,0 +1,108 @@
+use sha2::{Digest, Sha256};
+use sp1_prover::{HashableKey, SP1Stdin};
+
+use crate::{SP1CompressedProof, SP1VerifyingKey};
+
+/// The program verifying the proofs aggregated by [crate::ProverClient::aggregate], built from
+/// `programs/aggregation` with `make` in `programs`.
+pub const AGGREGATION_ELF: &[u8] =
+    include_bytes!("../programs/aggregation/elf/riscv32im-succinct-zkvm-elf");
+
+/// A proof verified by the aggregation program, identified by the hash of its verifying key and the
+/// digest of its public values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AggregatedProof {
+    /// The hash of the verifying key, as returned by [HashableKey::hash_u32].
+    pub vkey_hash: [u32; 8],
+    /// The SHA-256 digest of the public values.
+    pub public_values_digest: [u8; 32],
+}
+
+impl AggregatedProof {
+    pub fn new(proof: &SP1CompressedProof, vk: &SP1VerifyingKey) -> Self {
+        Self {
+            vkey_hash: vk.hash_u32(),
+            public_values_digest: Sha256::digest(proof.public_values.as_slice()).into(),
+        }
+    }
+}
+
+/// Writes the inputs of the aggregation program, to prove it with any of the prove methods of
+/// [crate::ProverClient].
+pub fn aggregation_stdin(proofs: &[(SP1CompressedProof, SP1VerifyingKey)]) -> SP1Stdin {
+    let pairs = proofs
+        .iter()
+        .map(|(proof, vk)| {
+            let aggregated = AggregatedProof::new(proof, vk);
+            (aggregated.vkey_hash, aggregated.public_values_digest)
+        })
+        .collect::<Vec<_>>();
+
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&pairs);
+    // The proofs are not read by the program, they are witnessed by the recursion prover when it
+    // verifies the program's calls to `verify_sp1_proof`.
+    for (proof, vk) in proofs.iter() {
+        stdin.write_proof(proof.proof.clone(), vk.vk.clone());
+    }
+    stdin
+}
+
+/// Encodes the public values committed by the aggregation program.
+///
+/// The commitment is `count || vkey_hash[0] || public_values_digest[0] || ...`, where the count is
+/// a big-endian u32 as with `abi.encodePacked` and the words of the vkey hashes are little-endian.
+pub fn encode_aggregation(proofs: &[AggregatedProof]) -> Vec<u8> {
+    let mut commitment = Vec::with_capacity(4 + proofs.len() * 64);
+    commitment.extend_from_slice(&(proofs.len() as u32).to_be_bytes());
+    for proof in proofs.iter() {
+        for word in proof.vkey_hash.iter() {
+            commitment.extend_from_slice(&word.to_le_bytes());
+        }
+        commitment.extend_from_slice(&proof.public_values_digest);
+    }
+    commitment
+}
+
+/// Decodes the public values committed by the aggregation program, returning `None` if they are
+/// malformed.
+pub fn decode_aggregation(public_values: &[u8]) -> Option<Vec<AggregatedProof>> {
+    let (count, pairs) = public_values.split_first_chunk::<4>()?;
+    let count = u32::from_be_bytes(*count) as usize;
+    if pairs.len() != count * 64 {
+        return None;
+    }
+    let proofs = pairs
+        .chunks_exact(64)
+        .map(|pair| {
+            let mut vkey_hash = [0u32; 8];
+            for (word, bytes) in vkey_hash.iter_mut().zip(pair[..32].chunks_exact(4)) {
+                *word = u32::from_le_bytes(bytes.try_into().unwrap());
+            }
+            AggregatedProof {
+                vkey_hash,
+                public_values_digest: pair[32..].try_into().unwrap(),
+            }
+        })
+        .collect();
+    Some(proofs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregation_encoding() {
+        let proofs = (0..3u8)
+            .map(|i| AggregatedProof {
+                vkey_hash: [i as u32; 8],
+                public_values_digest: [i; 32],
+            })
+            .collect::<Vec<_>>();
+        let encoded = encode_aggregation(&proofs);
+        assert_eq!(encoded.len(), 4 + 3 * 64);
+        assert_eq!(decode_aggregation(&encoded), Some(proofs));
+        assert_eq!(decode_aggregation(&encoded[..encoded.len() - 1]), None);
+    }
+}
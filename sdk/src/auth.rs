@@ -1,10 +1,12 @@
 use std::borrow::Cow;
 use std::str::FromStr;
 
+use alloy::primitives::Signature;
 use alloy::signers::{wallet::LocalWallet, Signer};
 use alloy::sol;
 use alloy::sol_types::{Eip712Domain, SolStruct};
 use anyhow::Result;
+use sha2::{Digest, Sha256};
 
 use crate::proto::network::UnclaimReason;
 
@@ -37,6 +39,11 @@ sol! {
         string proof_id;
     }
 
+    struct UploadArtifact {
+        string artifact_id;
+        bytes32 digest;
+    }
+
     struct RelayProof {
         uint64 nonce;
         string proof_id;
@@ -47,6 +54,9 @@ sol! {
     }
 }
 
+/// The HTTP header carrying the hex-encoded signature of an artifact upload.
+pub const ARTIFACT_SIGNATURE_HEADER: &str = "x-sp1-signature";
+
 /// Handles authentication for the Succinct prover network. All interactions that could potentially
 /// use computational resources must be authenticated by signing a message with a secp256k1 key.
 ///
@@ -146,6 +156,19 @@ impl NetworkAuth {
         self.sign_message(type_struct).await
     }
 
+    /// Signs a message to upload the content of an artifact, identified by its SHA-256 digest.
+    pub async fn sign_upload_artifact_message(
+        &self,
+        artifact_id: &str,
+        data: &[u8],
+    ) -> Result<Vec<u8>> {
+        let type_struct = UploadArtifact {
+            artifact_id: artifact_id.to_string(),
+            digest: <[u8; 32]>::from(Sha256::digest(data)).into(),
+        };
+        self.sign_message(type_struct).await
+    }
+
     /// Signs a message to remote relay a proof to a specific chain with the verifier and callback
     /// specified.
     pub async fn sign_relay_proof_message(
@@ -167,4 +190,97 @@ impl NetworkAuth {
         };
         self.sign_message(type_struct).await
     }
+
+    // Generic function to recover the address that signed a message based on the SolStruct.
+    fn recover_signer<T: SolStruct>(type_struct: T, signature: &[u8]) -> Result<[u8; 20]> {
+        let domain_separator = Self::get_domain_separator();
+        let message_hash = type_struct.eip712_signing_hash(&domain_separator);
+        let signature = Signature::try_from(signature)?;
+        let address = signature.recover_address_from_prehash(&message_hash)?;
+        Ok(*address.0)
+    }
+
+    /// Recovers the address that signed a message to create a proof.
+    pub fn recover_create_proof_signer(
+        signature: &[u8],
+        nonce: u64,
+        deadline: u64,
+        mode: i32,
+    ) -> Result<[u8; 20]> {
+        let type_struct = CreateProof {
+            nonce,
+            deadline,
+            mode: mode as u32,
+        };
+        Self::recover_signer(type_struct, signature)
+    }
+
+    /// Recovers the address that signed a message to submit a proof.
+    pub fn recover_submit_proof_signer(
+        signature: &[u8],
+        nonce: u64,
+        proof_id: &str,
+    ) -> Result<[u8; 20]> {
+        let type_struct = SubmitProof {
+            nonce,
+            proof_id: proof_id.to_string(),
+        };
+        Self::recover_signer(type_struct, signature)
+    }
+
+    /// Recovers the address that signed a message to claim a proof.
+    pub fn recover_claim_proof_signer(
+        signature: &[u8],
+        nonce: u64,
+        proof_id: &str,
+    ) -> Result<[u8; 20]> {
+        let type_struct = ClaimProof {
+            nonce,
+            proof_id: proof_id.to_string(),
+        };
+        Self::recover_signer(type_struct, signature)
+    }
+
+    /// Recovers the address that signed a message to unclaim a proof.
+    pub fn recover_unclaim_proof_signer(
+        signature: &[u8],
+        nonce: u64,
+        proof_id: &str,
+        reason: UnclaimReason,
+        description: &str,
+    ) -> Result<[u8; 20]> {
+        let type_struct = UnclaimProof {
+            nonce,
+            proof_id: proof_id.to_string(),
+            reason: reason as u8,
+            description: description.to_string(),
+        };
+        Self::recover_signer(type_struct, signature)
+    }
+
+    /// Recovers the address that signed a message to fulfill a proof.
+    pub fn recover_fulfill_proof_signer(
+        signature: &[u8],
+        nonce: u64,
+        proof_id: &str,
+    ) -> Result<[u8; 20]> {
+        let type_struct = FulfillProof {
+            nonce,
+            proof_id: proof_id.to_string(),
+        };
+        Self::recover_signer(type_struct, signature)
+    }
+
+    /// Recovers the address that signed a message to upload the content of an artifact.
+    pub fn recover_upload_artifact_signer(
+        signature: &[u8],
+        artifact_id: &str,
+        data: &[u8],
+    ) -> Result<[u8; 20]> {
+        let type_struct = UploadArtifact {
+            artifact_id: artifact_id.to_string(),
+            digest: <[u8; 32]>::from(Sha256::digest(data)).into(),
+        };
+        Self::recover_signer(type_struct, signature)
+    }
 }
@@ -1,7 +1,7 @@
 use std::{env, time::Duration};
 
 use crate::{
-    auth::NetworkAuth,
+    auth::{NetworkAuth, ARTIFACT_SIGNATURE_HEADER},
     proto::network::{UnclaimProofRequest, UnclaimReason},
 };
 use anyhow::{Context, Ok, Result};
@@ -29,6 +29,7 @@ const DEFAULT_SP1_VERIFIER_ADDRESS: &str = "0xed2107448519345059eab9cddab42ddc78
 
 pub struct NetworkClient {
     pub rpc: TwirpClient,
+    pub rpc_url: Url,
     pub http: HttpClientWithMiddleware,
     pub auth: NetworkAuth,
 }
@@ -36,10 +37,14 @@ pub struct NetworkClient {
 impl NetworkClient {
     // Create a new NetworkClient with the given private key for authentication.
     pub fn new(private_key: &str) -> Self {
-        let auth = NetworkAuth::new(private_key);
-
         let rpc_url = env::var("PROVER_NETWORK_RPC")
             .unwrap_or_else(|_| DEFAULT_PROVER_NETWORK_RPC.to_string());
+        Self::with_rpc_url(private_key, &rpc_url)
+    }
+
+    // Create a new NetworkClient with the given private key, connected to the given RPC endpoint.
+    pub fn with_rpc_url(private_key: &str, rpc_url: &str) -> Self {
+        let auth = NetworkAuth::new(private_key);
 
         let twirp_http_client = HttpClient::builder()
             .pool_max_idle_per_host(0)
@@ -47,8 +52,8 @@ impl NetworkClient {
             .build()
             .unwrap();
 
-        let rpc =
-            TwirpClient::new(Url::parse(&rpc_url).unwrap(), twirp_http_client, vec![]).unwrap();
+        let rpc_url = Url::parse(rpc_url).unwrap();
+        let rpc = TwirpClient::new(rpc_url.clone(), twirp_http_client, vec![]).unwrap();
 
         let http_client = HttpClient::builder()
             .pool_max_idle_per_host(0)
@@ -59,6 +64,7 @@ impl NetworkClient {
         Self {
             auth,
             rpc,
+            rpc_url,
             http: http_client.into(),
         }
     }
@@ -87,11 +93,57 @@ impl NetworkClient {
     }
 
     // Upload a file to the specified url.
+    //
+    // The content is signed for the artifact named by the last segment of the url, as required by
+    // [crate::server::ProverServer].
     async fn upload_file(&self, url: &str, data: Vec<u8>) -> Result<()> {
-        self.http.put(url).body(data).send().await?;
+        let url = Url::parse(url)?;
+        let artifact_id = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or_default()
+            .to_string();
+        let signature = self
+            .auth
+            .sign_upload_artifact_message(&artifact_id, &data)
+            .await?;
+        self.http
+            .put(url)
+            .header(ARTIFACT_SIGNATURE_HEADER, hex::encode(signature))
+            .body(data)
+            .send()
+            .await
+            .context("Failed to send HTTP request for artifact")?
+            .error_for_status()?;
         Ok(())
     }
 
+    // Get the URL of an artifact, as served by [crate::server::ProverServer].
+    pub fn artifact_url(&self, artifact_id: &str) -> Result<Url> {
+        Ok(self.rpc_url.join(&format!("artifacts/{}", artifact_id))?)
+    }
+
+    // Download the artifact with the given identifier.
+    pub async fn download_artifact(&self, artifact_id: &str) -> Result<Vec<u8>> {
+        let bytes = self
+            .http
+            .get(self.artifact_url(artifact_id)?)
+            .send()
+            .await
+            .context("Failed to send HTTP request for artifact")?
+            .error_for_status()?
+            .bytes()
+            .await
+            .context("Failed to load artifact bytes")?;
+        Ok(bytes.to_vec())
+    }
+
+    // Upload the artifact with the given identifier.
+    pub async fn upload_artifact(&self, artifact_id: &str, data: Vec<u8>) -> Result<()> {
+        self.upload_file(self.artifact_url(artifact_id)?.as_str(), data)
+            .await
+    }
+
     // Get the status of a given proof. If the status is ProofFulfilled, the proof is also returned.
     pub async fn get_proof_status<P: DeserializeOwned>(
         &self,
@@ -0,0 +1,239 @@
+//! The file format of the proofs saved with [SP1ProofWithPublicValues::save].
+//!
+//! A proof file starts with the [PROOF_MAGIC] bytes and the [PROOF_FORMAT_VERSION] as a
+//! little-endian u32, followed by a bincode-encoded [ProofHeader] and the bincode-encoded proof.
+//! The header records the SP1 version, the kind of the proof and the identifiers of the program and
+//! circuits it was generated for, so that loading a proof from another version fails with a clear
+//! error instead of failing to deserialize or to verify.
+
+use std::{
+    fmt::Debug,
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp1_core::stark::ShardProof;
+use sp1_prover::{
+    install::GROTH16_ARTIFACTS_COMMIT, CoreSC, Groth16Proof, HashableKey, InnerSC, PlonkBn254Proof,
+};
+use thiserror::Error;
+
+use crate::{SP1ProofWithPublicValues, SP1VerifyingKey};
+
+/// The bytes at the start of every proof file.
+pub const PROOF_MAGIC: [u8; 8] = *b"SP1PROOF";
+
+/// The version of the layout of proof files, incremented when the layout of [ProofHeader] or of
+/// the serialized proofs changes.
+pub const PROOF_FORMAT_VERSION: u32 = 2;
+
+/// The version of SP1 recorded in the proofs saved by this crate, shared with the proving key
+/// files.
+pub use sp1_prover::SP1_VERSION;
+
+/// The kind of a proof, which determines how it is verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProofKind {
+    Core,
+    Compressed,
+    Groth16,
+    Plonk,
+}
+
+/// The metadata saved before the proof in a proof file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofHeader {
+    /// The version of SP1 that generated the proof.
+    pub sp1_version: String,
+    pub kind: ProofKind,
+    /// The vkey hash of the program, as returned by [HashableKey::bytes32].
+    pub vkey_hash: String,
+    /// The version of the circuit artifacts the proof was generated with, for onchain proofs.
+    pub circuit_version: Option<String>,
+}
+
+/// An error returned when loading a proof file.
+#[derive(Error, Debug)]
+pub enum ProofFileError {
+    #[error("not an SP1 proof file (proofs saved before the proof file format are not supported)")]
+    InvalidMagic,
+    #[error("unsupported proof file format version {found}, expected {expected}")]
+    FormatVersionMismatch { found: u32, expected: u32 },
+    #[error("the proof was generated with SP1 {found}, but this is SP1 {expected}")]
+    Sp1VersionMismatch { found: String, expected: String },
+    #[error("expected a {expected:?} proof, found a {found:?} proof")]
+    KindMismatch {
+        found: ProofKind,
+        expected: ProofKind,
+    },
+    #[error("the proof was generated with circuit artifacts {found}, but this is {expected}")]
+    CircuitVersionMismatch { found: String, expected: String },
+}
+
+/// A proof that can be saved in a proof file.
+pub trait ProofPayload: Serialize + DeserializeOwned + Debug + Clone {
+    const KIND: ProofKind;
+
+    /// The version of the circuit artifacts the proof depends on.
+    fn circuit_version() -> Option<String> {
+        None
+    }
+}
+
+impl ProofPayload for Vec<ShardProof<CoreSC>> {
+    const KIND: ProofKind = ProofKind::Core;
+}
+
+impl ProofPayload for ShardProof<InnerSC> {
+    const KIND: ProofKind = ProofKind::Compressed;
+}
+
+impl ProofPayload for Groth16Proof {
+    const KIND: ProofKind = ProofKind::Groth16;
+
+    fn circuit_version() -> Option<String> {
+        Some(GROTH16_ARTIFACTS_COMMIT.to_string())
+    }
+}
+
+/// PLONK proofs are not generated with versioned circuit artifacts yet, so no circuit version is
+/// recorded for them.
+impl ProofPayload for PlonkBn254Proof {
+    const KIND: ProofKind = ProofKind::Plonk;
+}
+
+impl<P: ProofPayload> SP1ProofWithPublicValues<P> {
+    /// Returns the header saved with the proof of the program with the given verifying key.
+    pub fn header(&self, vk: &SP1VerifyingKey) -> ProofHeader {
+        ProofHeader {
+            sp1_version: SP1_VERSION.to_string(),
+            kind: P::KIND,
+            vkey_hash: vk.bytes32(),
+            circuit_version: P::circuit_version(),
+        }
+    }
+
+    /// Saves the proof of the program with the given verifying key to a path, in the proof file
+    /// format.
+    pub fn save(&self, path: impl AsRef<Path>, vk: &SP1VerifyingKey) -> Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("failed to create proof file {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(&PROOF_MAGIC)?;
+        writer.write_all(&PROOF_FORMAT_VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut writer, &self.header(vk))?;
+        bincode::serialize_into(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Loads a proof saved with [Self::save], checking that it was generated by the same version of
+    /// SP1 and is of the expected kind.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("failed to open proof file {}", path.display()))?;
+        let mut reader = BufReader::new(file);
+        let header = read_header(&mut reader)?;
+
+        if header.sp1_version != SP1_VERSION {
+            return Err(ProofFileError::Sp1VersionMismatch {
+                found: header.sp1_version,
+                expected: SP1_VERSION.to_string(),
+            }
+            .into());
+        }
+        if header.kind != P::KIND {
+            return Err(ProofFileError::KindMismatch {
+                found: header.kind,
+                expected: P::KIND,
+            }
+            .into());
+        }
+        if let (Some(found), Some(expected)) = (header.circuit_version, P::circuit_version()) {
+            if found != expected {
+                return Err(ProofFileError::CircuitVersionMismatch { found, expected }.into());
+            }
+        }
+
+        bincode::deserialize_from(&mut reader).context("failed to deserialize proof")
+    }
+}
+
+/// Reads the header of a proof file, without reading the proof.
+pub fn read_header(reader: &mut impl Read) -> Result<ProofHeader> {
+    let mut magic = [0u8; PROOF_MAGIC.len()];
+    reader
+        .read_exact(&mut magic)
+        .map_err(|_| ProofFileError::InvalidMagic)?;
+    if magic != PROOF_MAGIC {
+        return Err(ProofFileError::InvalidMagic.into());
+    }
+
+    let mut version = [0u8; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version != PROOF_FORMAT_VERSION {
+        return Err(ProofFileError::FormatVersionMismatch {
+            found: version,
+            expected: PROOF_FORMAT_VERSION,
+        }
+        .into());
+    }
+
+    bincode::deserialize_from(reader).context("failed to deserialize proof header")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Seek;
+
+    use super::*;
+    use crate::SP1Proof;
+
+    #[test]
+    fn test_proof_file_header() {
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (_, vk) = sp1_prover::SP1Prover::new().setup(elf);
+        let proof = SP1Proof {
+            proof: Vec::new(),
+            stdin: sp1_prover::SP1Stdin::new(),
+            public_values: sp1_prover::SP1PublicValues::new(),
+        };
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        proof.save(file.path(), &vk).unwrap();
+
+        let header = read_header(&mut file).unwrap();
+        assert_eq!(header, proof.header(&vk));
+        assert_eq!(header.kind, ProofKind::Core);
+        assert_eq!(header.vkey_hash, vk.bytes32());
+        SP1Proof::load(file.path()).unwrap();
+
+        // A proof from another version of SP1 is rejected.
+        let mut header = proof.header(&vk);
+        header.sp1_version = "0.0.0".to_string();
+        file.rewind().unwrap();
+        file.write_all(&PROOF_MAGIC).unwrap();
+        file.write_all(&PROOF_FORMAT_VERSION.to_le_bytes()).unwrap();
+        bincode::serialize_into(&mut file, &header).unwrap();
+        bincode::serialize_into(&mut file, &proof).unwrap();
+        let err = SP1Proof::load(file.path()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ProofFileError>(),
+            Some(ProofFileError::Sp1VersionMismatch { .. })
+        ));
+
+        // A file without the magic bytes is rejected.
+        std::fs::write(file.path(), bincode::serialize(&proof).unwrap()).unwrap();
+        let err = SP1Proof::load(file.path()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ProofFileError>(),
+            Some(ProofFileError::InvalidMagic)
+        ));
+    }
+}
@@ -0,0 +1,95 @@
+use std::{fs::File, path::Path, time::Duration};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sp1_core::{
+    runtime::Program,
+    stark::SecurityConfig,
+    utils::{estimate_core, BenchmarkProfile, ShardEstimate},
+};
+use sp1_prover::{SP1ProverOpts, SP1Stdin};
+
+/// The name of the hash function of the profiles used to calibrate [ProofEstimate].
+const PROFILE_HASHFN: &str = "poseidon";
+
+/// The estimated cost of proving a program, computed by [crate::ProverClient::estimate].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProofEstimate {
+    /// The number of cycles of the program.
+    pub cycles: u64,
+    /// The estimated traces of each shard of the core proof.
+    pub shards: Vec<ShardEstimate>,
+    /// The number of cells of the traces of every shard, counted in field elements.
+    pub total_trace_area: usize,
+    /// The estimated size in bytes of the proof of each shard.
+    pub shard_proof_sizes: Vec<usize>,
+    /// The number of recursion programs proven to compress the core proof.
+    pub num_recursion_steps: usize,
+    /// The estimated peak memory of the core prover, in bytes.
+    pub peak_memory: usize,
+    /// The estimated duration of the core prover, if the estimate was calibrated with a
+    /// [BenchmarkProfile].
+    pub prove_duration: Option<Duration>,
+}
+
+impl ProofEstimate {
+    /// Estimates the cost of proving a program with the given options and FRI parameters of the
+    /// core prover.
+    pub fn new(
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        opts: SP1ProverOpts,
+        security: SecurityConfig,
+    ) -> Result<Self> {
+        let program = Program::from(elf);
+        let core = estimate_core(program, stdin, opts.core_opts, security)?;
+
+        let batch_size = opts.compress_opts.batch_size;
+        let num_first_layer =
+            core.shards.len().div_ceil(batch_size) + stdin.proofs.len().div_ceil(batch_size);
+        let mut num_recursion_steps = num_first_layer;
+        let mut layer_size = num_first_layer;
+        loop {
+            layer_size = layer_size.div_ceil(batch_size);
+            num_recursion_steps += layer_size;
+            if layer_size <= 1 {
+                break;
+            }
+        }
+
+        Ok(Self {
+            cycles: core.shards.iter().map(|shard| shard.cycles).sum(),
+            total_trace_area: core.shards.iter().map(ShardEstimate::trace_area).sum(),
+            shard_proof_sizes: core
+                .shards
+                .iter()
+                .map(|shard| shard.proof_size(&security))
+                .collect(),
+            shards: core.shards,
+            num_recursion_steps,
+            peak_memory: core.peak_memory,
+            prove_duration: None,
+        })
+    }
+
+    /// Estimates the duration of the core prover from the throughput measured in a profile.
+    pub fn calibrate(mut self, profile: &BenchmarkProfile) -> Self {
+        self.prove_duration = profile
+            .trace_area_per_second(PROFILE_HASHFN)
+            .map(|throughput| Duration::from_secs_f64(self.total_trace_area as f64 / throughput));
+        self
+    }
+
+    /// The estimated size in bytes of the core proof.
+    pub fn total_proof_size(&self) -> usize {
+        self.shard_proof_sizes.iter().sum()
+    }
+}
+
+/// Loads a [BenchmarkProfile] written by the `eval` binary.
+pub fn load_benchmark_profile(path: impl AsRef<Path>) -> Result<BenchmarkProfile> {
+    let path = path.as_ref();
+    let file = File::open(path)
+        .with_context(|| format!("failed to open benchmark profile {}", path.display()))?;
+    serde_json::from_reader(file).context("failed to parse benchmark profile")
+}
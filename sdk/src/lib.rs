@@ -11,29 +11,62 @@
 pub mod proto {
     pub mod network;
 }
+pub mod aggregation;
 pub mod artifacts;
 pub mod auth;
 pub mod client;
+pub mod envelope;
+pub mod estimate;
+pub mod io {
+    pub use sp1_core::io::{Bincode, Borsh, Codec, CodecError, CodecFormat, Json, SolidityAbi};
+}
 pub mod provers;
+pub mod server;
+pub mod worker;
 pub mod utils {
-    pub use sp1_core::utils::setup_logger;
+    pub use sp1_core::stark::{ChipStats, SecurityConfig, ShardStats, SoundnessAssumption};
+    pub use sp1_core::utils::{
+        auto_tune_opts, dump_traces, setup_logger, CancellationToken, ProgressEvent, ProgressSink,
+        ProofPhase, ProofProgress, ProverResources, SP1CoreOpts, TunedOpts,
+    };
 }
 
-use std::{env, fmt::Debug, fs::File, path::Path};
+use std::{env, fmt::Debug, sync::OnceLock};
 
 use anyhow::{Ok, Result};
-pub use provers::{LocalProver, MockProver, NetworkProver, Prover};
+pub use envelope::{ProofFileError, ProofKind};
+pub use estimate::ProofEstimate;
+pub use provers::{LocalProver, MockProver, NetworkProver, NetworkProverError, Prover};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sp1_core::stark::{MachineVerificationError, ShardProof};
+pub use sp1_prover::verify::Groth16VerificationError;
 pub use sp1_prover::{
-    CoreSC, Groth16Proof, HashableKey, InnerSC, OuterSC, PlonkBn254Proof, SP1Prover, SP1ProvingKey,
-    SP1PublicValues, SP1Stdin, SP1VerifyingKey,
+    CompressOptions, CoreHash, CoreSC, Groth16Proof, HashableKey, InnerSC, OuterSC,
+    PlonkBn254Proof, ReduceTreeShape, SP1AnyCoreProof, SP1AnyCoreVerificationError,
+    SP1AnyCoreVerifyingKey, SP1Prover, SP1ProverOpts, SP1ProvingKey, SP1PublicValues,
+    SP1StageConfigs, SP1Stdin, SP1VerifyingKey,
 };
+use thiserror::Error;
+
+/// Includes the bytes of an ELF built by `sp1_helper::build_program` in the build script of the
+/// crate, by the name of its binary.
+///
+/// ```ignore
+/// const ELF: &[u8] = sp1_sdk::include_elf!("fibonacci-program");
+/// ```
+#[macro_export]
+macro_rules! include_elf {
+    ($name:literal) => {
+        include_bytes!(env!(concat!("SP1_ELF_", $name)))
+    };
+}
 
 /// A client for interacting with SP1.
 pub struct ProverClient {
     /// The underlying prover implementation.
     pub prover: Box<dyn Prover>,
+    /// The keys of [aggregation::AGGREGATION_ELF], set up on the first aggregation.
+    aggregation_keys: OnceLock<(SP1ProvingKey, SP1VerifyingKey)>,
 }
 
 /// A proof generated with SP1.
@@ -48,7 +81,17 @@ pub struct SP1ProofWithPublicValues<P> {
 
 /// A [SP1ProofWithPublicValues] generated with [ProverClient::prove].
 pub type SP1Proof = SP1ProofWithPublicValues<Vec<ShardProof<CoreSC>>>;
-pub type SP1ProofVerificationError = MachineVerificationError<CoreSC>;
+
+/// An error returned when a proof fails verification, naming the check that failed.
+#[derive(Error, Debug)]
+pub enum SP1ProofVerificationError {
+    #[error("invalid core proof: {0}")]
+    Core(MachineVerificationError<CoreSC>),
+    #[error("invalid compressed proof: {0}")]
+    Compressed(MachineVerificationError<CoreSC>),
+    #[error("invalid groth16 proof: {0}")]
+    Groth16(#[from] Groth16VerificationError),
+}
 
 /// A [SP1ProofWithPublicValues] generated with [ProverClient::prove_compressed].
 pub type SP1CompressedProof = SP1ProofWithPublicValues<ShardProof<InnerSC>>;
@@ -84,12 +127,15 @@ impl ProverClient {
         {
             "mock" => Self {
                 prover: Box::new(MockProver::new()),
+                aggregation_keys: OnceLock::new(),
             },
             "local" => Self {
                 prover: Box::new(LocalProver::new()),
+                aggregation_keys: OnceLock::new(),
             },
             "network" => Self {
                 prover: Box::new(NetworkProver::new()),
+                aggregation_keys: OnceLock::new(),
             },
             _ => panic!(
                 "invalid value for SP1_PROVER enviroment variable: expected 'local', 'mock', or 'remote'"
@@ -112,6 +158,7 @@ impl ProverClient {
     pub fn mock() -> Self {
         Self {
             prover: Box::new(MockProver::new()),
+            aggregation_keys: OnceLock::new(),
         }
     }
 
@@ -130,6 +177,7 @@ impl ProverClient {
     pub fn local() -> Self {
         Self {
             prover: Box::new(LocalProver::new()),
+            aggregation_keys: OnceLock::new(),
         }
     }
 
@@ -147,6 +195,7 @@ impl ProverClient {
     pub fn remote() -> Self {
         Self {
             prover: Box::new(NetworkProver::new()),
+            aggregation_keys: OnceLock::new(),
         }
     }
 
@@ -176,11 +225,50 @@ impl ProverClient {
         Ok(SP1Prover::execute(elf, &stdin)?)
     }
 
+    /// Estimates the cost of proving the given program on the given input, without generating a
+    /// proof.
+    ///
+    /// The program is executed once to count the rows of every chip in each shard, from which the
+    /// trace area, the proof sizes, the number of recursion steps and the peak memory of the prover
+    /// are derived. Use [ProofEstimate::calibrate] with a profile written by the `eval` binary to
+    /// also estimate the proving time on this machine.
+    ///
+    /// The estimate uses the options and the core FRI parameters of the prover of the client. Use
+    /// [ProverClient::estimate_with_opts] to estimate with other options.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    /// let estimate = client.estimate(elf, stdin).unwrap();
+    /// println!("shards: {}, trace area: {}", estimate.shards.len(), estimate.total_trace_area);
+    /// ```
+    pub fn estimate(&self, elf: &[u8], stdin: SP1Stdin) -> Result<ProofEstimate> {
+        self.estimate_with_opts(elf, stdin, self.prover.opts())
+    }
+
+    /// Estimates the cost of proving the given program on the given input with the given options,
+    /// without generating a proof. See [ProverClient::estimate].
+    pub fn estimate_with_opts(
+        &self,
+        elf: &[u8],
+        stdin: SP1Stdin,
+        opts: SP1ProverOpts,
+    ) -> Result<ProofEstimate> {
+        ProofEstimate::new(elf, &stdin, opts, self.prover.core_security())
+    }
+
     /// Setup a program to be proven and verified by the SP1 RISC-V zkVM by computing the proving
     /// and verifying keys.
     ///
     /// The proving key and verifying key essentially embed the program, as well as other auxiliary
-    /// data (such as lookup tables) that are used to prove the program's correctness.
+    /// data (such as lookup tables) that are used to prove the program's correctness. The proving
+    /// key can be saved with [SP1ProvingKey::save] and loaded with [SP1ProvingKey::load] to skip the
+    /// setup when the same program is proven again.
     ///
     /// ### Examples
     /// ```no_run
@@ -289,6 +377,56 @@ impl ProverClient {
         self.prover.prove_groth16(pk, stdin)
     }
 
+    /// Aggregates compressed proofs of any programs into a single compressed proof.
+    ///
+    /// The proof is a proof of [aggregation::AGGREGATION_ELF], which verifies each proof and
+    /// commits to the (vkey hash, public values digest) pair of every proof, in order, as encoded by
+    /// [aggregation::encode_aggregation]. Use [ProverClient::aggregate_groth16] to get a proof of
+    /// the same program that can be verified onchain.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proofs = (0..2)
+    ///     .map(|n| {
+    ///         let mut stdin = SP1Stdin::new();
+    ///         stdin.write(&n);
+    ///         (client.prove_compressed(&pk, stdin).unwrap(), vk.clone())
+    ///     })
+    ///     .collect::<Vec<_>>();
+    /// let proof = client.aggregate(&proofs).unwrap();
+    /// ```
+    pub fn aggregate(
+        &self,
+        proofs: &[(SP1CompressedProof, SP1VerifyingKey)],
+    ) -> Result<SP1CompressedProof> {
+        let (pk, _) = self.aggregation_keys();
+        self.prover
+            .prove_compressed(pk, aggregation::aggregation_stdin(proofs))
+    }
+
+    /// Aggregates compressed proofs of any programs into a single groth16 proof.
+    ///
+    /// See [ProverClient::aggregate] for the public values of the proof.
+    pub fn aggregate_groth16(
+        &self,
+        proofs: &[(SP1CompressedProof, SP1VerifyingKey)],
+    ) -> Result<SP1Groth16Proof> {
+        let (pk, _) = self.aggregation_keys();
+        self.prover
+            .prove_groth16(pk, aggregation::aggregation_stdin(proofs))
+    }
+
+    /// Returns the keys of [aggregation::AGGREGATION_ELF], setting them up once per client.
+    fn aggregation_keys(&self) -> &(SP1ProvingKey, SP1VerifyingKey) {
+        self.aggregation_keys
+            .get_or_init(|| self.setup(aggregation::AGGREGATION_ELF))
+    }
+
     /// Proves the execution of the given program with the given input in the plonk mode.
     ///
     /// Returns a proof of the program's execution in the plonk format. The proof is a succinct
@@ -369,7 +507,7 @@ impl ProverClient {
         &self,
         proof: &SP1CompressedProof,
         vkey: &SP1VerifyingKey,
-    ) -> Result<()> {
+    ) -> Result<(), SP1ProofVerificationError> {
         self.prover.verify_compressed(proof, vkey)
     }
 
@@ -399,7 +537,11 @@ impl ProverClient {
     /// // Verify the proof.
     /// client.verify_groth16(&proof, &vk).unwrap();
     /// ```
-    pub fn verify_groth16(&self, proof: &SP1Groth16Proof, vkey: &SP1VerifyingKey) -> Result<()> {
+    pub fn verify_groth16(
+        &self,
+        proof: &SP1Groth16Proof,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<(), SP1ProofVerificationError> {
         self.prover.verify_groth16(proof, vkey)
     }
 
@@ -429,7 +571,11 @@ impl ProverClient {
     /// // Verify the proof.
     /// client.verify_plonk(&proof, &vk).unwrap();
     /// ```
-    pub fn verify_plonk(&self, proof: &SP1PlonkProof, vkey: &SP1VerifyingKey) -> Result<()> {
+    pub fn verify_plonk(
+        &self,
+        proof: &SP1PlonkProof,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<(), SP1ProofVerificationError> {
         self.prover.verify_plonk(proof, vkey)
     }
 }
@@ -440,20 +586,6 @@ impl Default for ProverClient {
     }
 }
 
-impl<P: Debug + Clone + Serialize + DeserializeOwned> SP1ProofWithPublicValues<P> {
-    /// Saves the proof to a path.
-    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
-        bincode::serialize_into(File::create(path).expect("failed to open file"), self)
-            .map_err(Into::into)
-    }
-
-    /// Loads a proof from a path.
-    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
-        bincode::deserialize_from(File::open(path).expect("failed to open file"))
-            .map_err(Into::into)
-    }
-}
-
 impl SP1Groth16Proof {
     pub fn bytes(&self) -> String {
         format!("0x{}", self.proof.encoded_proof.clone())
@@ -463,7 +595,9 @@ impl SP1Groth16Proof {
 #[cfg(test)]
 mod tests {
 
-    use crate::{utils, ProverClient, SP1Stdin};
+    use sp1_core::stark::MachineVerificationError;
+
+    use crate::{utils, ProverClient, SP1ProofVerificationError, SP1PublicValues, SP1Stdin};
 
     #[test]
     fn test_execute() {
@@ -476,6 +610,28 @@ mod tests {
         client.execute(elf, stdin).unwrap();
     }
 
+    #[test]
+    fn test_estimate() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let estimate = client.estimate(elf, stdin).unwrap();
+        assert!(!estimate.shards.is_empty());
+        assert!(estimate.total_trace_area > 0);
+        assert_eq!(estimate.shard_proof_sizes.len(), estimate.shards.len());
+        assert!(estimate.num_recursion_steps >= 2);
+        assert!(estimate.prove_duration.is_none());
+
+        // The mock prover estimates with the default core FRI parameters.
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let mock_estimate = ProverClient::mock().estimate(elf, stdin).unwrap();
+        assert_eq!(mock_estimate.shards.len(), estimate.shards.len());
+    }
+
     #[test]
     #[should_panic]
     fn test_execute_panic() {
@@ -500,6 +656,25 @@ mod tests {
         client.verify(&proof, &vk).unwrap();
     }
 
+    #[test]
+    fn test_e2e_prove_local_tampered_public_values() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let mut proof = client.prove(&pk, stdin).unwrap();
+        proof.public_values = SP1PublicValues::from(&[0]);
+        assert!(matches!(
+            client.verify(&proof, &vk),
+            Err(SP1ProofVerificationError::Core(
+                MachineVerificationError::InvalidPublicValuesDigest
+            ))
+        ));
+    }
+
     #[test]
     fn test_e2e_prove_groth16() {
         utils::setup_logger();
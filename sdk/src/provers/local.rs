@@ -1,5 +1,6 @@
 use anyhow::Result;
-use sp1_prover::{SP1Prover, SP1Stdin};
+use sp1_core::utils::ProofProgress;
+use sp1_prover::{SP1Prover, SP1ProverOpts, SP1StageConfigs, SP1Stdin};
 
 use crate::{
     Prover, SP1CompressedProof, SP1Groth16Proof, SP1PlonkProof, SP1Proof, SP1ProofWithPublicValues,
@@ -9,13 +10,36 @@ use crate::{
 /// An implementation of [crate::ProverClient] that can generate end-to-end proofs locally.
 pub struct LocalProver {
     prover: SP1Prover,
+    opts: SP1ProverOpts,
+    progress: ProofProgress,
 }
 
 impl LocalProver {
     /// Creates a new [LocalProver].
     pub fn new() -> Self {
-        let prover = SP1Prover::new();
-        Self { prover }
+        Self::from_stage_configs(SP1StageConfigs::default())
+    }
+
+    /// Creates a new [LocalProver] whose stages use the given FRI parameters.
+    pub fn from_stage_configs(configs: SP1StageConfigs) -> Self {
+        let prover = SP1Prover::with_stage_configs(configs);
+        Self {
+            prover,
+            opts: SP1ProverOpts::default(),
+            progress: ProofProgress::default(),
+        }
+    }
+
+    /// Sets the options used by each stage of the prover.
+    pub fn with_opts(mut self, opts: SP1ProverOpts) -> Self {
+        self.opts = opts;
+        self
+    }
+
+    /// Sets the sink receiving the progress of the proofs and the token used to cancel them.
+    pub fn with_progress(mut self, progress: ProofProgress) -> Self {
+        self.progress = progress;
+        self
     }
 }
 
@@ -32,8 +56,13 @@ impl Prover for LocalProver {
         &self.prover
     }
 
+    fn opts(&self) -> SP1ProverOpts {
+        self.opts
+    }
+
     fn prove(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Proof> {
-        let proof = self.prover.prove_core(pk, &stdin)?;
+        let progress = self.progress.restarted();
+        let proof = self.prover.prove_core(pk, &stdin, self.opts, &progress)?;
         Ok(SP1ProofWithPublicValues {
             proof: proof.proof.0,
             stdin: proof.stdin,
@@ -42,10 +71,13 @@ impl Prover for LocalProver {
     }
 
     fn prove_compressed(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1CompressedProof> {
-        let proof = self.prover.prove_core(pk, &stdin)?;
+        let progress = self.progress.restarted();
+        let proof = self.prover.prove_core(pk, &stdin, self.opts, &progress)?;
         let deferred_proofs = stdin.proofs.iter().map(|p| p.0.clone()).collect();
         let public_values = proof.public_values.clone();
-        let reduce_proof = self.prover.compress(&pk.vk, proof, deferred_proofs)?;
+        let reduce_proof =
+            self.prover
+                .compress(&pk.vk, proof, deferred_proofs, self.opts, &progress)?;
         Ok(SP1CompressedProof {
             proof: reduce_proof.proof,
             stdin,
@@ -54,12 +86,17 @@ impl Prover for LocalProver {
     }
 
     fn prove_groth16(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Groth16Proof> {
-        let proof = self.prover.prove_core(pk, &stdin)?;
+        let progress = self.progress.restarted();
+        let proof = self.prover.prove_core(pk, &stdin, self.opts, &progress)?;
         let deferred_proofs = stdin.proofs.iter().map(|p| p.0.clone()).collect();
         let public_values = proof.public_values.clone();
-        let reduce_proof = self.prover.compress(&pk.vk, proof, deferred_proofs)?;
-        let compress_proof = self.prover.shrink(reduce_proof)?;
-        let outer_proof = self.prover.wrap_bn254(compress_proof)?;
+        let reduce_proof =
+            self.prover
+                .compress(&pk.vk, proof, deferred_proofs, self.opts, &progress)?;
+        let compress_proof = self.prover.shrink(reduce_proof, self.opts, &progress)?;
+        let outer_proof = self
+            .prover
+            .wrap_bn254(compress_proof, self.opts, &progress)?;
 
         let groth16_aritfacts = if sp1_prover::build::sp1_dev_mode() {
             sp1_prover::build::try_build_groth16_artifacts_dev(
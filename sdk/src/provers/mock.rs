@@ -5,6 +5,7 @@ use crate::{
 };
 use anyhow::Result;
 use p3_field::PrimeField;
+use sp1_core::stark::SecurityConfig;
 use sp1_prover::{
     verify::verify_groth16_public_inputs, Groth16Proof, HashableKey, SP1Prover, SP1Stdin,
 };
@@ -35,6 +36,10 @@ impl Prover for MockProver {
         unimplemented!("MockProver does not support SP1Prover")
     }
 
+    fn core_security(&self) -> SecurityConfig {
+        SecurityConfig::core()
+    }
+
     fn prove(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Proof> {
         let public_values = SP1Prover::execute(&pk.elf, &stdin)?;
         Ok(SP1ProofWithPublicValues {
@@ -84,16 +89,24 @@ impl Prover for MockProver {
         &self,
         _proof: &SP1CompressedProof,
         _vkey: &SP1VerifyingKey,
-    ) -> Result<()> {
+    ) -> Result<(), SP1ProofVerificationError> {
         Ok(())
     }
 
-    fn verify_groth16(&self, proof: &SP1Groth16Proof, vkey: &SP1VerifyingKey) -> Result<()> {
+    fn verify_groth16(
+        &self,
+        proof: &SP1Groth16Proof,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<(), SP1ProofVerificationError> {
         verify_groth16_public_inputs(vkey, &proof.public_values, &proof.proof.public_inputs)?;
         Ok(())
     }
 
-    fn verify_plonk(&self, _proof: &SP1PlonkProof, _vkey: &SP1VerifyingKey) -> Result<()> {
+    fn verify_plonk(
+        &self,
+        _proof: &SP1PlonkProof,
+        _vkey: &SP1VerifyingKey,
+    ) -> Result<(), SP1ProofVerificationError> {
         Ok(())
     }
 }
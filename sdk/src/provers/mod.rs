@@ -2,17 +2,20 @@ mod local;
 mod mock;
 mod network;
 
-use crate::{SP1CompressedProof, SP1Groth16Proof, SP1PlonkProof, SP1Proof};
+use crate::{
+    SP1CompressedProof, SP1Groth16Proof, SP1PlonkProof, SP1Proof, SP1ProofVerificationError,
+    SP1ProofWithPublicValues,
+};
 use anyhow::Result;
 pub use local::LocalProver;
 pub use mock::MockProver;
-pub use network::NetworkProver;
-use sp1_core::stark::MachineVerificationError;
-use sp1_prover::CoreSC;
+pub use network::{NetworkProver, NetworkProverError};
+use sp1_core::stark::{SecurityConfig, ShardProof, StarkGenericConfig};
+use sp1_prover::verify::{verify_compressed_public_values, verify_core_public_values};
 use sp1_prover::SP1CoreProofData;
 use sp1_prover::SP1Prover;
 use sp1_prover::SP1ReduceProof;
-use sp1_prover::{SP1ProvingKey, SP1Stdin, SP1VerifyingKey};
+use sp1_prover::{InnerSC, SP1ProverOpts, SP1ProvingKey, SP1Stdin, SP1VerifyingKey};
 
 /// An implementation of [crate::ProverClient].
 pub trait Prover: Send + Sync {
@@ -20,6 +23,16 @@ pub trait Prover: Send + Sync {
 
     fn sp1_prover(&self) -> &SP1Prover;
 
+    /// The options used by each stage of the prover.
+    fn opts(&self) -> SP1ProverOpts {
+        SP1ProverOpts::default()
+    }
+
+    /// The FRI parameters of the core prover.
+    fn core_security(&self) -> SecurityConfig {
+        self.sp1_prover().core_machine.config().security()
+    }
+
     fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey);
 
     /// Prove the execution of a RISCV ELF with the given inputs.
@@ -39,26 +52,48 @@ pub trait Prover: Send + Sync {
         &self,
         proof: &SP1Proof,
         vkey: &SP1VerifyingKey,
-    ) -> Result<(), MachineVerificationError<CoreSC>> {
+    ) -> Result<(), SP1ProofVerificationError> {
+        let proof_data = SP1CoreProofData(proof.proof.clone());
         self.sp1_prover()
-            .verify(&SP1CoreProofData(proof.proof.clone()), vkey)
+            .verify(&proof_data, vkey)
+            .and_then(|_| {
+                verify_core_public_values(
+                    &proof_data,
+                    &proof.public_values,
+                    &deferred_proofs(proof),
+                )
+            })
+            .map_err(SP1ProofVerificationError::Core)
     }
 
     /// Verify that a compressed SP1 proof is valid given its vkey and metadata.
-    fn verify_compressed(&self, proof: &SP1CompressedProof, vkey: &SP1VerifyingKey) -> Result<()> {
+    fn verify_compressed(
+        &self,
+        proof: &SP1CompressedProof,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<(), SP1ProofVerificationError> {
+        let reduce_proof = SP1ReduceProof {
+            proof: proof.proof.clone(),
+        };
         self.sp1_prover()
-            .verify_compressed(
-                &SP1ReduceProof {
-                    proof: proof.proof.clone(),
-                },
-                vkey,
-            )
-            .map_err(|e| e.into())
+            .verify_compressed(&reduce_proof, vkey)
+            .and_then(|_| {
+                verify_compressed_public_values(
+                    &reduce_proof,
+                    &proof.public_values,
+                    &deferred_proofs(proof),
+                )
+            })
+            .map_err(SP1ProofVerificationError::Compressed)
     }
 
     /// Verify that a SP1 Groth16 proof is valid. Verify that the public inputs of the Groth16Proof match
     /// the hash of the VK and the committed public values of the SP1ProofWithPublicValues.
-    fn verify_groth16(&self, proof: &SP1Groth16Proof, vkey: &SP1VerifyingKey) -> Result<()> {
+    fn verify_groth16(
+        &self,
+        proof: &SP1Groth16Proof,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<(), SP1ProofVerificationError> {
         let sp1_prover = self.sp1_prover();
 
         let groth16_aritfacts = if sp1_prover::build::sp1_dev_mode() {
@@ -72,7 +107,24 @@ pub trait Prover: Send + Sync {
     }
 
     /// Verify that a SP1 PLONK proof is valid given its vkey and metadata.
-    fn verify_plonk(&self, _proof: &SP1PlonkProof, _vkey: &SP1VerifyingKey) -> Result<()> {
+    fn verify_plonk(
+        &self,
+        _proof: &SP1PlonkProof,
+        _vkey: &SP1VerifyingKey,
+    ) -> Result<(), SP1ProofVerificationError> {
         Ok(())
     }
 }
+
+/// The proofs written to the stdin of the program, in order.
+///
+/// The program verifies a prefix of them, one per call to `verify_sp1_proof`, which is the prefix
+/// covered by the deferred proofs digest of the proof.
+fn deferred_proofs<P>(proof: &SP1ProofWithPublicValues<P>) -> Vec<ShardProof<InnerSC>> {
+    proof
+        .stdin
+        .proofs
+        .iter()
+        .map(|(proof, _)| proof.clone())
+        .collect()
+}
@@ -1,6 +1,6 @@
 use std::{env, time::Duration};
 
-use crate::proto::network::ProofMode;
+use crate::proto::network::{GetProofStatusResponse, ProofMode, UnclaimReason};
 use crate::{
     client::NetworkClient,
     proto::network::{ProofStatus, TransactionStatus},
@@ -12,12 +12,48 @@ use crate::{
 use anyhow::{Context, Result};
 use serde::de::DeserializeOwned;
 use sp1_core::runtime::{Program, Runtime};
+use sp1_core::utils::SP1CoreOpts;
 use sp1_prover::utils::block_on;
 use sp1_prover::{SP1Prover, SP1Stdin};
-use tokio::{runtime, time::sleep};
+use thiserror::Error;
+use tokio::{
+    runtime,
+    time::{sleep, Instant},
+};
 
 use super::LocalProver;
 
+/// The delay before the first poll of the status of a proof request.
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The maximum delay between two polls of the status of a proof request.
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// An error returned while waiting for a proof requested with a [NetworkProver].
+#[derive(Error, Debug)]
+pub enum NetworkProverError {
+    #[error("proof {proof_id} was not fulfilled within {timeout:?}")]
+    Timeout { proof_id: String, timeout: Duration },
+    #[error("proof {proof_id} was unclaimed ({}): {description}", reason.as_str_name())]
+    Unclaimed {
+        proof_id: String,
+        reason: UnclaimReason,
+        description: String,
+    },
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl NetworkProverError {
+    fn unclaimed(proof_id: &str, status: &GetProofStatusResponse) -> Self {
+        Self::Unclaimed {
+            proof_id: proof_id.to_string(),
+            reason: status.unclaim_reason(),
+            description: status.unclaim_description().to_string(),
+        }
+    }
+}
+
 /// An implementation of [crate::ProverClient] that can generate proofs on a remote RPC server.
 pub struct NetworkProver {
     client: NetworkClient,
@@ -36,16 +72,29 @@ impl NetworkProver {
         }
     }
 
-    pub async fn prove_async<P: DeserializeOwned>(
+    /// Creates a new [NetworkProver] using the given client.
+    pub fn with_client(client: NetworkClient) -> Self {
+        Self {
+            client,
+            local_prover: LocalProver::new(),
+        }
+    }
+
+    /// Requests a proof of the program on the given input, returning the proof id.
+    ///
+    /// The program is executed locally first so that requests that cannot be proven are never
+    /// submitted. The id can be persisted to collect the proof later with [Self::get_proof] or
+    /// [Self::wait_proof].
+    pub async fn request_proof(
         &self,
         elf: &[u8],
         stdin: SP1Stdin,
         mode: ProofMode,
-    ) -> Result<P> {
+    ) -> Result<String> {
         let client = &self.client;
         // Execute the runtime before creating the proof request.
         let program = Program::from(elf);
-        let mut runtime = Runtime::new(program);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
         runtime.write_vecs(&stdin.buffer);
         for (proof, vkey) in stdin.proofs.iter() {
             runtime.write_proof(proof.clone(), vkey.clone());
@@ -57,34 +106,81 @@ impl NetworkProver {
 
         let proof_id = client.create_proof(elf, &stdin, mode).await?;
         log::info!("Created {}", proof_id);
+        Ok(proof_id)
+    }
 
+    /// Gets the proof of a request if it has been fulfilled, or `None` if it is still pending.
+    ///
+    /// Returns [NetworkProverError::Unclaimed] if the prover gave up on the request.
+    pub async fn get_proof<P: DeserializeOwned>(
+        &self,
+        proof_id: &str,
+    ) -> Result<Option<P>, NetworkProverError> {
+        let (status, maybe_proof) = self.client.get_proof_status::<P>(proof_id).await?;
+        match status.status() {
+            ProofStatus::ProofFulfilled => Ok(maybe_proof),
+            ProofStatus::ProofUnclaimed => Err(NetworkProverError::unclaimed(proof_id, &status)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Waits for the proof of a request, polling its status with exponential backoff.
+    ///
+    /// Returns [NetworkProverError::Timeout] if the proof is not fulfilled within `timeout`, in
+    /// which case the request can still be collected later.
+    pub async fn wait_proof<P: DeserializeOwned>(
+        &self,
+        proof_id: &str,
+        timeout: Option<Duration>,
+    ) -> Result<P, NetworkProverError> {
+        let start = Instant::now();
+        let mut interval = MIN_POLL_INTERVAL;
         let mut is_claimed = false;
         loop {
-            let (status, maybe_proof) = client.get_proof_status::<P>(&proof_id).await?;
-
+            let (status, maybe_proof) = self.client.get_proof_status::<P>(proof_id).await?;
             match status.status() {
                 ProofStatus::ProofFulfilled => {
                     return Ok(maybe_proof.unwrap());
                 }
-                ProofStatus::ProofClaimed => {
-                    if !is_claimed {
-                        log::info!("Proof request claimed, proving...");
-                        is_claimed = true;
-                    }
-                }
                 ProofStatus::ProofUnclaimed => {
-                    return Err(anyhow::anyhow!(
-                        "Proof generation failed: {}",
-                        status.unclaim_description()
-                    ));
+                    return Err(NetworkProverError::unclaimed(proof_id, &status));
                 }
-                _ => {
-                    sleep(Duration::from_secs(1)).await;
+                ProofStatus::ProofClaimed if !is_claimed => {
+                    log::info!("Proof request claimed, proving...");
+                    is_claimed = true;
                 }
+                _ => {}
             }
+
+            let delay = match timeout {
+                Some(timeout) => {
+                    let remaining = timeout.saturating_sub(start.elapsed());
+                    if remaining.is_zero() {
+                        return Err(NetworkProverError::Timeout {
+                            proof_id: proof_id.to_string(),
+                            timeout,
+                        });
+                    }
+                    interval.min(remaining)
+                }
+                None => interval,
+            };
+            sleep(delay).await;
+            interval = (interval * 2).min(MAX_POLL_INTERVAL);
         }
     }
 
+    /// Requests a proof and waits until it is fulfilled.
+    pub async fn prove_async<P: DeserializeOwned>(
+        &self,
+        elf: &[u8],
+        stdin: SP1Stdin,
+        mode: ProofMode,
+    ) -> Result<P> {
+        let proof_id = self.request_proof(elf, stdin, mode).await?;
+        Ok(self.wait_proof(&proof_id, None).await?)
+    }
+
     #[allow(dead_code)]
     /// Remotely relay a proof to a set of chains with their callback contracts.
     pub fn remote_relay(
@@ -185,3 +281,33 @@ impl Default for NetworkProver {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::tests::spawn_server;
+
+    const PRIVATE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_wait_proof_timeout() {
+        let (_, url, _dir) = spawn_server(0).await;
+
+        let prover = NetworkProver::with_client(NetworkClient::with_rpc_url(PRIVATE_KEY, &url));
+        let elf =
+            include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof_id = prover
+            .request_proof(elf, stdin, ProofMode::Core)
+            .await
+            .unwrap();
+
+        let proof = prover.get_proof::<SP1Proof>(&proof_id).await.unwrap();
+        assert!(proof.is_none());
+        let result = prover
+            .wait_proof::<SP1Proof>(&proof_id, Some(Duration::from_secs(2)))
+            .await;
+        assert!(matches!(result, Err(NetworkProverError::Timeout { .. })));
+    }
+}
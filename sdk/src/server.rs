@@ -0,0 +1,948 @@
+//! A self-hostable implementation of the prover network RPCs.
+//!
+//! [ProverServer] serves the `NetworkService` twirp API used by [crate::NetworkProver], so that
+//! `PROVER_NETWORK_RPC` can point to a private proving cluster. Proof requests are kept as JSON
+//! files in a job directory and the programs, inputs and proofs are stored as artifacts next to
+//! them. Requests are proven by the server itself with a [LocalProver] or claimed by external
+//! provers through `ClaimProof` and `FulfillProof`.
+//!
+//! Artifacts can only be uploaded with a signature of the requester, for the program and stdin,
+//! or of the claimer, for the proof. They are downloaded without authentication, so the server
+//! should only be reachable from a trusted network.
+
+use std::{
+    collections::HashMap,
+    fs,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::put,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sp1_prover::SP1Stdin;
+use tokio::sync::Notify;
+use twirp::TwirpErrorResponse;
+
+use crate::{
+    auth::{NetworkAuth, ARTIFACT_SIGNATURE_HEADER},
+    proto::network::{
+        router, ClaimProofRequest, ClaimProofResponse, CreateProofRequest, CreateProofResponse,
+        FulfillProofRequest, FulfillProofResponse, GetNonceRequest, GetNonceResponse,
+        GetProofRequestsRequest, GetProofRequestsResponse, GetProofStatusRequest,
+        GetProofStatusResponse, GetRelayStatusRequest, GetRelayStatusResponse, NetworkService,
+        ProofMode, ProofStatus, RelayProofRequest, RelayProofResponse, RequestedProof,
+        SubmitProofRequest, SubmitProofResponse, UnclaimProofRequest, UnclaimProofResponse,
+        UnclaimReason, SERVICE_FQN,
+    },
+    LocalProver, Prover,
+};
+
+/// The address recorded as the claimer of the requests proven by the server itself.
+const LOCAL_PROVER_ADDRESS: [u8; 20] = [0; 20];
+
+/// The maximum number of requests returned by `GetProofRequests`.
+const MAX_PROOF_REQUESTS: usize = 10;
+
+/// How often the local provers look for requests when they are not notified of a submission, and
+/// how often the expired claims are released.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The default [ServerOpts::claim_timeout].
+pub const DEFAULT_CLAIM_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Options of a [ProverServer].
+#[derive(Debug, Clone)]
+pub struct ServerOpts {
+    /// The directory storing the jobs and artifacts.
+    pub dir: PathBuf,
+    /// The URL under which clients reach the server, used to build the artifact URLs.
+    pub public_url: String,
+    /// The number of requests proven concurrently by the server itself. With zero, requests are
+    /// only proven by external provers claiming them.
+    pub local_provers: usize,
+    /// How long an external prover can keep a request claimed before it is requested again, so
+    /// that a prover that stopped without unclaiming does not block the request.
+    pub claim_timeout: Duration,
+}
+
+/// A proof request tracked by a [ProverServer].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofJob {
+    pub proof_id: String,
+    pub mode: ProofMode,
+    pub status: ProofStatus,
+    /// The account that created the request.
+    pub requester: [u8; 20],
+    /// The latest time, in seconds since the epoch, at which the request can be fulfilled.
+    pub deadline: u64,
+    pub created_at: u64,
+    pub program_artifact_id: String,
+    pub stdin_artifact_id: String,
+    pub proof_artifact_id: String,
+    /// The account that claimed the request, if any.
+    pub claimer: Option<[u8; 20]>,
+    pub claimed_at: Option<u64>,
+    pub unclaim_reason: Option<UnclaimReason>,
+    pub unclaim_description: Option<String>,
+}
+
+impl ProofJob {
+    fn artifact_ids(&self) -> [&str; 3] {
+        [
+            &self.program_artifact_id,
+            &self.stdin_artifact_id,
+            &self.proof_artifact_id,
+        ]
+    }
+}
+
+/// A server implementing [NetworkService] on top of a [LocalProver].
+pub struct ProverServer {
+    prover: Arc<LocalProver>,
+    opts: ServerOpts,
+    jobs: Mutex<HashMap<String, ProofJob>>,
+    nonces: Mutex<HashMap<String, u64>>,
+    /// Serializes the updates of the jobs and nonces, so that they are written to disk in order
+    /// without holding the locks above during the writes.
+    writes: tokio::sync::Mutex<()>,
+    submitted: Notify,
+}
+
+impl ProverServer {
+    /// Creates a new [ProverServer], loading the jobs left in the job directory.
+    ///
+    /// Requests that were being proven by the server itself when it stopped are queued again.
+    pub fn new(prover: LocalProver, opts: ServerOpts) -> Result<Self> {
+        let jobs_dir = opts.dir.join("jobs");
+        fs::create_dir_all(&jobs_dir).context("failed to create the jobs directory")?;
+        fs::create_dir_all(opts.dir.join("artifacts"))
+            .context("failed to create the artifacts directory")?;
+
+        let mut jobs = HashMap::new();
+        for entry in fs::read_dir(&jobs_dir)? {
+            let path = entry?.path();
+            let bytes = fs::read(&path)?;
+            let mut job: ProofJob = serde_json::from_slice(&bytes)
+                .with_context(|| format!("failed to parse job {}", path.display()))?;
+            if job.status == ProofStatus::ProofClaimed && job.claimer == Some(LOCAL_PROVER_ADDRESS)
+            {
+                job.status = ProofStatus::ProofRequested;
+                job.claimer = None;
+                job.claimed_at = None;
+            }
+            jobs.insert(job.proof_id.clone(), job);
+        }
+        tracing::info!("loaded {} jobs from {}", jobs.len(), jobs_dir.display());
+
+        let nonces_path = opts.dir.join("nonces.json");
+        let nonces = if nonces_path.exists() {
+            serde_json::from_slice(&fs::read(&nonces_path)?).context("failed to parse nonces")?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self {
+            prover: Arc::new(prover),
+            opts,
+            jobs: Mutex::new(jobs),
+            nonces: Mutex::new(nonces),
+            writes: tokio::sync::Mutex::new(()),
+            submitted: Notify::new(),
+        })
+    }
+
+    /// Returns the jobs tracked by the server.
+    pub fn jobs(&self) -> Vec<ProofJob> {
+        self.jobs.lock().unwrap().values().cloned().collect()
+    }
+
+    /// Returns the router serving the twirp API and the artifacts.
+    pub fn router(self: &Arc<Self>) -> Router {
+        let artifacts = Router::new()
+            .route(
+                "/artifacts/:id",
+                put(upload_artifact).get(download_artifact),
+            )
+            .layer(DefaultBodyLimit::disable())
+            .with_state(self.clone());
+        Router::new()
+            .nest(SERVICE_FQN, router(self.clone()))
+            .merge(artifacts)
+    }
+
+    /// Starts the local provers and serves the API on the given address.
+    pub async fn serve(self: Arc<Self>, addr: SocketAddr) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        tracing::info!(
+            "serving the prover network API on {}",
+            listener.local_addr()?
+        );
+        self.serve_with_listener(listener).await
+    }
+
+    /// Starts the local provers and serves the API on the given listener.
+    pub async fn serve_with_listener(
+        self: Arc<Self>,
+        listener: tokio::net::TcpListener,
+    ) -> Result<()> {
+        for _ in 0..self.opts.local_provers {
+            tokio::spawn(self.clone().run_local_prover());
+        }
+        tokio::spawn(self.clone().run_claim_expiry());
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+
+    /// Proves the submitted requests one at a time, until the server stops.
+    async fn run_local_prover(self: Arc<Self>) {
+        loop {
+            let Some(job) = self.claim_next().await else {
+                let _ = tokio::time::timeout(POLL_INTERVAL, self.submitted.notified()).await;
+                continue;
+            };
+            tracing::info!(
+                "proving {} in mode {}",
+                job.proof_id,
+                job.mode.as_str_name()
+            );
+
+            let server = self.clone();
+            let proof_id = job.proof_id.clone();
+            let result = tokio::task::spawn_blocking(move || server.prove_job(&job))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+
+            let update = match result {
+                Ok(()) => {
+                    self.update_job(&proof_id, |job| {
+                        job.status = ProofStatus::ProofFulfilled;
+                        Ok(())
+                    })
+                    .await
+                }
+                Err(err) => {
+                    tracing::error!("failed to prove {}: {:?}", proof_id, err);
+                    self.update_job(&proof_id, |job| {
+                        job.status = ProofStatus::ProofUnclaimed;
+                        job.unclaim_reason = Some(UnclaimReason::Abandoned);
+                        job.unclaim_description = Some(format!("{:#}", err));
+                        Ok(())
+                    })
+                    .await
+                }
+            };
+            if let Err(err) = update {
+                tracing::error!("failed to update {}: {:?}", proof_id, err);
+            }
+        }
+    }
+
+    /// Releases the expired claims of external provers, until the server stops.
+    async fn run_claim_expiry(self: Arc<Self>) {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            self.release_expired_claims().await;
+        }
+    }
+
+    /// Requests again the requests claimed by external provers for longer than the claim timeout.
+    ///
+    /// The requests proven by the server itself are not released, as their provers are only
+    /// stopped with the server.
+    async fn release_expired_claims(&self) {
+        let now = unix_time();
+        let timeout = self.opts.claim_timeout.as_secs();
+        let expired = {
+            let jobs = self.jobs.lock().unwrap();
+            jobs.values()
+                .filter(|job| Self::is_claim_expired(job, now, timeout))
+                .map(|job| job.proof_id.clone())
+                .collect::<Vec<_>>()
+        };
+        for proof_id in expired {
+            let update = self
+                .update_job(&proof_id, |job| {
+                    if !Self::is_claim_expired(job, now, timeout) {
+                        return Err(twirp::failed_precondition("the claim has not expired"));
+                    }
+                    job.status = ProofStatus::ProofRequested;
+                    job.claimer = None;
+                    job.claimed_at = None;
+                    Ok(())
+                })
+                .await;
+            match update {
+                Ok(()) => {
+                    tracing::warn!("the claim of {} expired", proof_id);
+                    self.submitted.notify_one();
+                }
+                Err(err) => tracing::debug!("kept the claim of {}: {:?}", proof_id, err),
+            }
+        }
+    }
+
+    fn is_claim_expired(job: &ProofJob, now: u64, timeout: u64) -> bool {
+        job.status == ProofStatus::ProofClaimed
+            && job.claimer != Some(LOCAL_PROVER_ADDRESS)
+            && job
+                .claimed_at
+                .map_or(true, |claimed_at| claimed_at.saturating_add(timeout) <= now)
+    }
+
+    /// Claims the oldest submitted request for the server itself.
+    async fn claim_next(&self) -> Option<ProofJob> {
+        let now = unix_time();
+        let proof_id = {
+            let jobs = self.jobs.lock().unwrap();
+            jobs.values()
+                .filter(|job| job.status == ProofStatus::ProofRequested && job.deadline >= now)
+                .min_by_key(|job| job.created_at)
+                .map(|job| job.proof_id.clone())?
+        };
+        self.update_job(&proof_id, |job| {
+            if job.status != ProofStatus::ProofRequested {
+                return Err(twirp::failed_precondition("the proof is not requested"));
+            }
+            job.status = ProofStatus::ProofClaimed;
+            job.claimer = Some(LOCAL_PROVER_ADDRESS);
+            job.claimed_at = Some(now);
+            Ok(job.clone())
+        })
+        .await
+        .ok()
+    }
+
+    /// Proves a claimed request and stores its proof artifact.
+    fn prove_job(&self, job: &ProofJob) -> Result<()> {
+        let program = fs::read(self.artifact_path(&job.program_artifact_id))
+            .context("failed to read the program artifact")?;
+        let stdin = fs::read(self.artifact_path(&job.stdin_artifact_id))
+            .context("failed to read the stdin artifact")?;
+        let (elf, stdin) = decode_artifacts(&program, &stdin)?;
+        let proof = prove_with_mode(self.prover.as_ref(), &elf, stdin, job.mode)?;
+        fs::write(self.artifact_path(&job.proof_artifact_id), proof)
+            .context("failed to write the proof artifact")?;
+        Ok(())
+    }
+
+    fn artifact_path(&self, artifact_id: &str) -> PathBuf {
+        self.opts.dir.join("artifacts").join(artifact_id)
+    }
+
+    fn artifact_url(&self, artifact_id: &str) -> String {
+        format!(
+            "{}/artifacts/{}",
+            self.opts.public_url.trim_end_matches('/'),
+            artifact_id
+        )
+    }
+
+    /// Applies `f` to a job and saves it if `f` succeeds.
+    ///
+    /// The job is only replaced in memory once it is saved, so readers never observe an update that
+    /// failed to be written.
+    async fn update_job<T>(
+        &self,
+        proof_id: &str,
+        f: impl FnOnce(&mut ProofJob) -> Result<T, TwirpErrorResponse>,
+    ) -> Result<T, TwirpErrorResponse> {
+        let _writes = self.writes.lock().await;
+        let mut job = self
+            .jobs
+            .lock()
+            .unwrap()
+            .get(proof_id)
+            .cloned()
+            .ok_or_else(|| twirp::not_found(format!("proof {} not found", proof_id)))?;
+        let result = f(&mut job)?;
+        self.save_job(&job).await.map_err(internal)?;
+        self.jobs.lock().unwrap().insert(job.proof_id.clone(), job);
+        Ok(result)
+    }
+
+    async fn save_job(&self, job: &ProofJob) -> Result<()> {
+        let path = self
+            .opts
+            .dir
+            .join("jobs")
+            .join(format!("{}.json", job.proof_id));
+        tokio::fs::write(path, serde_json::to_vec_pretty(job)?).await?;
+        Ok(())
+    }
+
+    /// Checks that `nonce` is the next nonce of the signer and increments it.
+    async fn use_nonce(
+        &self,
+        signer: anyhow::Result<[u8; 20]>,
+        nonce: u64,
+    ) -> Result<[u8; 20], TwirpErrorResponse> {
+        let signer = signer.map_err(|err| twirp::unauthenticated(err.to_string()))?;
+        let _writes = self.writes.lock().await;
+        let mut nonces = self.nonces.lock().unwrap().clone();
+        let expected = nonces.entry(hex::encode(signer)).or_default();
+        if *expected != nonce {
+            return Err(twirp::invalid_argument(format!(
+                "invalid nonce {}, expected {}",
+                nonce, expected
+            )));
+        }
+        *expected += 1;
+        let bytes = serde_json::to_vec(&nonces).map_err(internal)?;
+        tokio::fs::write(self.opts.dir.join("nonces.json"), bytes)
+            .await
+            .map_err(internal)?;
+        *self.nonces.lock().unwrap() = nonces;
+        Ok(signer)
+    }
+
+    /// Returns whether an artifact can be uploaded by `signer`, which is only the case for the
+    /// program and stdin of a request being prepared by the signer and the proof of a request
+    /// claimed by the signer.
+    fn is_writable(&self, artifact_id: &str, signer: [u8; 20]) -> bool {
+        self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .any(|job| match job.status {
+                ProofStatus::ProofPreparing => {
+                    job.requester == signer
+                        && (job.program_artifact_id == artifact_id
+                            || job.stdin_artifact_id == artifact_id)
+                }
+                ProofStatus::ProofClaimed => {
+                    job.claimer == Some(signer) && job.proof_artifact_id == artifact_id
+                }
+                _ => false,
+            })
+    }
+
+    fn is_known(&self, artifact_id: &str) -> bool {
+        self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .any(|job| job.artifact_ids().contains(&artifact_id))
+    }
+}
+
+#[twirp::async_trait::async_trait]
+impl NetworkService for ProverServer {
+    async fn create_proof(
+        &self,
+        _ctx: twirp::Context,
+        req: CreateProofRequest,
+    ) -> Result<CreateProofResponse, TwirpErrorResponse> {
+        let signer = NetworkAuth::recover_create_proof_signer(
+            &req.signature,
+            req.nonce,
+            req.deadline,
+            req.mode,
+        );
+        let mode = ProofMode::try_from(req.mode)
+            .map_err(|_| twirp::invalid_argument(format!("invalid proof mode {}", req.mode)))?;
+        check_proof_mode(mode).map_err(|err| twirp::invalid_argument(err.to_string()))?;
+        let now = unix_time();
+        if req.deadline < now {
+            return Err(twirp::invalid_argument("the deadline has already passed"));
+        }
+        let requester = self.use_nonce(signer, req.nonce).await?;
+
+        let job = ProofJob {
+            proof_id: new_id("proofrequest"),
+            mode,
+            status: ProofStatus::ProofPreparing,
+            requester,
+            deadline: req.deadline,
+            created_at: now,
+            program_artifact_id: new_id("artifact"),
+            stdin_artifact_id: new_id("artifact"),
+            proof_artifact_id: new_id("artifact"),
+            claimer: None,
+            claimed_at: None,
+            unclaim_reason: None,
+            unclaim_description: None,
+        };
+        let _writes = self.writes.lock().await;
+        self.save_job(&job).await.map_err(internal)?;
+        let res = CreateProofResponse {
+            proof_id: job.proof_id.clone(),
+            program_url: self.artifact_url(&job.program_artifact_id),
+            stdin_url: self.artifact_url(&job.stdin_artifact_id),
+        };
+        tracing::info!("created {}", job.proof_id);
+        self.jobs.lock().unwrap().insert(job.proof_id.clone(), job);
+        Ok(res)
+    }
+
+    async fn submit_proof(
+        &self,
+        _ctx: twirp::Context,
+        req: SubmitProofRequest,
+    ) -> Result<SubmitProofResponse, TwirpErrorResponse> {
+        let signer =
+            NetworkAuth::recover_submit_proof_signer(&req.signature, req.nonce, &req.proof_id);
+        let signer = self.use_nonce(signer, req.nonce).await?;
+        self.update_job(&req.proof_id, |job| {
+            if job.requester != signer {
+                return Err(twirp::permission_denied(
+                    "only the requester can submit a proof",
+                ));
+            }
+            if job.status != ProofStatus::ProofPreparing {
+                return Err(twirp::failed_precondition(
+                    "the proof was already submitted",
+                ));
+            }
+            if !self.artifact_path(&job.program_artifact_id).exists()
+                || !self.artifact_path(&job.stdin_artifact_id).exists()
+            {
+                return Err(twirp::failed_precondition(
+                    "the program and stdin must be uploaded before submitting",
+                ));
+            }
+            job.status = ProofStatus::ProofRequested;
+            Ok(())
+        })
+        .await?;
+        self.submitted.notify_one();
+        Ok(SubmitProofResponse {})
+    }
+
+    async fn claim_proof(
+        &self,
+        _ctx: twirp::Context,
+        req: ClaimProofRequest,
+    ) -> Result<ClaimProofResponse, TwirpErrorResponse> {
+        let signer =
+            NetworkAuth::recover_claim_proof_signer(&req.signature, req.nonce, &req.proof_id);
+        let signer = self.use_nonce(signer, req.nonce).await?;
+        let now = unix_time();
+        self.update_job(&req.proof_id, |job| {
+            if job.status != ProofStatus::ProofRequested {
+                return Err(twirp::failed_precondition("the proof is not requested"));
+            }
+            if job.deadline < now {
+                return Err(twirp::failed_precondition("the deadline has passed"));
+            }
+            job.status = ProofStatus::ProofClaimed;
+            job.claimer = Some(signer);
+            job.claimed_at = Some(now);
+            Ok(ClaimProofResponse {
+                program_artifact_id: job.program_artifact_id.clone(),
+                stdin_artifact_id: job.stdin_artifact_id.clone(),
+                proof_artifact_id: job.proof_artifact_id.clone(),
+            })
+        })
+        .await
+    }
+
+    async fn unclaim_proof(
+        &self,
+        _ctx: twirp::Context,
+        req: UnclaimProofRequest,
+    ) -> Result<UnclaimProofResponse, TwirpErrorResponse> {
+        let reason = UnclaimReason::try_from(req.reason).map_err(|_| {
+            twirp::invalid_argument(format!("invalid unclaim reason {}", req.reason))
+        })?;
+        let signer = NetworkAuth::recover_unclaim_proof_signer(
+            &req.signature,
+            req.nonce,
+            &req.proof_id,
+            reason,
+            &req.description,
+        );
+        let signer = self.use_nonce(signer, req.nonce).await?;
+        self.update_job(&req.proof_id, |job| {
+            if job.status != ProofStatus::ProofClaimed || job.claimer != Some(signer) {
+                return Err(twirp::failed_precondition(
+                    "the proof is not claimed by the caller",
+                ));
+            }
+            job.status = ProofStatus::ProofUnclaimed;
+            job.unclaim_reason = Some(reason);
+            job.unclaim_description = Some(req.description.clone());
+            Ok(())
+        })
+        .await?;
+        tracing::warn!("{} was unclaimed: {}", req.proof_id, req.description);
+        Ok(UnclaimProofResponse {})
+    }
+
+    async fn fulfill_proof(
+        &self,
+        _ctx: twirp::Context,
+        req: FulfillProofRequest,
+    ) -> Result<FulfillProofResponse, TwirpErrorResponse> {
+        let signer =
+            NetworkAuth::recover_fulfill_proof_signer(&req.signature, req.nonce, &req.proof_id);
+        let signer = self.use_nonce(signer, req.nonce).await?;
+        let now = unix_time();
+        self.update_job(&req.proof_id, |job| {
+            if job.status != ProofStatus::ProofClaimed || job.claimer != Some(signer) {
+                return Err(twirp::failed_precondition(
+                    "the proof is not claimed by the caller",
+                ));
+            }
+            if !self.artifact_path(&job.proof_artifact_id).exists() {
+                return Err(twirp::failed_precondition(
+                    "the proof must be uploaded before fulfilling",
+                ));
+            }
+            job.status = ProofStatus::ProofFulfilled;
+            Ok(FulfillProofResponse {
+                proving_seconds: now.saturating_sub(job.claimed_at.unwrap_or(now)),
+            })
+        })
+        .await
+    }
+
+    async fn relay_proof(
+        &self,
+        _ctx: twirp::Context,
+        _req: RelayProofRequest,
+    ) -> Result<RelayProofResponse, TwirpErrorResponse> {
+        Err(twirp::unimplemented("relaying proofs is not supported"))
+    }
+
+    async fn get_nonce(
+        &self,
+        _ctx: twirp::Context,
+        req: GetNonceRequest,
+    ) -> Result<GetNonceResponse, TwirpErrorResponse> {
+        let nonces = self.nonces.lock().unwrap();
+        Ok(GetNonceResponse {
+            nonce: nonces
+                .get(&hex::encode(&req.address))
+                .copied()
+                .unwrap_or_default(),
+        })
+    }
+
+    async fn get_proof_status(
+        &self,
+        _ctx: twirp::Context,
+        req: GetProofStatusRequest,
+    ) -> Result<GetProofStatusResponse, TwirpErrorResponse> {
+        let jobs = self.jobs.lock().unwrap();
+        let job = jobs
+            .get(&req.proof_id)
+            .ok_or_else(|| twirp::not_found(format!("proof {} not found", req.proof_id)))?;
+        Ok(GetProofStatusResponse {
+            status: job.status.into(),
+            proof_url: (job.status == ProofStatus::ProofFulfilled)
+                .then(|| self.artifact_url(&job.proof_artifact_id)),
+            unclaim_reason: job.unclaim_reason.map(Into::into),
+            unclaim_description: job.unclaim_description.clone(),
+        })
+    }
+
+    async fn get_proof_requests(
+        &self,
+        _ctx: twirp::Context,
+        req: GetProofRequestsRequest,
+    ) -> Result<GetProofRequestsResponse, TwirpErrorResponse> {
+        let jobs = self.jobs.lock().unwrap();
+        let mut matching = jobs
+            .values()
+            .filter(|job| i32::from(job.status) == req.status)
+            .collect::<Vec<_>>();
+        matching.sort_by_key(|job| std::cmp::Reverse(job.created_at));
+        Ok(GetProofRequestsResponse {
+            proofs: matching
+                .into_iter()
+                .take(MAX_PROOF_REQUESTS)
+                .map(|job| RequestedProof {
+                    proof_id: job.proof_id.clone(),
+                    mode: job.mode.into(),
+                })
+                .collect(),
+        })
+    }
+
+    async fn get_relay_status(
+        &self,
+        _ctx: twirp::Context,
+        _req: GetRelayStatusRequest,
+    ) -> Result<GetRelayStatusResponse, TwirpErrorResponse> {
+        Err(twirp::unimplemented("relaying proofs is not supported"))
+    }
+}
+
+async fn upload_artifact(
+    State(server): State<Arc<ProverServer>>,
+    Path(artifact_id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    let signer = headers
+        .get(ARTIFACT_SIGNATURE_HEADER)
+        .and_then(|value| hex::decode(value.as_bytes()).ok())
+        .and_then(|signature| {
+            NetworkAuth::recover_upload_artifact_signer(&signature, &artifact_id, &body).ok()
+        });
+    let Some(signer) = signer else {
+        return StatusCode::UNAUTHORIZED;
+    };
+    if !server.is_writable(&artifact_id, signer) {
+        return StatusCode::FORBIDDEN;
+    }
+    match tokio::fs::write(server.artifact_path(&artifact_id), &body).await {
+        Ok(()) => StatusCode::OK,
+        Err(err) => {
+            tracing::error!("failed to write artifact {}: {}", artifact_id, err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+async fn download_artifact(
+    State(server): State<Arc<ProverServer>>,
+    Path(artifact_id): Path<String>,
+) -> Result<Vec<u8>, StatusCode> {
+    if !server.is_known(&artifact_id) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    tokio::fs::read(server.artifact_path(&artifact_id))
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)
+}
+
+/// Decodes the program and stdin artifacts of a request.
+///
+/// The artifacts are encoded with bincode, as uploaded by [crate::client::NetworkClient].
+pub fn decode_artifacts(program: &[u8], stdin: &[u8]) -> Result<(Vec<u8>, SP1Stdin)> {
+    let elf = bincode::deserialize(program).context("failed to decode the program")?;
+    let stdin = bincode::deserialize(stdin).context("failed to decode the stdin")?;
+    Ok((elf, stdin))
+}
+
+/// Checks that requests in the given mode can be proven by a [LocalProver].
+pub fn check_proof_mode(mode: ProofMode) -> Result<()> {
+    match mode {
+        ProofMode::Core | ProofMode::Compressed | ProofMode::Groth16 => Ok(()),
+        ProofMode::Plonk => anyhow::bail!("PLONK proofs are not supported"),
+        ProofMode::Unspecified => anyhow::bail!("unspecified proof mode"),
+    }
+}
+
+/// Proves a program in the given mode, returning the proof artifact downloaded by
+/// [crate::NetworkProver].
+pub fn prove_with_mode(
+    prover: &dyn Prover,
+    elf: &[u8],
+    stdin: SP1Stdin,
+    mode: ProofMode,
+) -> Result<Vec<u8>> {
+    check_proof_mode(mode)?;
+    let (pk, _) = prover.setup(elf);
+    let proof = match mode {
+        ProofMode::Core => bincode::serialize(&prover.prove(&pk, stdin)?)?,
+        ProofMode::Compressed => bincode::serialize(&prover.prove_compressed(&pk, stdin)?)?,
+        ProofMode::Groth16 => bincode::serialize(&prover.prove_groth16(&pk, stdin)?)?,
+        ProofMode::Plonk | ProofMode::Unspecified => unreachable!("checked above"),
+    };
+    Ok(proof)
+}
+
+/// Returns a new unique identifier with the given prefix.
+fn new_id(prefix: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = Sha256::new();
+    hasher.update(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Invalid time")
+            .as_nanos()
+            .to_le_bytes(),
+    );
+    hasher.update(std::process::id().to_le_bytes());
+    hasher.update(COUNTER.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+    format!("{}_{}", prefix, hex::encode(&hasher.finalize()[..16]))
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Invalid time")
+        .as_secs()
+}
+
+fn internal(err: impl std::fmt::Display) -> TwirpErrorResponse {
+    twirp::internal(err.to_string())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{client::NetworkClient, utils, SP1Proof};
+
+    const PRIVATE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const PROVER_KEY: &str = "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    /// Serves a [ProverServer] with the given number of local provers on a free local port.
+    ///
+    /// Returns the server, its URL and the directory storing its jobs, which is removed when
+    /// dropped.
+    pub(crate) async fn spawn_server(
+        local_provers: usize,
+    ) -> (Arc<ProverServer>, String, tempfile::TempDir) {
+        utils::setup_logger();
+        let dir = tempfile::tempdir().unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let opts = ServerOpts {
+            dir: dir.path().to_path_buf(),
+            public_url: url.clone(),
+            local_provers,
+            claim_timeout: DEFAULT_CLAIM_TIMEOUT,
+        };
+        let server = Arc::new(ProverServer::new(LocalProver::new(), opts).unwrap());
+        tokio::spawn(server.clone().serve_with_listener(listener));
+        (server, url, dir)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_server_proves_request() {
+        let (server, url, _dir) = spawn_server(1).await;
+
+        let client = NetworkClient::with_rpc_url(PRIVATE_KEY, &url);
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof_id = client
+            .create_proof(elf, &stdin, ProofMode::Core)
+            .await
+            .unwrap();
+
+        let proof = loop {
+            let (status, proof) = client
+                .get_proof_status::<SP1Proof>(&proof_id)
+                .await
+                .unwrap();
+            assert_ne!(status.status(), ProofStatus::ProofUnclaimed);
+            if let Some(proof) = proof {
+                break proof;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        };
+        let (_, vk) = server.prover.setup(elf);
+        server.prover.verify(&proof, &vk).unwrap();
+        assert_eq!(client.get_nonce().await.unwrap(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_server_rejects_unsupported_mode() {
+        let (server, url, _dir) = spawn_server(0).await;
+
+        let client = NetworkClient::with_rpc_url(PRIVATE_KEY, &url);
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let result = client
+            .create_proof(elf, &SP1Stdin::new(), ProofMode::Plonk)
+            .await;
+        assert!(result.is_err());
+        assert!(server.jobs().is_empty());
+        assert_eq!(client.get_nonce().await.unwrap(), 0);
+    }
+
+    /// Creates a request with `requester` and claims it with `prover`, returning the request and
+    /// the claim.
+    async fn create_claimed_proof(
+        requester: &NetworkClient,
+        prover: &NetworkClient,
+    ) -> (String, ClaimProofResponse) {
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let proof_id = requester
+            .create_proof(elf, &SP1Stdin::new(), ProofMode::Core)
+            .await
+            .unwrap();
+        let claim = prover.claim_proof(&proof_id).await.unwrap();
+        (proof_id, claim)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_server_rejects_unauthorized_uploads() {
+        let (_server, url, _dir) = spawn_server(0).await;
+        let requester = NetworkClient::with_rpc_url(PRIVATE_KEY, &url);
+        let prover = NetworkClient::with_rpc_url(PROVER_KEY, &url);
+        let (_, claim) = create_claimed_proof(&requester, &prover).await;
+
+        // Unsigned uploads and uploads signed by anyone but the claimer are rejected.
+        let status = reqwest::Client::new()
+            .put(prover.artifact_url(&claim.proof_artifact_id).unwrap())
+            .body(vec![1, 2, 3])
+            .send()
+            .await
+            .unwrap()
+            .status();
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(requester
+            .upload_artifact(&claim.proof_artifact_id, vec![1, 2, 3])
+            .await
+            .is_err());
+        assert!(prover
+            .upload_artifact(&claim.program_artifact_id, vec![1, 2, 3])
+            .await
+            .is_err());
+
+        prover
+            .upload_artifact(&claim.proof_artifact_id, vec![1, 2, 3])
+            .await
+            .unwrap();
+        assert_eq!(
+            requester
+                .download_artifact(&claim.proof_artifact_id)
+                .await
+                .unwrap(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_server_releases_expired_claims() {
+        let (server, url, _dir) = spawn_server(0).await;
+        let requester = NetworkClient::with_rpc_url(PRIVATE_KEY, &url);
+        let prover = NetworkClient::with_rpc_url(PROVER_KEY, &url);
+        let (proof_id, _) = create_claimed_proof(&requester, &prover).await;
+
+        // A claim within the timeout is kept.
+        server.release_expired_claims().await;
+        let job = server.jobs.lock().unwrap()[&proof_id].clone();
+        assert_eq!(job.status, ProofStatus::ProofClaimed);
+
+        server
+            .update_job(&proof_id, |job| {
+                job.claimed_at = Some(0);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        server.release_expired_claims().await;
+        let job = server.jobs.lock().unwrap()[&proof_id].clone();
+        assert_eq!(job.status, ProofStatus::ProofRequested);
+        assert_eq!(job.claimer, None);
+
+        // The expired claimer can no longer upload the proof or fulfill the request.
+        assert!(prover
+            .upload_artifact(&job.proof_artifact_id, vec![1, 2, 3])
+            .await
+            .is_err());
+        assert!(prover.fulfill_proof(&proof_id).await.is_err());
+    }
+}
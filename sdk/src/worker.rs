@@ -0,0 +1,292 @@
+//! A prover-side worker fulfilling the proof requests of a prover network.
+//!
+//! [ProverWorker] polls the network for submitted requests, claims them, proves them with a
+//! [LocalProver] in the requested [ProofMode] and fulfills them once the proof is uploaded. Requests
+//! that cannot be proven are unclaimed with a reason, so that the requester is not left waiting.
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use anyhow::{Context, Result};
+use sp1_prover::SP1Prover;
+use tokio::{
+    sync::{Mutex, Semaphore},
+    time::sleep,
+};
+
+use crate::{
+    client::NetworkClient,
+    proto::network::{ClaimProofResponse, ProofMode, ProofStatus, RequestedProof, UnclaimReason},
+    server::{check_proof_mode, decode_artifacts, prove_with_mode},
+    LocalProver,
+};
+
+/// Options of a [ProverWorker].
+#[derive(Debug, Clone, Copy)]
+pub struct WorkerOpts {
+    /// The number of requests proven concurrently.
+    pub concurrency: usize,
+    /// The number of times a failed step, such as downloading an artifact or generating the proof,
+    /// is retried before the request is unclaimed.
+    pub max_retries: usize,
+    /// The delay between two polls for requests when none could be claimed.
+    pub poll_interval: Duration,
+    /// The delay before retrying a failed step.
+    pub retry_delay: Duration,
+}
+
+impl Default for WorkerOpts {
+    fn default() -> Self {
+        Self {
+            concurrency: 1,
+            max_retries: 2,
+            poll_interval: Duration::from_secs(5),
+            retry_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+/// The reason a claimed request could not be fulfilled.
+struct WorkerFailure {
+    reason: UnclaimReason,
+    error: anyhow::Error,
+}
+
+impl WorkerFailure {
+    fn invalid(error: anyhow::Error) -> Self {
+        Self {
+            reason: UnclaimReason::Invalid,
+            error,
+        }
+    }
+
+    fn abandoned(error: anyhow::Error) -> Self {
+        Self {
+            reason: UnclaimReason::Abandoned,
+            error,
+        }
+    }
+}
+
+/// A worker claiming and fulfilling the proof requests of a prover network.
+pub struct ProverWorker {
+    client: NetworkClient,
+    prover: Arc<LocalProver>,
+    opts: WorkerOpts,
+    /// Serializes the signed requests, which must each use the next nonce of the account.
+    signing: Mutex<()>,
+}
+
+impl ProverWorker {
+    /// Creates a new [ProverWorker] authenticated with the client's key.
+    pub fn new(client: NetworkClient, prover: LocalProver, opts: WorkerOpts) -> Self {
+        Self {
+            client,
+            prover: Arc::new(prover),
+            opts,
+            signing: Mutex::new(()),
+        }
+    }
+
+    /// Claims and proves requests until an unrecoverable error occurs.
+    pub async fn run(self: Arc<Self>) -> Result<()> {
+        let permits = Arc::new(Semaphore::new(self.opts.concurrency.max(1)));
+        loop {
+            let permit = permits.clone().acquire_owned().await?;
+            match self.claim_next().await {
+                Ok(Some((request, claim))) => {
+                    let worker = self.clone();
+                    tokio::spawn(async move {
+                        worker.process(request, claim).await;
+                        drop(permit);
+                    });
+                }
+                Ok(None) => {
+                    drop(permit);
+                    sleep(self.opts.poll_interval).await;
+                }
+                Err(err) => {
+                    log::warn!("Failed to poll proof requests: {:#}", err);
+                    drop(permit);
+                    sleep(self.opts.poll_interval).await;
+                }
+            }
+        }
+    }
+
+    /// Claims the first requested proof that is not claimed by another prover in the meantime.
+    async fn claim_next(&self) -> Result<Option<(RequestedProof, ClaimProofResponse)>> {
+        let requests = self
+            .client
+            .get_proof_requests(ProofStatus::ProofRequested)
+            .await?;
+        for request in requests.proofs {
+            let claim = {
+                let _guard = self.signing.lock().await;
+                self.client.claim_proof(&request.proof_id).await
+            };
+            match claim {
+                Ok(claim) => {
+                    log::info!("Claimed {}", request.proof_id);
+                    return Ok(Some((request, claim)));
+                }
+                Err(err) => log::debug!("Failed to claim {}: {:#}", request.proof_id, err),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Proves and fulfills a claimed request, unclaiming it if it fails.
+    async fn process(&self, request: RequestedProof, claim: ClaimProofResponse) {
+        let proof_id = request.proof_id.as_str();
+        let result = match self.prove(request.mode(), &claim).await {
+            Ok(()) => self
+                .retry("fulfill the proof", || async move {
+                    let _guard = self.signing.lock().await;
+                    self.client.fulfill_proof(proof_id).await
+                })
+                .await
+                .map(|res| log::info!("Fulfilled {} in {}s", proof_id, res.proving_seconds))
+                .map_err(WorkerFailure::abandoned),
+            Err(failure) => Err(failure),
+        };
+
+        if let Err(failure) = result {
+            log::error!("Failed to prove {}: {:#}", proof_id, failure.error);
+            let unclaim = {
+                let _guard = self.signing.lock().await;
+                self.client
+                    .unclaim_proof(
+                        proof_id.to_string(),
+                        failure.reason,
+                        format!("{:#}", failure.error),
+                    )
+                    .await
+            };
+            if let Err(err) = unclaim {
+                log::error!("Failed to unclaim {}: {:#}", proof_id, err);
+            }
+        }
+    }
+
+    /// Downloads the artifacts of a claimed request, proves it and uploads the proof.
+    async fn prove(
+        &self,
+        mode: ProofMode,
+        claim: &ClaimProofResponse,
+    ) -> Result<(), WorkerFailure> {
+        // Requests in a mode the local prover does not support can never be proven, so they are
+        // unclaimed as invalid before downloading anything.
+        check_proof_mode(mode).map_err(WorkerFailure::invalid)?;
+
+        let program = self
+            .retry("download the program", || {
+                self.client.download_artifact(&claim.program_artifact_id)
+            })
+            .await
+            .map_err(WorkerFailure::abandoned)?;
+        let stdin = self
+            .retry("download the stdin", || {
+                self.client.download_artifact(&claim.stdin_artifact_id)
+            })
+            .await
+            .map_err(WorkerFailure::abandoned)?;
+
+        // Requests whose artifacts cannot be decoded or whose program fails to execute can never be
+        // proven, so they are unclaimed as invalid without retrying.
+        let (elf, stdin) = decode_artifacts(&program, &stdin).map_err(WorkerFailure::invalid)?;
+        SP1Prover::execute(&elf, &stdin)
+            .context("Failed to execute program")
+            .map_err(WorkerFailure::invalid)?;
+
+        let elf = Arc::new(elf);
+        let proof = self
+            .retry("generate the proof", || {
+                let prover = self.prover.clone();
+                let elf = elf.clone();
+                let stdin = stdin.clone();
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        prove_with_mode(prover.as_ref(), &elf, stdin, mode)
+                    })
+                    .await?
+                }
+            })
+            .await
+            .map_err(WorkerFailure::abandoned)?;
+
+        self.retry("upload the proof", || {
+            self.client
+                .upload_artifact(&claim.proof_artifact_id, proof.clone())
+        })
+        .await
+        .map_err(WorkerFailure::abandoned)
+    }
+
+    /// Runs `f` until it succeeds, at most `max_retries + 1` times.
+    async fn retry<T, F, Fut>(&self, step: &str, mut f: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match f().await {
+                Ok(value) => return Ok(value),
+                Err(err) if attempt < self.opts.max_retries => {
+                    attempt += 1;
+                    log::warn!(
+                        "Failed to {} (attempt {}/{}): {:#}",
+                        step,
+                        attempt,
+                        self.opts.max_retries + 1,
+                        err
+                    );
+                    sleep(self.opts.retry_delay).await;
+                }
+                Err(err) => return Err(err.context(format!("Failed to {}", step))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{server::tests::spawn_server, SP1Stdin};
+
+    const REQUESTER_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const PROVER_KEY: &str = "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_worker_unclaims_invalid_request() {
+        let (_, url, _dir) = spawn_server(0).await;
+
+        let worker = ProverWorker::new(
+            NetworkClient::with_rpc_url(PROVER_KEY, &url),
+            LocalProver::new(),
+            WorkerOpts {
+                poll_interval: Duration::from_millis(100),
+                ..Default::default()
+            },
+        );
+        tokio::spawn(Arc::new(worker).run());
+
+        let client = NetworkClient::with_rpc_url(REQUESTER_KEY, &url);
+        let elf = include_bytes!("../../tests/panic/elf/riscv32im-succinct-zkvm-elf");
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof_id = client
+            .create_proof(elf, &stdin, ProofMode::Core)
+            .await
+            .unwrap();
+
+        let status = loop {
+            let (status, _) = client.get_proof_status::<()>(&proof_id).await.unwrap();
+            if status.status() == ProofStatus::ProofUnclaimed {
+                break status;
+            }
+            sleep(Duration::from_millis(100)).await;
+        };
+        assert_eq!(status.unclaim_reason(), UnclaimReason::Invalid);
+    }
+}
@@ -9,6 +9,93 @@ use std::io::Write;
 const FD_HINT: u32 = 4;
 pub const FD_PUBLIC_VALUES: u32 = 3;
 
+/// The encoding of a value read from the input or committed to the public values, matching
+/// `sp1_core::io::CodecFormat` on the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodecFormat {
+    Bincode,
+    Borsh,
+    Json,
+    SolidityAbi,
+}
+
+/// An encoding of values of type `T`, matching `sp1_core::io::Codec` on the host.
+///
+/// [Bincode] is always available, the other codecs are enabled by the `borsh`, `json` and `abi`
+/// features.
+pub trait Codec<T> {
+    const FORMAT: CodecFormat;
+
+    fn encode(value: &T) -> Vec<u8>;
+
+    fn decode(bytes: &[u8]) -> T;
+}
+
+pub struct Bincode;
+
+impl<T: Serialize + DeserializeOwned> Codec<T> for Bincode {
+    const FORMAT: CodecFormat = CodecFormat::Bincode;
+
+    fn encode(value: &T) -> Vec<u8> {
+        bincode::serialize(value).expect("serialization failed")
+    }
+
+    fn decode(bytes: &[u8]) -> T {
+        bincode::deserialize(bytes).expect("deserialization failed")
+    }
+}
+
+#[cfg(feature = "borsh")]
+pub struct Borsh;
+
+#[cfg(feature = "borsh")]
+impl<T: borsh::BorshSerialize + borsh::BorshDeserialize> Codec<T> for Borsh {
+    const FORMAT: CodecFormat = CodecFormat::Borsh;
+
+    fn encode(value: &T) -> Vec<u8> {
+        borsh::to_vec(value).expect("serialization failed")
+    }
+
+    fn decode(bytes: &[u8]) -> T {
+        borsh::from_slice(bytes).expect("deserialization failed")
+    }
+}
+
+/// The JSON encoding, with each value followed by a newline.
+#[cfg(feature = "json")]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl<T: Serialize + DeserializeOwned> Codec<T> for Json {
+    const FORMAT: CodecFormat = CodecFormat::Json;
+
+    fn encode(value: &T) -> Vec<u8> {
+        let mut bytes = serde_json::to_vec(value).expect("serialization failed");
+        bytes.push(b'\n');
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> T {
+        serde_json::from_slice(bytes).expect("deserialization failed")
+    }
+}
+
+#[cfg(feature = "abi")]
+pub struct SolidityAbi;
+
+#[cfg(feature = "abi")]
+impl<T: alloy_sol_types::SolValue> Codec<T> for SolidityAbi {
+    const FORMAT: CodecFormat = CodecFormat::SolidityAbi;
+
+    fn encode(value: &T) -> Vec<u8> {
+        value.abi_encode()
+    }
+
+    fn decode(bytes: &[u8]) -> T {
+        T::abi_decode(bytes, true).expect("deserialization failed")
+    }
+}
+
 pub struct SyscallWriter {
     fd: u32,
 }
@@ -57,6 +144,11 @@ pub fn read<T: DeserializeOwned>() -> T {
     bincode::deserialize(&vec).expect("deserialization failed")
 }
 
+/// Reads a value written with `SP1Stdin::write_with` and the same codec.
+pub fn read_with<C: Codec<T>, T>() -> T {
+    C::decode(&read_vec())
+}
+
 pub fn commit<T: Serialize>(value: &T) {
     let writer = SyscallWriter {
         fd: FD_PUBLIC_VALUES,
@@ -71,6 +163,11 @@ pub fn commit_slice(buf: &[u8]) {
     my_writer.write_all(buf).unwrap();
 }
 
+/// Commits a value encoded with the codec `C`, to be read with `SP1PublicValues::read_with`.
+pub fn commit_with<C: Codec<T>, T>(value: &T) {
+    commit_slice(&C::encode(value));
+}
+
 pub fn hint<T: Serialize>(value: &T) {
     let writer = SyscallWriter { fd: FD_HINT };
     bincode::serialize_into(writer, value).expect("serialization failed");